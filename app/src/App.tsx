@@ -342,7 +342,7 @@ const App: React.FC = () => {
   const myPowerupScore = useMemo(() => {
     if (!myPlayerId || !displayGame?.players) return 0;
     const p = displayGame.players.find((x) => x.id === myPlayerId);
-    return p ? Number(p.powerupScore ?? 0) : 0;
+    return p?.powerup ? 1 : 0;
   }, [myPlayerId, displayGame?.players]);
 
   const boardSideLen = useMemo(() => {
@@ -360,10 +360,12 @@ const App: React.FC = () => {
   }, [displayGame?.maxPlayers, selectedMode]);
 
   const registrationFeeLamports = useMemo(() => {
-    const fromDisplay = Number(displayGame?.registrationFeeLamports ?? 0);
+    const fromDisplay = Number(
+      displayGame?.nextRegistrationFeeLamports ?? displayGame?.registrationFeeLamports ?? 0
+    );
     if (fromDisplay > 0) return fromDisplay;
     return selectedMode?.registrationFeeLamports ?? REGISTRATION_FEE_LAMPORTS;
-  }, [displayGame?.registrationFeeLamports, selectedMode]);
+  }, [displayGame?.nextRegistrationFeeLamports, displayGame?.registrationFeeLamports, selectedMode]);
 
   const backToLanding = useCallback(() => {
     setShowLanding(true);
@@ -668,7 +670,7 @@ const App: React.FC = () => {
     }
 
     const nextMap = new Map<number, number>();
-    for (const p of players) nextMap.set(p.id, Number(p.powerupScore ?? 0));
+    for (const p of players) nextMap.set(p.id, p.powerup ? 1 : 0);
 
     const prevMap = prevPowerupScoresRef.current;
     prevPowerupScoresRef.current = nextMap;
@@ -700,7 +702,7 @@ const App: React.FC = () => {
     }
 
     const nextMap = new Map<number, number>();
-    for (const p of players) nextMap.set(p.id, Number(p.powerupScore ?? 0));
+    for (const p of players) nextMap.set(p.id, p.powerup ? 1 : 0);
 
     const prevMap = prevPowerupScoresForLaserRef.current;
     prevPowerupScoresForLaserRef.current = nextMap;
@@ -1604,7 +1606,7 @@ const App: React.FC = () => {
                     style={{ color: PLAYER_COLORS[i % PLAYER_COLORS.length] }}
                   >
                     {PLAYER_LABELS[i] ?? `P${p.id}`}
-                    {Number(p.powerupScore ?? 0) > 0 && (
+                    {p.powerup && (
                       <span className="player-power-badge" title="Power charged!"> ⚡</span>
                     )}
                   </span>
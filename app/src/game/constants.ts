@@ -28,6 +28,9 @@ export const EMPTY = 0;
 export const KING_MARK = 255;
 export const POWERUP_MARK = 254;
 export const BOMB_MARK = 253;
+export const WALL_MARK = 252;
+export const BOMB_KIT_MARK = 251;
+export const JACKPOT_MARK = 250;
 
 export const REGISTRATION_FEE_LAMPORTS = 1_000_000; // 0.001 SOL
 
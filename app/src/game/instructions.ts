@@ -5,15 +5,32 @@ import {
   REGISTER_PLAYER_DISCRIMINATOR,
   TREASURY_PUBKEY,
 } from "./constants";
+import { getReferralPDA, getRegistryPDA } from "./pda";
 
 export function buildRegisterPlayerIx(
   payer: PublicKey,
   boardPDA: PublicKey,
-  gameId: number
+  gameId: number,
+  seedCommitment: Uint8Array = new Uint8Array(32),
+  allowlistProof: Uint8Array[] = [],
+  gateTokenAccount: PublicKey = PROGRAM_ID,
+  referrer: PublicKey | null = null,
+  priceUpdate: PublicKey = PROGRAM_ID,
+  jackpotVault: PublicKey = PROGRAM_ID,
+  wagerEscrow: PublicKey = PROGRAM_ID,
+  sponsorVault: PublicKey = PROGRAM_ID
 ): TransactionInstruction {
-  const data = Buffer.alloc(8 + 8);
+  const referrerOffset = 52 + 32 * allowlistProof.length;
+  const data = Buffer.alloc(referrerOffset + (referrer ? 33 : 1));
   Buffer.from(REGISTER_PLAYER_DISCRIMINATOR).copy(data, 0);
   data.writeBigUInt64LE(BigInt(gameId), 8);
+  Buffer.from(seedCommitment).copy(data, 16);
+  data.writeUInt32LE(allowlistProof.length, 48);
+  allowlistProof.forEach((node, i) => Buffer.from(node).copy(data, 52 + 32 * i));
+  if (referrer) {
+    data.writeUInt8(1, referrerOffset);
+    referrer.toBuffer().copy(data, referrerOffset + 1);
+  }
 
   return new TransactionInstruction({
     keys: [
@@ -21,6 +38,17 @@ export function buildRegisterPlayerIx(
       { pubkey: boardPDA, isSigner: false, isWritable: true },
       { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
       { pubkey: TREASURY_PUBKEY, isSigner: false, isWritable: true },
+      { pubkey: getRegistryPDA(), isSigner: false, isWritable: true },
+      { pubkey: gateTokenAccount, isSigner: false, isWritable: false },
+      {
+        pubkey: referrer ? getReferralPDA(referrer) : PROGRAM_ID,
+        isSigner: false,
+        isWritable: Boolean(referrer),
+      },
+      { pubkey: priceUpdate, isSigner: false, isWritable: false },
+      { pubkey: jackpotVault, isSigner: false, isWritable: !jackpotVault.equals(PROGRAM_ID) },
+      { pubkey: wagerEscrow, isSigner: false, isWritable: !wagerEscrow.equals(PROGRAM_ID) },
+      { pubkey: sponsorVault, isSigner: false, isWritable: false },
     ],
     programId: PROGRAM_ID,
     data,
@@ -11,3 +11,16 @@ export function getBoardPDA(gameId: number): PublicKey {
   return pda;
 }
 
+
+export function getRegistryPDA(): PublicKey {
+  const [pda] = PublicKey.findProgramAddressSync([Buffer.from("registry")], PROGRAM_ID);
+  return pda;
+}
+
+export function getReferralPDA(referrer: PublicKey): PublicKey {
+  const [pda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("referral"), referrer.toBuffer()],
+    PROGRAM_ID
+  );
+  return pda;
+}
@@ -3,7 +3,11 @@ export interface PlayerInfo {
   player: string;
   score: string;
   currentPosition: number;
-  powerupScore: string;
+  powerup: string | null;
+  movesMade: number;
+  kingsCaptured: number;
+  bombsHit: number;
+  powerupsUsed: number;
 }
 
 export interface TxTrace {
@@ -22,6 +26,7 @@ export interface CompletedGameSnapshot {
   boardSideLen?: number;
   maxPlayers?: number;
   registrationFeeLamports?: string;
+  nextRegistrationFeeLamports?: string;
   lamportsPerScore?: string;
   playersCount?: number;
   isActive?: boolean;
@@ -29,7 +34,7 @@ export interface CompletedGameSnapshot {
   secondsRemaining?: number;
   players?: PlayerInfo[];
   board?: number[][];
-  boardLegend?: { 0: string; "1-max": string; 253: string; 254: string; 255: string };
+  boardLegend?: { 0: string; "1-max": string; 250: string; 251: string; 252: string; 253: string; 254: string; 255: string };
   completedAtIso: string;
   txTrace: TxTrace;
 }
@@ -42,6 +47,7 @@ export interface GameStatus {
   boardSideLen?: number;
   maxPlayers?: number;
   registrationFeeLamports?: string;
+  nextRegistrationFeeLamports?: string;
   lamportsPerScore?: string;
   playersCount?: number;
   isActive?: boolean;
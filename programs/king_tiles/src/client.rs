@@ -0,0 +1,343 @@
+//! Off-chain helpers for integrators: PDA derivation, instruction builders with the full
+//! account lists (VRF, delegation, and magic-program accounts included), and `Board` decoding.
+
+use anchor_lang::prelude::Pubkey;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::sysvar::slot_hashes;
+use anchor_lang::{system_program, AccountDeserialize, InstructionData, ToAccountMetas};
+use ephemeral_rollups_sdk::consts::{MAGIC_CONTEXT_ID, MAGIC_PROGRAM_ID};
+use ephemeral_rollups_sdk::pda::{
+    DELEGATE_BUFFER_TAG, DELEGATION_METADATA_TAG, DELEGATION_RECORD_TAG,
+};
+use ephemeral_vrf_sdk::consts::{DEFAULT_EPHEMERAL_QUEUE, IDENTITY, VRF_PROGRAM_ID};
+
+use crate::constants::{ASSOCIATED_TOKEN_PROGRAM_ID, SPL_TOKEN_PROGRAM_ID, TREASURY};
+use crate::state::{Board, Direction, GameOptions, GameSessionArgs};
+use crate::trophy::associated_token_address;
+use crate::{accounts, instruction, ID};
+
+pub fn board_pda(game_id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"board", TREASURY.as_ref(), &game_id.to_le_bytes()], &ID).0
+}
+
+pub fn registry_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"registry"], &ID).0
+}
+
+pub fn treasury_vault_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"treasury_vault"], &ID).0
+}
+
+pub fn config_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"config"], &ID).0
+}
+
+/// Must hold the mint authority of the configured XP mint.
+pub fn xp_authority_pda() -> Pubkey {
+    Pubkey::find_program_address(&[b"xp_authority"], &ID).0
+}
+
+/// One per round: `game_end_timestamp` is the finished round's, as stored on the board.
+pub fn game_summary_pda(game_id: u64, game_end_timestamp: i64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"game_summary",
+            &game_id.to_le_bytes(),
+            &game_end_timestamp.to_le_bytes(),
+        ],
+        &ID,
+    )
+    .0
+}
+
+/// Exists only once `refund_stranded_board` has refunded that round.
+pub fn stranded_refund_pda(game_id: u64, round: u32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"stranded_refund",
+            &game_id.to_le_bytes(),
+            &round.to_le_bytes(),
+        ],
+        &ID,
+    )
+    .0
+}
+
+fn delegation_pda(tag: &[u8], board: &Pubkey, program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[tag, board.as_ref()], program_id).0
+}
+
+fn program_identity_pda() -> Pubkey {
+    Pubkey::find_program_address(&[IDENTITY], &ID).0
+}
+
+/// Decodes a fetched board account, checking its discriminator.
+pub fn decode_board(data: &[u8]) -> anchor_lang::Result<Board> {
+    Board::try_deserialize(&mut &data[..])
+}
+
+fn build(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Treasury-signed; creates the board and its registry listing.
+pub fn start_game_session(
+    game_id: u64,
+    board_side_len: u8,
+    max_players: u8,
+    registration_fee_lamports: u64,
+    lamports_per_score: u64,
+    walls: Vec<u8>,
+    options: GameOptions,
+) -> Instruction {
+    build(
+        accounts::StartGameSession {
+            treasury_signer: TREASURY,
+            board_account: board_pda(game_id),
+            system_program: system_program::ID,
+            map_template: None,
+            registry: registry_pda(),
+            treasury_vault: treasury_vault_pda(),
+        },
+        instruction::StartGameSession {
+            game_id,
+            args: GameSessionArgs {
+                board_side_len,
+                max_players,
+                registration_fee_lamports,
+                lamports_per_score,
+                walls,
+                options,
+            },
+        },
+    )
+}
+
+/// Seats `payer` in an open lobby without any of the optional gate, referral, oracle, jackpot,
+/// wager, sponsor, or profile accounts.
+pub fn register_player(
+    payer: Pubkey,
+    game_id: u64,
+    seed_commitment: [u8; 32],
+    allowlist_proof: Vec<[u8; 32]>,
+) -> Instruction {
+    build(
+        accounts::RegisterPlayer {
+            payer,
+            board_account: board_pda(game_id),
+            system_program: system_program::ID,
+            treasury: TREASURY,
+            treasury_vault: treasury_vault_pda(),
+            registry: registry_pda(),
+            gate_token_account: None,
+            referral_account: None,
+            price_update: None,
+            jackpot_vault: None,
+            wager_escrow: None,
+            sponsor_vault: None,
+            profile: None,
+        },
+        instruction::RegisterPlayer {
+            game_id,
+            seed_commitment,
+            allowlist_proof,
+            referrer: None,
+        },
+    )
+}
+
+/// Treasury-signed; `validator` pins the ER validator, `None` lets any validator take it.
+pub fn delegate_board(game_id: u64, validator: Option<Pubkey>) -> Instruction {
+    let board = board_pda(game_id);
+    let delegation_program = ephemeral_rollups_sdk::id();
+    let mut ix = build(
+        accounts::DelegateBoard {
+            treasury_signer: TREASURY,
+            board_account: board,
+            system_program: system_program::ID,
+            buffer_pda: delegation_pda(DELEGATE_BUFFER_TAG, &board, &ID),
+            delegation_record_pda: delegation_pda(
+                DELEGATION_RECORD_TAG,
+                &board,
+                &delegation_program,
+            ),
+            delegation_metadata_pda: delegation_pda(
+                DELEGATION_METADATA_TAG,
+                &board,
+                &delegation_program,
+            ),
+            pda: board,
+            owner_program: ID,
+            delegation_program,
+        },
+        instruction::DelegateBoard { game_id },
+    );
+    if let Some(validator) = validator {
+        ix.accounts
+            .push(AccountMeta::new_readonly(validator, false));
+    }
+    ix
+}
+
+/// Send to the ER once the board is delegated.
+pub fn make_move(payer: Pubkey, game_id: u64, player_id: u8, direction: Direction) -> Instruction {
+    build(
+        accounts::MakeMove {
+            treasury: TREASURY,
+            payer,
+            board_account: board_pda(game_id),
+        },
+        instruction::MakeMove {
+            game_id,
+            player_id,
+            direction,
+        },
+    )
+}
+
+/// Send to the ER; `authority` is the owning player or the treasury.
+pub fn use_power(
+    authority: Pubkey,
+    game_id: u64,
+    player_id: u8,
+    direction: Direction,
+) -> Instruction {
+    build(
+        accounts::UsePower {
+            treasury: TREASURY,
+            authority,
+            board_account: board_pda(game_id),
+        },
+        instruction::UsePower {
+            game_id,
+            player_id,
+            direction,
+        },
+    )
+}
+
+/// Permissionless king-move crank on the ER's VRF queue; `payer` collects the crank tip.
+pub fn request_randomness_for_king_move(
+    payer: Pubkey,
+    game_id: u64,
+    client_seed: u8,
+) -> Instruction {
+    build(
+        accounts::RequestRandomnessForKingMove {
+            payer,
+            treasury: TREASURY,
+            board_account: board_pda(game_id),
+            oracle_queue: DEFAULT_EPHEMERAL_QUEUE,
+            program_identity: program_identity_pda(),
+            vrf_program: VRF_PROGRAM_ID,
+            slot_hashes: slot_hashes::ID,
+            system_program: system_program::ID,
+        },
+        instruction::RequestRandomnessForKingMove {
+            client_seed,
+            game_id,
+        },
+    )
+}
+
+/// Treasury-signed combined king/powerup/bomb VRF request on the ER's queue.
+pub fn request_randomness_for_tick(game_id: u64, client_seed: u8) -> Instruction {
+    build(
+        accounts::RequestRandomnessForTick {
+            treasury_signer: TREASURY,
+            board_account: board_pda(game_id),
+            oracle_queue: DEFAULT_EPHEMERAL_QUEUE,
+            program_identity: program_identity_pda(),
+            vrf_program: VRF_PROGRAM_ID,
+            slot_hashes: slot_hashes::ID,
+            system_program: system_program::ID,
+        },
+        instruction::RequestRandomnessForTick {
+            client_seed,
+            game_id,
+        },
+    )
+}
+
+/// Treasury-signed, on the ER; commits and undelegates the board.
+pub fn end_game_session(game_id: u64) -> Instruction {
+    build(
+        accounts::EndGameSession {
+            treasury: TREASURY,
+            board_account: board_pda(game_id),
+            system_program: system_program::ID,
+            magic_program: MAGIC_PROGRAM_ID,
+            magic_context: MAGIC_CONTEXT_ID,
+        },
+        instruction::EndGameSession { game_id },
+    )
+}
+
+/// Treasury-signed, on the base layer; `players` is the page of players to pay, in join order
+/// from `board.next_payout_index`, and `round` is `board.round`. Free-play, jackpot, and wager
+/// games need their vault accounts set on the returned instruction's optional slots.
+pub fn distribute_rewards(game_id: u64, round: u32, players: &[Pubkey]) -> Instruction {
+    let mut ix = build(
+        accounts::DistributeRewards {
+            treasury: TREASURY,
+            board_account: board_pda(game_id),
+            stranded_refund: stranded_refund_pda(game_id, round),
+            system_program: system_program::ID,
+            sponsor_vault: None,
+            jackpot_vault: None,
+            wager_escrow: None,
+            treasury_vault: treasury_vault_pda(),
+        },
+        instruction::DistributeRewards { game_id },
+    );
+    ix.accounts.extend(
+        players
+            .iter()
+            .map(|player| AccountMeta::new(*player, false)),
+    );
+    ix
+}
+
+/// Treasury-signed, on the base layer once `distribute_rewards` has paid its last page;
+/// `game_end_timestamp` is the board's.
+pub fn record_game_summary(game_id: u64, game_end_timestamp: i64) -> Instruction {
+    build(
+        accounts::RecordGameSummary {
+            treasury: TREASURY,
+            board_account: board_pda(game_id),
+            game_summary: game_summary_pda(game_id, game_end_timestamp),
+            system_program: system_program::ID,
+        },
+        instruction::RecordGameSummary { game_id },
+    )
+}
+
+/// Treasury-signed, on the base layer after `distribute_rewards`; `players` is every seated
+/// player in join order.
+pub fn mint_xp(game_id: u64, xp_mint: Pubkey, players: &[Pubkey]) -> Instruction {
+    let mut ix = build(
+        accounts::MintXp {
+            treasury: TREASURY,
+            board_account: board_pda(game_id),
+            config: config_pda(),
+            xp_mint,
+            xp_authority: xp_authority_pda(),
+            token_program: SPL_TOKEN_PROGRAM_ID,
+            associated_token_program: ASSOCIATED_TOKEN_PROGRAM_ID,
+            system_program: system_program::ID,
+        },
+        instruction::MintXp { game_id },
+    );
+    for player in players {
+        ix.accounts.push(AccountMeta::new_readonly(*player, false));
+        ix.accounts.push(AccountMeta::new(
+            associated_token_address(player, &xp_mint),
+            false,
+        ));
+    }
+    ix
+}
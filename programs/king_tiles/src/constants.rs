@@ -8,12 +8,216 @@ pub const BOMB_MARK: u8 = 253;
 
 pub const POWERUP_MARK: u8 = 254;
 
-pub const POWERUP_SCORE: u64 = 4;
+pub const WALL_MARK: u8 = 252;
+
+pub const BOMB_KIT_MARK: u8 = 251;
+
+pub const JACKPOT_MARK: u8 = 250;
+
+pub const GUARD_MARK: u8 = 249;
+
+/// An empty poisoned cell; players walk onto it like `EMPTY`.
+pub const POISON_MARK: u8 = 248;
+
+pub const MAX_WALLS: usize = 32;
+
+pub const MAX_PLAYERS: usize = 6;
+
+pub const MAX_BOMBS: usize = 4;
+
+/// King bodyguards on 10x10 and 12x12 boards; the 8x8 duel gets one.
+pub const MAX_GUARDS: usize = 2;
+
+/// Minimum gap between guard steps, however many relayers crank the score.
+pub const GUARD_STEP_SECONDS: i64 = 1;
+
+/// Points a hunter earns for catching the king-player in the `KingPlayer` variant.
+pub const KING_CATCH_POINTS: u64 = 5;
+
+/// The king-player earns `EVASION_POINTS` for every `EVASION_INTERVAL_SECONDS` uncaught.
+pub const EVASION_INTERVAL_SECONDS: i64 = 5;
+pub const EVASION_POINTS: u64 = 2;
+
+/// Minimum gap between `spread_poison` ticks; each tick drains and spreads once.
+pub const POISON_SPREAD_SECONDS: i64 = 5;
+
+/// Points a player standing on poison loses per tick, floored at zero.
+pub const POISON_DRAIN_POINTS: u64 = 1;
+
+/// Empty cells poison always leaves, so the king, items, bombs, guards, and respawns can still
+/// be placed: spreading stops and `seed_poison` fails once only this many are left.
+pub const POISON_MIN_EMPTY_CELLS: usize = 16;
+
+/// Length of a camping score tick; faster score cranks share one.
+pub const CAMPING_TICK_SECONDS: i64 = 1;
+
+/// Window over which `board.push_cap` counts the pushes a player has taken.
+pub const PUSH_CAP_WINDOW_SECONDS: i64 = 10;
+
+/// Minimum gap between two emotes from the same seat.
+pub const EMOTE_COOLDOWN_SECONDS: i64 = 2;
+
+/// Emote ids run `0..EMOTE_COUNT`; clients map them to artwork.
+pub const EMOTE_COUNT: u8 = 16;
+
+pub const NO_POSITION: u8 = u8::MAX;
+
+pub const BOMB_FUSE_KING_MOVES: u8 = 3;
+
+pub const MAX_BOMB_INVENTORY: u8 = 2;
+
+/// Number of `PowerupKind` variants; sizes per-kind tallies such as profile credits.
+pub const POWERUP_KIND_COUNT: usize = 5;
+/// Most unused powerups of one kind a profile can bank for later games.
+pub const MAX_POWERUP_CREDITS: u8 = 3;
+
+pub const BOMB_KIT_SPAWN_ODDS: u8 = 4;
+
+/// One in this many item spawns also drops the jackpot tile, at most once per round.
+pub const JACKPOT_SPAWN_ODDS: u8 = 16;
+
+/// Share of the `JackpotVault` paid to a jackpot winner; the rest rolls over.
+pub const JACKPOT_PAYOUT_BPS: u16 = 5_000;
+
+pub const MAX_MAP_NAME_LEN: usize = 32;
+
+/// Achievement ids index bits of `PlayerProfile::achievements`.
+pub const MAX_ACHIEVEMENTS: u8 = 64;
+
+pub const MAX_ACHIEVEMENT_NAME_LEN: usize = 32;
+
+pub const MAX_DISPLAY_NAME_LEN: usize = 24;
+
+pub const MAX_METADATA_URI_LEN: usize = 200;
+
+pub const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Push powerup distance every board used before it became per-game; migrated boards keep it.
+pub const LEGACY_POWERUP_PUSH_DISTANCE: u8 = 4;
+
+pub const DEFAULT_GAME_DURATION_SECONDS: i64 = 60;
+/// Upper bound on a round's length, including every `extend_game` call.
+pub const MAX_GAME_DURATION_SECONDS: i64 = 600;
+/// Upper bound on the free-movement warmup before a round's clock starts.
+pub const MAX_WARMUP_SECONDS: i64 = 60;
+
+pub const SHIELD_DURATION_SECONDS: i64 = 10;
+
+pub const SHIELD_CHARGES: u8 = 1;
+
+pub const DOUBLE_SCORE_DURATION_SECONDS: i64 = 15;
+
+pub const KING_STEAL_BONUS_POINTS: u64 = 1;
+
+/// Cap on the per-tick streak bonus for holding the king (1, 1, 2, 2, 3, ... points).
+pub const KING_STREAK_MAX_BONUS: u64 = 5;
+
+/// 1-in-N chance that a VRF king move turns the king golden.
+pub const GOLDEN_KING_ODDS: u8 = 8;
+
+/// How long a golden king doubles king-holding points.
+pub const GOLDEN_KING_DURATION_SECONDS: i64 = 10;
+
+/// How long the `FreezeAll` global event stuns every player.
+pub const FREEZE_ALL_SECONDS: i64 = 3;
+
+/// Upper bound on the halftime freeze set by `set_halftime`.
+pub const MAX_HALFTIME_FREEZE_SECONDS: u8 = 10;
+
+/// How long the `DoublePowerups` global event makes each powerup spawn drop a second item.
+pub const DOUBLE_POWERUPS_DURATION_SECONDS: i64 = 20;
+
+pub const MAX_PUSH_CHAIN_DEPTH: u8 = 3;
+
+pub const STUN_DURATION_SECONDS: i64 = 2;
+
+pub const RESPAWN_IMMUNITY_SECONDS: i64 = 3;
+
+pub const KING_CONTEST_THRESHOLD: usize = 2;
+
+// Bits in `Board::vrf_pending`, one per randomness consumer with a request in flight.
+pub const VRF_PENDING_KING_MOVE: u8 = 1 << 0;
+pub const VRF_PENDING_POWERUP: u8 = 1 << 1;
+pub const VRF_PENDING_BOMB: u8 = 1 << 2;
+pub const VRF_PENDING_KING_START: u8 = 1 << 3;
+pub const VRF_PENDING_SPAWNS: u8 = 1 << 4;
+pub const VRF_PENDING_GLOBAL_EVENT: u8 = 1 << 5;
+pub const VRF_PENDING_HALFTIME: u8 = 1 << 6;
+pub const VRF_PENDING_TICK: u8 = VRF_PENDING_KING_MOVE | VRF_PENDING_POWERUP | VRF_PENDING_BOMB;
+
+/// A pending flag older than this is treated as a lost callback and may be re-requested.
+pub const VRF_PENDING_TIMEOUT_SECONDS: i64 = 30;
+
+// Domain tags for splitting a single VRF output across the game tick consumers.
+pub const KING_RANDOMNESS_DOMAIN: &[u8] = b"king";
+pub const POWERUP_RANDOMNESS_DOMAIN: &[u8] = b"powerup";
+pub const BOMB_RANDOMNESS_DOMAIN: &[u8] = b"bomb";
+pub const JACKPOT_RANDOMNESS_DOMAIN: &[u8] = b"jackpot";
+pub const GOLDEN_KING_RANDOMNESS_DOMAIN: &[u8] = b"golden_king";
+pub const BONUS_POWERUP_RANDOMNESS_DOMAIN: &[u8] = b"bonus_powerup";
+pub const GUARD_RANDOMNESS_DOMAIN: &[u8] = b"guard";
+
+/// Stake a relayer must keep deposited to crank score ticks.
+pub const MIN_RELAYER_STAKE_LAMPORTS: u64 = 100_000_000;
+/// Wait between `unregister_relayer` and `withdraw_relayer_stake`; the stake stays slashable.
+pub const RELAYER_UNBONDING_SECONDS: i64 = 2 * 24 * 60 * 60;
+
+/// How long a round must have been over, with its board still delegated, before
+/// `refund_stranded_board` may refund it from the base layer.
+pub const STRANDED_BOARD_GRACE_SECONDS: i64 = 60 * 60;
+
+pub const DEFAULT_RATING: u32 = 1200;
+pub const ELO_K_FACTOR: i64 = 32;
+
+/// Boards tracked by the on-chain `GameRegistry`; finished listings are evicted first.
+pub const MAX_LISTINGS: usize = 32;
+
+pub const MAX_BPS: u16 = 10_000;
 
 pub const BOARD_SIZE: usize = 144; // 12x12 grid = 144 cells
 
+/// `u64` words in a one-bit-per-cell `Bitboard`.
+pub const BOARD_WORDS: usize = BOARD_SIZE.div_ceil(64);
+
+/// Layout version written to new boards; bump alongside any `Board` field change so
+/// `migrate_board` can upgrade live accounts. Boards created before versioning are version 0.
+pub const BOARD_VERSION: u8 = 1;
+
 pub const TREASURY: Pubkey = pubkey!("86uKSrcwj3j6gaSkK5Ggvt4ni5rokpBhrk2X2jUjDUoA");
 
+pub const SPL_TOKEN_PROGRAM_ID: Pubkey = pubkey!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+
+pub const SPL_TOKEN_2022_PROGRAM_ID: Pubkey =
+    pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+/// Size of the base SPL token account layout shared by Token and Token-2022.
+pub const SPL_TOKEN_ACCOUNT_LEN: usize = 165;
+
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey =
+    pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+pub const TOKEN_METADATA_PROGRAM_ID: Pubkey =
+    pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+pub const SPL_MINT_LEN: usize = 82;
+
+pub const TROPHY_SYMBOL: &str = "KTROPHY";
+
+pub const MAX_TROPHY_URI_LEN: usize = 200;
+
+/// Owners of Pyth `PriceUpdateV2` accounts: pull-oracle receiver and sponsored push feeds.
+pub const PYTH_RECEIVER_PROGRAM_ID: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+pub const PYTH_PUSH_ORACLE_PROGRAM_ID: Pubkey =
+    pubkey!("pythWSnswVUd12oZpeFP8e9CVaEqJg25g1Vtc2biRsT");
+
+pub const PYTH_PRICE_UPDATE_DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+/// Oldest Pyth price accepted when converting a USD entry fee.
+pub const MAX_PRICE_AGE_SECONDS: i64 = 60;
+
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
 pub fn king_starting_position(board_side_len: u8) -> usize {
     let side = board_side_len as usize;
     let center_upper_left = side.checked_div(2).unwrap().checked_sub(1).unwrap();
@@ -23,3 +227,22 @@ pub fn king_starting_position(board_side_len: u8) -> usize {
         .checked_add(center_upper_left)
         .unwrap()
 }
+
+pub fn default_spawn_positions() -> [u8; MAX_PLAYERS] {
+    core::array::from_fn(|i| i as u8)
+}
+
+pub fn corner_spawn_positions(board_side_len: u8) -> [u8; MAX_PLAYERS] {
+    let side = board_side_len as usize;
+    let last_row = side.checked_mul(side.checked_sub(1).unwrap()).unwrap();
+    let mid = side.checked_div(2).unwrap();
+    [
+        0,
+        side.checked_sub(1).unwrap(),
+        last_row,
+        last_row.checked_add(side).unwrap().checked_sub(1).unwrap(),
+        mid,
+        last_row.checked_add(mid).unwrap(),
+    ]
+    .map(|cell| cell as u8)
+}
@@ -29,12 +29,267 @@ pub enum KingTilesError {
     #[msg("Invalid move")]
     InvalidMove,
 
+    #[msg("Player is stunned")]
+    PlayerStunned,
+
     #[msg("No powerup available")]
     NoPowerup,
 
+    #[msg("Powerup has expired")]
+    PowerupExpired,
+
     #[msg("Invalid powerup move")]
     InvalidPowerupMove,
 
+    #[msg("No bombs in inventory")]
+    NoBombInventory,
+
     #[msg("Invalid game configuration")]
     InvalidGameConfig,
+
+    #[msg("King has not been placed yet")]
+    KingNotPlaced,
+
+    #[msg("King has already been placed")]
+    KingAlreadyPlaced,
+
+    #[msg("Player spawns have already been placed")]
+    SpawnsAlreadyPlaced,
+
+    #[msg("Wall cell is out of bounds or not empty")]
+    InvalidWallCell,
+
+    #[msg("Map template is invalid or does not match the game mode")]
+    InvalidMapTemplate,
+
+    #[msg("A VRF request for this board is already in flight")]
+    VrfRequestPending,
+
+    #[msg("Seed reveal does not match the player's commitment")]
+    InvalidSeedReveal,
+
+    #[msg("The king moved too recently for another relocation")]
+    KingMoveTooSoon,
+
+    #[msg("Relayer stake is below the minimum required to crank")]
+    InsufficientRelayerStake,
+
+    #[msg("Slash amount exceeds the relayer's stake")]
+    InvalidSlashAmount,
+
+    #[msg("A player's profile has already been updated for this game")]
+    ProfilesAlreadyRecorded,
+
+    #[msg("A player's rating has already been settled for this game")]
+    RatingsAlreadySettled,
+
+    #[msg("Season window is invalid or does not cover this game")]
+    InvalidSeasonWindow,
+
+    #[msg("Season has not ended yet")]
+    SeasonNotOver,
+
+    #[msg("This result has already been recorded for the season")]
+    SeasonResultAlreadyRecorded,
+
+    #[msg("Season prize already paid or pool cannot cover it")]
+    InvalidSeasonPrize,
+
+    #[msg("Game registry has no room for another listing")]
+    RegistryFull,
+
+    #[msg("Player is not on this game's allowlist")]
+    NotAllowlisted,
+
+    #[msg("Registrant does not hold enough of the gate token")]
+    TokenGateNotMet,
+
+    #[msg("Referrer is invalid or its referral account was not provided")]
+    InvalidReferrer,
+
+    #[msg("No referral rewards to claim")]
+    NoReferralRewards,
+
+    #[msg("Sponsor vault is missing or cannot cover the rewards")]
+    InsufficientSponsorFunds,
+
+    #[msg("Price account is missing, malformed, or not the configured Pyth feed")]
+    InvalidPriceFeed,
+
+    #[msg("Pyth price is too old to convert the entry fee")]
+    StalePriceFeed,
+
+    #[msg("Jackpot vault account was not provided")]
+    JackpotVaultMissing,
+
+    #[msg("A trophy was already minted for this round")]
+    TrophyAlreadyMinted,
+
+    #[msg("Trophy metadata URI is too long")]
+    InvalidTrophyUri,
+
+    #[msg("Achievement id, threshold, or name is invalid")]
+    InvalidAchievement,
+
+    #[msg("Player has not met this achievement's condition")]
+    AchievementNotEarned,
+
+    #[msg("Achievement already unlocked")]
+    AchievementAlreadyUnlocked,
+
+    #[msg("Quest target must be positive")]
+    InvalidQuest,
+
+    #[msg("Quest is not running for this day")]
+    QuestNotActive,
+
+    #[msg("Game was already counted towards this quest")]
+    QuestProgressAlreadyRecorded,
+
+    #[msg("Quest target not reached")]
+    QuestNotComplete,
+
+    #[msg("Quest reward already claimed")]
+    QuestAlreadyClaimed,
+
+    #[msg("Quest vault cannot cover the reward")]
+    QuestRewardUnavailable,
+
+    #[msg("Betting is closed for this game")]
+    BettingClosed,
+
+    #[msg("Bet must be positive and on a registered player, and may not switch players")]
+    InvalidBet,
+
+    #[msg("Bets for this game are already settled")]
+    BetsAlreadySettled,
+
+    #[msg("Bets for this game are not settled yet")]
+    BetsNotSettled,
+
+    #[msg("Bet already claimed")]
+    BetAlreadyClaimed,
+
+    #[msg("Bet did not back a winner")]
+    BetLost,
+
+    #[msg("Wager escrow account was not provided")]
+    WagerEscrowMissing,
+
+    #[msg("Wager escrow is already settled")]
+    WagerAlreadySettled,
+
+    #[msg("Signer is not the configured admin")]
+    NotAdmin,
+
+    #[msg("Withdrawal exceeds this epoch's treasury cap")]
+    WithdrawCapExceeded,
+
+    #[msg("Treasury vault holds less than the requested withdrawal")]
+    InsufficientTreasuryVault,
+
+    #[msg("Parameter change is still timelocked")]
+    ParamChangeLocked,
+
+    #[msg("Parameter value is invalid for this board")]
+    InvalidParamChange,
+
+    #[msg("Game is paused")]
+    GamePaused,
+
+    #[msg("Game is not paused")]
+    GameNotPaused,
+
+    #[msg("Extension must be positive and keep the round within the maximum duration")]
+    InvalidExtension,
+
+    #[msg("Treasury or sponsor vault cannot cover the worst-case payout")]
+    InsufficientPrizeFunds,
+
+    #[msg("Rewards have not been distributed yet")]
+    RewardsPending,
+
+    #[msg("A backup validator is required")]
+    InvalidValidator,
+
+    #[msg("Board is not delegated to the ER; send gameplay there once it is")]
+    BoardNotDelegated,
+
+    #[msg("Board data does not match the layout version given for migration")]
+    InvalidBoardLayout,
+
+    #[msg("The player in the way cannot be pushed: a wall, an occupied cell, or too long a chain")]
+    BlockedPush,
+
+    #[msg("Target cell is outside the active board")]
+    OutOfBounds,
+
+    #[msg("Target cell is already occupied")]
+    CellOccupied,
+
+    #[msg("Payout page must hold the next unpaid players, at least one while any remain")]
+    InvalidPayoutPage,
+
+    #[msg("Payout accounts must be system-owned wallets")]
+    InvalidPayoutAccount,
+
+    #[msg("A payout page may list each wallet only once")]
+    DuplicatePayoutAccount,
+
+    #[msg("Not available in this game variant")]
+    WrongGameVariant,
+
+    #[msg("This setting can only change while the lobby is empty")]
+    LobbyNotEmpty,
+
+    #[msg("That player has taken too many pushes recently")]
+    PushCapReached,
+
+    #[msg("Not available until warmup ends")]
+    WarmupInProgress,
+
+    #[msg("Halftime is not due yet, already ran, or is disabled for this game")]
+    HalftimeNotDue,
+
+    #[msg("XP is not configured")]
+    XpDisabled,
+
+    #[msg("XP was already minted for this round")]
+    XpAlreadyMinted,
+
+    #[msg("XP mint must be an SPL mint whose authority is the xp_authority PDA")]
+    InvalidXpMint,
+
+    #[msg("No banked credit for that powerup")]
+    NoPowerupCredit,
+
+    #[msg("A powerup credit was already redeemed this round, or a powerup is already held")]
+    PowerupCreditUnavailable,
+
+    #[msg("Spectators can only join an open lobby")]
+    SpectatingClosed,
+
+    #[msg("Unknown emote")]
+    InvalidEmote,
+
+    #[msg("Emotes are on cooldown")]
+    EmoteCooldown,
+
+    #[msg("Display name must be 1-24 bytes and the metadata URI at most 200")]
+    InvalidPlayerIdentity,
+
+    #[msg("Relayer is already unbonding")]
+    RelayerUnbonding,
+
+    #[msg("Relayer stake is still in its unbonding period")]
+    RelayerStakeLocked,
+
+    #[msg("Too few empty cells are left for more poison")]
+    PoisonSaturated,
+
+    #[msg("Board is not delegated, or its round ended too recently to count as stranded")]
+    BoardNotStranded,
+
+    #[msg("This round was refunded while its board was stranded")]
+    RoundRefunded,
 }
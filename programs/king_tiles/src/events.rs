@@ -1,10 +1,18 @@
-
 use anchor_lang::prelude::*;
 
+use crate::constants::MAX_PLAYERS;
+use crate::state::{GameParam, GameVariant, GlobalEvent, PowerupKind};
+
 #[event]
 pub struct PlayerRegisteredEvent {
     pub player: Pubkey,
+    /// Account that paid the registration fee; equals `player` for self-registration.
+    pub sponsor: Pubkey,
     pub game_id: u64,
+    /// Registration fee charged for this seat, after any escalation.
+    pub fee_lamports: u64,
+    /// From the player's profile when one was passed; empty otherwise.
+    pub display_name: String,
 }
 
 #[event]
@@ -51,6 +59,13 @@ pub struct PowerupMoveEvent {
 pub struct PowerUsedEvent {
     pub player: u8,
     pub game_id: u64,
+    pub kind: PowerupKind,
+    /// Cells a `Push` beam crossed in order; empty for other powerups.
+    pub scanned_cells: Vec<u8>,
+    /// Id of the player the beam hit (0 for none) and their cell before and after the push.
+    pub victim: u8,
+    pub victim_from: u8,
+    pub victim_to: u8,
 }
 #[event]
 pub struct PlayerScoredPowerupEvent {
@@ -63,8 +78,689 @@ pub struct BombDropEvent {
     pub game_id: u64,
     pub bomb_drop: u8,
 }
+#[event]
+pub struct BombDetonatedEvent {
+    pub game_id: u64,
+    pub cell: u8,
+}
+
 #[event]
 pub struct PlayerScoredBombEvent {
     pub player: Pubkey,
     pub game_id: u64,
 }
+
+#[event]
+pub struct MapTemplateCreatedEvent {
+    pub template: Pubkey,
+    pub creator: Pubkey,
+    pub name: String,
+    pub board_side_len: u8,
+}
+
+#[event]
+pub struct BombKitCollectedEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+}
+
+#[event]
+pub struct BombPlacedEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub cell: u8,
+}
+
+#[event]
+pub struct ShieldAbsorbedEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+}
+
+#[event]
+pub struct TeleportEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub from: u8,
+    pub to: u8,
+}
+
+#[event]
+pub struct KingPulledEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub from: u8,
+    pub to: u8,
+}
+
+#[event]
+pub struct ScoreTickEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub points: u64,
+    pub multiplier: u64,
+    pub elapsed_seconds: u64,
+    /// Seconds of `elapsed_seconds` scored at double under a golden king.
+    pub golden_seconds: u64,
+}
+
+#[event]
+pub struct PowerupExpiredEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub kind: PowerupKind,
+}
+
+#[event]
+pub struct ScoreStolenEvent {
+    pub attacker: Pubkey,
+    pub victim: Pubkey,
+    pub game_id: u64,
+    pub points: u64,
+}
+
+#[event]
+pub struct KingStolenEvent {
+    pub attacker: Pubkey,
+    pub victim: Pubkey,
+    pub game_id: u64,
+    pub points: u64,
+}
+
+#[event]
+pub struct PlayerStunnedEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub stunned_until: i64,
+}
+
+#[event]
+pub struct PlayerImmuneEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub immune_until: i64,
+}
+
+#[event]
+pub struct KingContestedEvent {
+    pub game_id: u64,
+    pub contested: bool,
+}
+
+#[event]
+pub struct PlayersSpawnedEvent {
+    pub game_id: u64,
+    pub spawn_positions: [u8; MAX_PLAYERS],
+}
+
+#[event]
+pub struct SeedRevealedEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+}
+
+#[event]
+pub struct CrankTipPaidEvent {
+    pub cranker: Pubkey,
+    pub game_id: u64,
+    pub lamports: u64,
+}
+
+#[event]
+pub struct RelayerRegisteredEvent {
+    pub relayer: Pubkey,
+    pub stake_lamports: u64,
+}
+
+#[event]
+pub struct RelayerSlashedEvent {
+    pub relayer: Pubkey,
+    pub amount: u64,
+    pub remaining_stake: u64,
+}
+
+#[event]
+pub struct RelayerUnbondingEvent {
+    pub relayer: Pubkey,
+    pub withdrawable_at: i64,
+}
+
+#[event]
+pub struct RelayerStakeWithdrawnEvent {
+    pub relayer: Pubkey,
+    pub lamports: u64,
+}
+
+#[event]
+pub struct PlayerProfileUpdatedEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub games_played: u32,
+    pub wins: u32,
+    pub total_score: u64,
+}
+
+#[event]
+pub struct RatingUpdatedEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub old_rating: u32,
+    pub new_rating: u32,
+}
+
+#[event]
+pub struct SeasonStartedEvent {
+    pub season_id: u32,
+    pub start_timestamp: i64,
+    pub end_timestamp: i64,
+}
+
+#[event]
+pub struct SeasonResultRecordedEvent {
+    pub season_id: u32,
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub points: u64,
+    pub season_points: u64,
+}
+
+#[event]
+pub struct SeasonPrizePaidEvent {
+    pub season_id: u32,
+    pub player: Pubkey,
+    pub lamports: u64,
+}
+
+#[event]
+pub struct RematchStartedEvent {
+    pub game_id: u64,
+    pub fee_collected: bool,
+}
+
+#[event]
+pub struct BoardResetEvent {
+    pub game_id: u64,
+    pub registration_fee_lamports: u64,
+    pub lamports_per_score: u64,
+}
+
+#[event]
+pub struct ReferralCreditedEvent {
+    pub referrer: Pubkey,
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub lamports: u64,
+}
+
+#[event]
+pub struct ReferralRewardsClaimedEvent {
+    pub referrer: Pubkey,
+    pub lamports: u64,
+}
+
+#[event]
+pub struct SponsorVaultFundedEvent {
+    pub game_id: u64,
+    pub sponsor: Pubkey,
+    pub lamports: u64,
+}
+
+#[event]
+pub struct JackpotSpawnedEvent {
+    pub game_id: u64,
+    pub cell: u8,
+}
+
+#[event]
+pub struct JackpotHitEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+}
+
+#[event]
+pub struct JackpotPaidEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub lamports: u64,
+}
+
+#[event]
+pub struct TrophyMintedEvent {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub mint: Pubkey,
+    pub score: u64,
+    pub minted_at: i64,
+}
+
+#[event]
+pub struct AchievementUnlockedEvent {
+    pub player: Pubkey,
+    pub achievement_id: u8,
+    pub name: String,
+}
+
+#[event]
+pub struct DailyQuestRefreshedEvent {
+    pub quest_id: u8,
+    pub day: i64,
+    pub target: u64,
+    pub reward_lamports: u64,
+}
+
+#[event]
+pub struct QuestProgressEvent {
+    pub quest_id: u8,
+    pub player: Pubkey,
+    pub progress: u64,
+    pub target: u64,
+}
+
+#[event]
+pub struct QuestRewardClaimedEvent {
+    pub quest_id: u8,
+    pub player: Pubkey,
+    pub lamports: u64,
+}
+
+#[event]
+pub struct BetPlacedEvent {
+    pub game_id: u64,
+    pub round: u32,
+    pub bettor: Pubkey,
+    pub on_player: u8,
+    pub amount: u64,
+    pub total_staked: u64,
+}
+
+#[event]
+pub struct BetsSettledEvent {
+    pub game_id: u64,
+    pub round: u32,
+    pub winner_mask: u8,
+    pub total_staked: u64,
+    pub winners_staked: u64,
+    pub refund: bool,
+}
+
+#[event]
+pub struct BetClaimedEvent {
+    pub game_id: u64,
+    pub bettor: Pubkey,
+    pub lamports: u64,
+}
+
+#[event]
+pub struct WagerSettledEvent {
+    pub game_id: u64,
+    pub winner: Pubkey,
+    pub payout: u64,
+    pub rake: u64,
+}
+
+#[event]
+pub struct WagerRefundedEvent {
+    pub game_id: u64,
+    pub lamports: u64,
+}
+
+#[event]
+pub struct TreasuryDepositEvent {
+    pub amount: u64,
+    pub total_deposited_lamports: u64,
+}
+
+#[event]
+pub struct AdminWithdrawEvent {
+    pub admin: Pubkey,
+    pub destination: Pubkey,
+    pub amount: u64,
+    pub epoch: u64,
+    pub withdrawn_in_epoch: u64,
+}
+
+#[event]
+pub struct ParamChangeProposedEvent {
+    pub game_id: u64,
+    pub param: GameParam,
+    pub value: u64,
+    pub executable_at: i64,
+}
+
+#[event]
+pub struct ParamChangeExecutedEvent {
+    pub game_id: u64,
+    pub param: GameParam,
+    pub value: u64,
+}
+
+#[event]
+pub struct GamePausedEvent {
+    pub game_id: u64,
+    pub paused_at: i64,
+}
+
+#[event]
+pub struct GameResumedEvent {
+    pub game_id: u64,
+    pub paused_seconds: i64,
+    pub game_end_timestamp: i64,
+}
+
+#[event]
+pub struct GameExtendedEvent {
+    pub game_id: u64,
+    pub extra_seconds: i64,
+    pub game_end_timestamp: i64,
+}
+
+#[event]
+pub struct RewardPaidEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub score: u64,
+    pub lamports: u64,
+}
+
+#[event]
+pub struct DistributionCompleteEvent {
+    pub game_id: u64,
+    pub players_paid: u8,
+    pub total_lamports: u64,
+}
+
+#[event]
+pub struct BoardForceRecoveredEvent {
+    pub game_id: u64,
+    pub admin: Pubkey,
+    pub game_end_timestamp: i64,
+}
+
+#[event]
+pub struct BoardCheckpointEvent {
+    pub game_id: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LobbyFilledEvent {
+    pub game_id: u64,
+}
+
+#[event]
+pub struct BoardRedelegatedEvent {
+    pub game_id: u64,
+    pub validator: Pubkey,
+}
+
+#[event]
+pub struct BoardMigratedEvent {
+    pub game_id: u64,
+    pub from_version: u8,
+    pub to_version: u8,
+}
+
+#[event]
+pub struct LobbyExpandedEvent {
+    pub game_id: u64,
+    pub board_side_len: u8,
+    pub max_players: u8,
+}
+
+/// Compute units left at the end of `make_move` / `use_power`; emitted only in builds with the
+/// `compute-telemetry` feature.
+#[cfg(feature = "compute-telemetry")]
+#[event]
+pub struct ComputeUnitsEvent {
+    pub game_id: u64,
+    pub instruction: String,
+    pub remaining_units: u64,
+}
+
+#[event]
+pub struct KingStreakEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub streak: u16,
+    pub bonus_points: u64,
+}
+
+#[event]
+pub struct GoldenKingEvent {
+    pub game_id: u64,
+    pub until: i64,
+}
+
+#[event]
+pub struct GlobalEventTriggeredEvent {
+    pub game_id: u64,
+    pub event: GlobalEvent,
+}
+
+#[event]
+pub struct GuardsMovedEvent {
+    pub game_id: u64,
+    pub guard_positions: Vec<u8>,
+}
+
+#[event]
+pub struct GameVariantSetEvent {
+    pub game_id: u64,
+    pub variant: GameVariant,
+}
+
+#[event]
+pub struct KingRoleAssignedEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub player_id: u8,
+}
+
+#[event]
+pub struct KingPlayerCaughtEvent {
+    pub hunter: Pubkey,
+    pub king_player: Pubkey,
+    pub game_id: u64,
+    pub points: u64,
+}
+
+#[event]
+pub struct KingEvadedEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub points: u64,
+    pub intervals: u64,
+}
+
+#[event]
+pub struct KingPickedUpEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub cell: u8,
+}
+
+#[event]
+pub struct KingDroppedEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub cell: u8,
+}
+
+#[event]
+pub struct PoisonSpreadEvent {
+    pub game_id: u64,
+    pub cell: u8,
+}
+
+#[event]
+pub struct PoisonDrainEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub points: u64,
+}
+
+#[event]
+pub struct CampingDecaySetEvent {
+    pub game_id: u64,
+    pub idle_ticks: u16,
+    pub decay_points: u64,
+}
+
+#[event]
+pub struct CampingDecayEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub points: u64,
+    pub idle_ticks: u16,
+}
+
+#[event]
+pub struct BombPenaltySetEvent {
+    pub game_id: u64,
+    pub points: u64,
+}
+
+#[event]
+pub struct BombPenaltyEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub points: u64,
+}
+
+#[event]
+pub struct PowerupPushDistanceSetEvent {
+    pub game_id: u64,
+    pub distance: u8,
+}
+
+#[event]
+pub struct PushCapSetEvent {
+    pub game_id: u64,
+    pub cap: u8,
+}
+
+#[event]
+pub struct WarmupSetEvent {
+    pub game_id: u64,
+    pub seconds: u16,
+}
+
+#[event]
+pub struct WarmupStartedEvent {
+    pub game_id: u64,
+    pub warmup_ends_at: i64,
+}
+
+#[event]
+pub struct HalftimeSetEvent {
+    pub game_id: u64,
+    pub freeze_seconds: u8,
+}
+
+#[event]
+pub struct HalftimeEvent {
+    pub game_id: u64,
+    pub king_position: u8,
+    pub powerup_position: u8,
+    pub bomb_positions: Vec<u8>,
+    pub resumes_at: i64,
+}
+
+#[event]
+pub struct FeeEscalationSetEvent {
+    pub game_id: u64,
+    pub escalation_bps: u16,
+}
+
+#[event]
+pub struct XpConfigSetEvent {
+    pub xp_mint: Pubkey,
+    pub xp_per_score: u64,
+}
+
+#[event]
+pub struct XpMintedEvent {
+    pub game_id: u64,
+    pub player: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct PowerupCreditEarnedEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub kind: PowerupKind,
+    /// Credits of this kind now banked on the profile.
+    pub credits: u8,
+}
+
+#[event]
+pub struct PowerupCreditRedeemedEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub kind: PowerupKind,
+}
+
+#[event]
+pub struct PowerupCreditReservedEvent {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub round: u32,
+    pub kind: PowerupKind,
+    /// Credits of `kind` left on the profile after the debit.
+    pub credits: u8,
+}
+
+/// Every active cell's mark in row-major order, at most `BOARD_SIZE` bytes so it always
+/// fits in one log line; lets spectators sync without fetching the delegated board.
+#[event]
+pub struct BoardSnapshotEvent {
+    pub game_id: u64,
+    pub board_side_len: u8,
+    pub cells: Vec<u8>,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct SpectatorFeeSetEvent {
+    pub game_id: u64,
+    pub fee_lamports: u64,
+}
+
+#[event]
+pub struct SpectatorJoinedEvent {
+    pub game_id: u64,
+    pub spectator: Pubkey,
+    pub fee_lamports: u64,
+    pub viewer_count: u32,
+}
+
+#[event]
+pub struct SpectatorLeftEvent {
+    pub game_id: u64,
+    pub spectator: Pubkey,
+    pub viewer_count: u32,
+}
+
+#[event]
+pub struct EmoteEvent {
+    pub game_id: u64,
+    pub player_id: u8,
+    pub emote_id: u8,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct StrandedBoardRefundedEvent {
+    pub game_id: u64,
+    pub round: u32,
+    pub admin: Pubkey,
+    pub refunded_lamports: u64,
+}
+
+#[event]
+pub struct PlayerIdentitySetEvent {
+    pub player: Pubkey,
+    pub display_name: String,
+    pub metadata_uri: String,
+}
@@ -1,21 +1,30 @@
-
 use anchor_lang::prelude::*;
+#[cfg(feature = "slot-hash-randomness")]
+use anchor_lang::solana_program::sysvar::SysvarId;
 use ephemeral_rollups_sdk::anchor::{commit, delegate, ephemeral};
 use ephemeral_rollups_sdk::cpi::DelegateConfig;
-use ephemeral_rollups_sdk::ephem::commit_and_undelegate_accounts;
+use ephemeral_rollups_sdk::ephem::{commit_accounts, commit_and_undelegate_accounts};
 use ephemeral_vrf_sdk::anchor::vrf;
 use ephemeral_vrf_sdk::instructions::{create_request_randomness_ix, RequestRandomnessParams};
 use ephemeral_vrf_sdk::types::SerializableAccountMeta;
-mod constants;
-mod error;
+use solana_sha256_hasher::hashv;
+#[cfg(all(feature = "client", not(target_os = "solana")))]
+pub mod client;
+pub mod constants;
+pub mod error;
 use error::*;
-mod events;
+pub mod events;
 use events::*;
 mod movement;
-mod state;
+mod rating;
+pub mod state;
+mod trophy;
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
 use constants::*;
 use movement::*;
+use rating::*;
 use state::*;
+use trophy::*;
 declare_id!("GAfcEqSSQJm2coiTRf4wL1SDX78jciwE6bN9eHwUaXi9");
 
 #[ephemeral]
@@ -26,44 +35,105 @@ pub mod king_tiles {
     pub fn start_game_session(
         ctx: Context<StartGameSession>,
         game_id: u64,
-        board_side_len: u8,
-        max_players: u8,
-        registration_fee_lamports: u64,
-        lamports_per_score: u64,
+        args: GameSessionArgs,
     ) -> Result<()> {
         msg!("Starting game session for game_id: {}", game_id);
+        open_game_session(
+            &mut ctx.accounts.board_account,
+            &mut ctx.accounts.registry,
+            ctx.accounts.map_template.as_ref(),
+            ctx.accounts.treasury_signer.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            game_id,
+            args,
+        )
+    }
+
+    /// Lets a registered partner authority (a wallet, or a program PDA signing through CPI)
+    /// create a board under the treasury's seeds; `payer` funds the rent and crank tip budget.
+    pub fn start_partner_game_session(
+        ctx: Context<StartPartnerGameSession>,
+        game_id: u64,
+        args: GameSessionArgs,
+    ) -> Result<()> {
+        msg!(
+            "Partner {} starting game session for game_id: {}",
+            ctx.accounts.partner_authority.key(),
+            game_id
+        );
+        open_game_session(
+            &mut ctx.accounts.board_account,
+            &mut ctx.accounts.registry,
+            ctx.accounts.map_template.as_ref(),
+            ctx.accounts.payer.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            game_id,
+            args,
+        )?;
+        let partner = &mut ctx.accounts.partner;
+        partner.games_created = partner.games_created.checked_add(1).unwrap();
+        Ok(())
+    }
+
+    /// Allows `authority` to call `start_partner_game_session`.
+    pub fn register_partner(ctx: Context<RegisterPartner>, authority: Pubkey) -> Result<()> {
+        let partner = &mut ctx.accounts.partner;
+        partner.authority = authority;
+        partner.games_created = 0;
+        partner.registered_at = current_timestamp()?;
+        Ok(())
+    }
+
+    /// Revokes a partner's game creation rights; boards it already created are unaffected.
+    pub fn remove_partner(ctx: Context<RemovePartner>) -> Result<()> {
+        msg!("Removing partner {}", ctx.accounts.partner.authority);
+        Ok(())
+    }
+
+    pub fn create_map_template(
+        ctx: Context<CreateMapTemplate>,
+        name: String,
+        board_side_len: u8,
+        king_start: u8,
+        spawn_points: Vec<u8>,
+        walls: Vec<u8>,
+    ) -> Result<()> {
+        msg!("Creating map template: {}", name);
         require!(
-            valid_mode(board_side_len, max_players),
-            KingTilesError::InvalidGameConfig
+            !name.is_empty() && name.len() <= MAX_MAP_NAME_LEN,
+            KingTilesError::InvalidMapTemplate
         );
         require!(
-            registration_fee_lamports > 0 && lamports_per_score > 0,
-            KingTilesError::InvalidGameConfig
+            valid_mode(board_side_len, spawn_points.len() as u8) && walls.len() <= MAX_WALLS,
+            KingTilesError::InvalidMapTemplate
+        );
+        require!(
+            valid_map_layout(board_side_len, king_start, &spawn_points, &walls),
+            KingTilesError::InvalidMapTemplate
         );
 
-        let board_account = &mut ctx.accounts.board_account;
-        board_account.game_id = game_id;
-        board_account.board_side_len = board_side_len;
-        board_account.max_players = max_players;
-        board_account.registration_fee_lamports = registration_fee_lamports;
-        board_account.lamports_per_score = lamports_per_score;
-        board_account.players.clear();
-        board_account.players_count = 0;
-        board_account.is_active = false;
-        board_account.last_move_timestamp = 0;
-        board_account.game_end_timestamp = 0;
-        board_account.powerup_current_position = 0;
-        board_account.bomb_current_position = 0;
-        board_account.board = [EMPTY; BOARD_SIZE];
-
-        let king_position = king_starting_position(board_side_len);
-        board_account.king_current_position = king_position as u8;
-        board_account.board[king_position] = KING_MARK;
+        let template = &mut ctx.accounts.map_template;
+        template.creator = ctx.accounts.creator.key();
+        template.name = name;
+        template.board_side_len = board_side_len;
+        template.king_start = king_start;
+        template.spawn_points = spawn_points;
+        template.walls = walls;
+
+        emit!(MapTemplateCreatedEvent {
+            template: template.key(),
+            creator: template.creator,
+            name: template.name.clone(),
+            board_side_len,
+        });
         Ok(())
     }
 
     pub fn delegate_board(ctx: Context<DelegateBoard>, game_id: u64) -> Result<()> {
         msg!("Delegating board for game_id: {}", game_id);
+        let board = &mut ctx.accounts.board_account;
+        board.mark_delegated(current_timestamp()?);
+        board.exit(&crate::ID)?;
         ctx.accounts.delegate_pda(
             &ctx.accounts.treasury_signer,
             &[
@@ -82,47 +152,114 @@ pub mod king_tiles {
         Ok(())
     }
 
-    pub fn register_player(ctx: Context<RegisterPlayer>, game_id: u64) -> Result<()> {
-        msg!("Registering player for game_id: {}", game_id);
-        let board_account = &mut ctx.accounts.board_account;
+    /// Starts a full `delegate_on_activation` lobby and delegates its board to the config's
+    /// validator in the same transaction, so the round clock never runs on the base layer.
+    pub fn activate_and_delegate(ctx: Context<ActivateAndDelegate>, game_id: u64) -> Result<()> {
+        msg!("Activating and delegating board for game_id: {}", game_id);
+        let board = &mut ctx.accounts.board_account;
         require!(
-            board_account.players_count < board_account.max_players,
-            KingTilesError::MaxPlayersReached
+            !board.is_active
+                && board.game_end_timestamp == 0
+                && board.players_count == board.max_players,
+            KingTilesError::GameNotFull
         );
-        require!(!board_account.is_active, KingTilesError::GameAlreadyStarted);
-        let transfer_ix = anchor_lang::system_program::Transfer {
-            from: ctx.accounts.payer.to_account_info(),
-            to: ctx.accounts.treasury.to_account_info(),
-        };
-        anchor_lang::system_program::transfer(
-            CpiContext::new(ctx.accounts.system_program.to_account_info(), transfer_ix),
-            board_account.registration_fee_lamports,
+        activate_round(board)?;
+        require!(
+            ctx.accounts.registry.sync(board),
+            KingTilesError::RegistryFull
+        );
+        board.mark_delegated(current_timestamp()?);
+        board.exit(&crate::ID)?;
+        let validator = ctx
+            .accounts
+            .config
+            .as_ref()
+            .map(|config| config.validator)
+            .filter(|validator| *validator != Pubkey::default());
+        ctx.accounts.delegate_pda(
+            &ctx.accounts.treasury_signer,
+            &[
+                b"board",
+                ctx.accounts.treasury_signer.key().as_ref(),
+                &game_id.to_le_bytes(),
+            ],
+            DelegateConfig {
+                validator,
+                ..Default::default()
+            },
         )?;
+        emit!(DelegateBoardEvent { game_id });
+        Ok(())
+    }
 
-        let players_count = board_account.players_count;
+    pub fn register_player(
+        ctx: Context<RegisterPlayer>,
+        game_id: u64,
+        seed_commitment: [u8; 32],
+        allowlist_proof: Vec<[u8; 32]>,
+        referrer: Option<Pubkey>,
+    ) -> Result<()> {
+        let player = ctx.accounts.payer.key();
+        seat_player(
+            ctx,
+            game_id,
+            player,
+            seed_commitment,
+            allowlist_proof,
+            referrer,
+        )
+    }
 
-        let player = Player {
-            player: ctx.accounts.payer.key(),
-            score: 0,
-            current_position: board_account.players_count as i16,
-            id: players_count.checked_add(1).unwrap() as u8,
-            powerup_score: 0,
-        };
-        board_account.players.push(player);
-        board_account.board[player.current_position as usize] = player.id;
-        board_account.players_count = players_count.checked_add(1).unwrap();
-
-        if board_account.players_count == board_account.max_players {
-            board_account.is_active = true;
-            let clock = Clock::get()?;
-            board_account.game_end_timestamp = clock.unix_timestamp.checked_add(60).unwrap();
-            emit!(GameStartedEvent {
-                game_id: board_account.game_id,
-            });
-        }
-        emit!(PlayerRegisteredEvent {
-            player: ctx.accounts.payer.key(),
-            game_id: ctx.accounts.board_account.game_id
+    /// Sponsored entry: the payer covers the fee while the seat and rewards belong to `player`.
+    pub fn register_player_for(
+        ctx: Context<RegisterPlayer>,
+        game_id: u64,
+        player: Pubkey,
+        seed_commitment: [u8; 32],
+        allowlist_proof: Vec<[u8; 32]>,
+        referrer: Option<Pubkey>,
+    ) -> Result<()> {
+        seat_player(
+            ctx,
+            game_id,
+            player,
+            seed_commitment,
+            allowlist_proof,
+            referrer,
+        )
+    }
+
+    pub fn reveal_seed(
+        ctx: Context<RevealSeed>,
+        game_id: u64,
+        player_id: u8,
+        seed: [u8; 32],
+    ) -> Result<()> {
+        let _ = game_id;
+        let board = &mut ctx.accounts.board_account;
+        let player_index = player_id_to_index(player_id);
+        require!(
+            player_index < board.players_count as usize,
+            KingTilesError::NotPlayer
+        );
+        require!(
+            board.players[player_index].player == ctx.accounts.payer.key(),
+            KingTilesError::NotPlayer
+        );
+        let player = board.players[player_index];
+        require!(
+            player.seed_commitment != [0; 32] && !player.seed_revealed,
+            KingTilesError::InvalidSeedReveal
+        );
+        require!(
+            hashv(&[&seed, player.player.as_ref()]).to_bytes() == player.seed_commitment,
+            KingTilesError::InvalidSeedReveal
+        );
+        board.players[player_index].seed_revealed = true;
+        board.seed_mix = hashv(&[&board.seed_mix, &seed]).to_bytes();
+        emit!(SeedRevealedEvent {
+            player: player.player,
+            game_id: board.game_id,
         });
         Ok(())
     }
@@ -142,6 +279,11 @@ pub mod king_tiles {
             KingTilesError::GameEnded
         );
         require!(board.is_active, KingTilesError::GameNotStarted);
+        require!(board.paused_at == 0, KingTilesError::GamePaused);
+        require!(
+            board.delegation_state == DelegationState::Delegated,
+            KingTilesError::BoardNotDelegated
+        );
         require!(
             board.players_count == board.max_players,
             KingTilesError::GameNotFull
@@ -159,6 +301,9 @@ pub mod king_tiles {
             board.players[player_index].player == ctx.accounts.payer.key(),
             KingTilesError::NotPlayer
         );
+        require_not_stunned(board, player_index, clock.unix_timestamp)?;
+        expire_stale_powerup(board, player_index, clock.unix_timestamp);
+        settle_king_occupancy(board)?;
         let move_position = direction.offset(board.board_side_len);
         let active_cells = board.active_board_cells();
         let payer_key = ctx.accounts.payer.key();
@@ -168,13 +313,150 @@ pub mod king_tiles {
             .unwrap()
             .rem_euclid(active_cells as i16) as usize;
 
-        check_board_for_new_position(payer_key, board, player_index, new_position, move_position);
+        match check_board_for_new_position(
+            payer_key,
+            board,
+            player_index,
+            new_position,
+            move_position,
+        )? {
+            MoveOutcome::Blocked => return err!(KingTilesError::CellOccupied),
+            MoveOutcome::PushBlocked => return err!(KingTilesError::BlockedPush),
+            MoveOutcome::PushCapped => return err!(KingTilesError::PushCapReached),
+            MoveOutcome::Moved | MoveOutcome::Deflected | MoveOutcome::Warped => {}
+        }
+        follow_carrier(board, Some((player_index, new_position)));
+        settle_king_occupancy(board)?;
+        let moves_made = board.players[player_index].moves_made;
+        board.players[player_index].moves_made = moves_made.checked_add(1).unwrap();
 
         emit!(MoveMadeEvent {
             player: payer_key,
             game_id: board.game_id,
         });
+        #[cfg(feature = "compute-telemetry")]
+        emit_compute_units(board.game_id, "make_move");
+
+        Ok(())
+    }
+
+    pub fn place_bomb(
+        ctx: Context<PlaceBomb>,
+        game_id: u64,
+        player_id: u8,
+        direction: Direction,
+    ) -> Result<()> {
+        let _ = game_id;
+        let board = &mut ctx.accounts.board_account;
+
+        let clock = Clock::get()?;
+        require!(
+            clock.unix_timestamp < board.game_end_timestamp,
+            KingTilesError::GameEnded
+        );
+        require!(board.is_active, KingTilesError::GameNotStarted);
+        require!(board.paused_at == 0, KingTilesError::GamePaused);
+        require!(
+            !board.in_warmup(current_timestamp()?),
+            KingTilesError::WarmupInProgress
+        );
+        require!(
+            board.delegation_state == DelegationState::Delegated,
+            KingTilesError::BoardNotDelegated
+        );
+        let player_index = player_id_to_index(player_id);
+        require!(
+            player_index < board.players_count as usize,
+            KingTilesError::NotPlayer
+        );
+        require!(
+            board.players[player_index].player == ctx.accounts.payer.key(),
+            KingTilesError::NotPlayer
+        );
+        require_not_stunned(board, player_index, clock.unix_timestamp)?;
+        require!(
+            board.players[player_index].bomb_inventory > 0,
+            KingTilesError::NoBombInventory
+        );
+
+        let current_position = board.players[player_index].current_position as usize;
+        let target = neighbor_in_direction(board, current_position, direction)
+            .ok_or(KingTilesError::OutOfBounds)?;
+        require!(board.cell(target) == EMPTY, KingTilesError::CellOccupied);
+
+        board.arm_bomb(target);
+        board.players[player_index].bomb_inventory = board.players[player_index]
+            .bomb_inventory
+            .checked_sub(1)
+            .unwrap();
+
+        emit!(BombPlacedEvent {
+            player: ctx.accounts.payer.key(),
+            game_id: board.game_id,
+            cell: target as u8,
+        });
+        Ok(())
+    }
+
+    pub fn use_teleport(
+        ctx: Context<UseTeleport>,
+        game_id: u64,
+        player_id: u8,
+        target_cell: u8,
+    ) -> Result<()> {
+        let _ = game_id;
+        let board = &mut ctx.accounts.board_account;
+
+        let now = current_timestamp()?;
+        require!(now < board.game_end_timestamp, KingTilesError::GameEnded);
+        require!(board.is_active, KingTilesError::GameNotStarted);
+        require!(board.paused_at == 0, KingTilesError::GamePaused);
+        require!(!board.in_warmup(now), KingTilesError::WarmupInProgress);
+        require!(
+            board.delegation_state == DelegationState::Delegated,
+            KingTilesError::BoardNotDelegated
+        );
+        require!(
+            (1..=board.players_count).contains(&player_id),
+            KingTilesError::NotPlayer
+        );
+        let player_index = player_id_to_index(player_id);
+        require!(
+            board.players[player_index].player == ctx.accounts.payer.key(),
+            KingTilesError::NotPlayer
+        );
+        require_not_stunned(board, player_index, now)?;
+        require!(
+            board.players[player_index].powerup == Some(PowerupKind::Teleport),
+            KingTilesError::NoPowerup
+        );
+        require!(
+            !board.players[player_index].powerup_expired(now),
+            KingTilesError::PowerupExpired
+        );
+        let target = target_cell as usize;
+        require!(
+            target < board.active_board_cells(),
+            KingTilesError::OutOfBounds
+        );
+        // Poison is walkable, so a teleport may land on it like a move can.
+        require!(is_open(board.cell(target)), KingTilesError::CellOccupied);
+
+        let from = board.players[player_index].current_position as u8;
+        settle_king_occupancy(board)?;
+        new_position_is_empty(board, player_index, target);
+        follow_carrier(board, Some((player_index, target)));
+        settle_king_occupancy(board)?;
+        board.players[player_index].powerup = None;
+        let powerups_used = board.players[player_index].powerups_used;
+        board.players[player_index].powerups_used = powerups_used.checked_add(1).unwrap();
 
+        emit!(TeleportEvent {
+            player: ctx.accounts.payer.key(),
+            game_id: board.game_id,
+            from,
+            to: target_cell,
+        });
         Ok(())
     }
 
@@ -187,12 +469,128 @@ pub mod king_tiles {
             "Requesting VRF randomness for king move, game_id: {}",
             game_id
         );
+        let now = current_timestamp()?;
+        require!(
+            ctx.accounts.board_account.king_move_due(now),
+            KingTilesError::KingMoveTooSoon
+        );
+        require!(
+            !ctx.accounts
+                .board_account
+                .vrf_request_pending(VRF_PENDING_KING_MOVE, now),
+            KingTilesError::VrfRequestPending
+        );
+        ctx.accounts
+            .board_account
+            .mark_vrf_requested(VRF_PENDING_KING_MOVE, now);
         let ix = create_request_randomness_ix(RequestRandomnessParams {
-            payer: ctx.accounts.treasury_signer.key(),
+            payer: ctx.accounts.payer.key(),
             oracle_queue: ctx.accounts.oracle_queue.key(),
             callback_program_id: ID,
             callback_discriminator: instruction::CallbackKingMove::DISCRIMINATOR.to_vec(),
-            caller_seed: [client_seed; 32],
+            caller_seed: mixed_caller_seed(&ctx.accounts.board_account, client_seed),
+            accounts_metas: Some(vec![
+                SerializableAccountMeta {
+                    pubkey: ctx.accounts.treasury.key(),
+                    is_signer: false,
+                    is_writable: false,
+                },
+                SerializableAccountMeta {
+                    pubkey: ctx.accounts.board_account.key(),
+                    is_signer: false,
+                    is_writable: true,
+                },
+            ]),
+            ..Default::default()
+        });
+        ctx.accounts
+            .invoke_signed_vrf(&ctx.accounts.payer.to_account_info(), &ix)?;
+        let tip = ctx.accounts.board_account.options.crank_tip_lamports;
+        pay_crank_tip(
+            &ctx.accounts.board_account,
+            &ctx.accounts.payer.to_account_info(),
+            tip,
+        )?;
+        Ok(())
+    }
+
+    pub fn request_randomness_for_king_start(
+        ctx: Context<RequestRandomnessForKingStart>,
+        client_seed: u8,
+        game_id: u64,
+    ) -> Result<()> {
+        msg!(
+            "Requesting VRF randomness for king start, game_id: {}",
+            game_id
+        );
+        require!(
+            ctx.accounts.board_account.king_current_position == NO_POSITION,
+            KingTilesError::KingAlreadyPlaced
+        );
+        let now = current_timestamp()?;
+        require!(
+            !ctx.accounts
+                .board_account
+                .vrf_request_pending(VRF_PENDING_KING_START, now),
+            KingTilesError::VrfRequestPending
+        );
+        ctx.accounts
+            .board_account
+            .mark_vrf_requested(VRF_PENDING_KING_START, now);
+        let ix = create_request_randomness_ix(RequestRandomnessParams {
+            payer: ctx.accounts.treasury_signer.key(),
+            oracle_queue: ctx.accounts.oracle_queue.key(),
+            callback_program_id: ID,
+            callback_discriminator: instruction::CallbackKingStart::DISCRIMINATOR.to_vec(),
+            caller_seed: mixed_caller_seed(&ctx.accounts.board_account, client_seed),
+            accounts_metas: Some(vec![
+                SerializableAccountMeta {
+                    pubkey: ctx.accounts.treasury_signer.key(),
+                    is_signer: false,
+                    is_writable: false,
+                },
+                SerializableAccountMeta {
+                    pubkey: ctx.accounts.board_account.key(),
+                    is_signer: false,
+                    is_writable: true,
+                },
+            ]),
+            ..Default::default()
+        });
+        ctx.accounts
+            .invoke_signed_vrf(&ctx.accounts.treasury_signer.to_account_info(), &ix)?;
+        Ok(())
+    }
+
+    pub fn request_randomness_for_spawn_players(
+        ctx: Context<RequestRandomnessForSpawnPlayers>,
+        client_seed: u8,
+        game_id: u64,
+    ) -> Result<()> {
+        msg!(
+            "Requesting VRF randomness for player spawns, game_id: {}",
+            game_id
+        );
+        require!(
+            ctx.accounts.board_account.spawns_pending,
+            KingTilesError::SpawnsAlreadyPlaced
+        );
+        let now = current_timestamp()?;
+        require!(
+            !ctx.accounts
+                .board_account
+                .vrf_request_pending(VRF_PENDING_SPAWNS, now),
+            KingTilesError::VrfRequestPending
+        );
+        ctx.accounts
+            .board_account
+            .mark_vrf_requested(VRF_PENDING_SPAWNS, now);
+        let ix = create_request_randomness_ix(RequestRandomnessParams {
+            payer: ctx.accounts.treasury_signer.key(),
+            oracle_queue: ctx.accounts.oracle_queue.key(),
+            callback_program_id: ID,
+            callback_discriminator: instruction::CallbackSpawnPlayers::DISCRIMINATOR.to_vec(),
+            caller_seed: mixed_caller_seed(&ctx.accounts.board_account, client_seed),
             accounts_metas: Some(vec![
                 SerializableAccountMeta {
                     pubkey: ctx.accounts.treasury_signer.key(),
@@ -221,12 +619,22 @@ pub mod king_tiles {
             "Requesting VRF randomness for powerup move, game_id: {}",
             game_id
         );
+        let now = current_timestamp()?;
+        require!(
+            !ctx.accounts
+                .board_account
+                .vrf_request_pending(VRF_PENDING_POWERUP, now),
+            KingTilesError::VrfRequestPending
+        );
+        ctx.accounts
+            .board_account
+            .mark_vrf_requested(VRF_PENDING_POWERUP, now);
         let ix = create_request_randomness_ix(RequestRandomnessParams {
             payer: ctx.accounts.treasury_signer.key(),
             oracle_queue: ctx.accounts.oracle_queue.key(),
             callback_program_id: ID,
             callback_discriminator: instruction::CallbackSpawnPowerup::DISCRIMINATOR.to_vec(),
-            caller_seed: [client_seed; 32],
+            caller_seed: mixed_caller_seed(&ctx.accounts.board_account, client_seed),
             accounts_metas: Some(vec![
                 SerializableAccountMeta {
                     pubkey: ctx.accounts.treasury_signer.key(),
@@ -255,12 +663,22 @@ pub mod king_tiles {
             "Requesting VRF randomness for bomb drop, game_id: {}",
             game_id
         );
+        let now = current_timestamp()?;
+        require!(
+            !ctx.accounts
+                .board_account
+                .vrf_request_pending(VRF_PENDING_BOMB, now),
+            KingTilesError::VrfRequestPending
+        );
+        ctx.accounts
+            .board_account
+            .mark_vrf_requested(VRF_PENDING_BOMB, now);
         let ix = create_request_randomness_ix(RequestRandomnessParams {
             payer: ctx.accounts.treasury_signer.key(),
             oracle_queue: ctx.accounts.oracle_queue.key(),
             callback_program_id: ID,
             callback_discriminator: instruction::CallbackBombDrop::DISCRIMINATOR.to_vec(),
-            caller_seed: [client_seed; 32],
+            caller_seed: mixed_caller_seed(&ctx.accounts.board_account, client_seed),
             accounts_metas: Some(vec![
                 SerializableAccountMeta {
                     pubkey: ctx.accounts.treasury_signer.key(),
@@ -280,77 +698,383 @@ pub mod king_tiles {
         Ok(())
     }
 
-    pub fn callback_bomb_drop(ctx: Context<CallbackBombDrop>, randomness: [u8; 32]) -> Result<()> {
-        let board = &mut ctx.accounts.board_account;
-        let active_cells = board.active_board_cells();
-        let bomb_current_position = board.bomb_current_position;
-        let mut cell_index = ephemeral_vrf_sdk::rnd::random_u8_with_range(
-            &randomness,
-            0,
-            (active_cells.checked_sub(1).unwrap()) as u8,
-        ) as usize;
-        if board.board[bomb_current_position as usize] == BOMB_MARK {
-            board.board[bomb_current_position as usize] = EMPTY;
-        }
-        while board.board[cell_index] != EMPTY {
-            cell_index = (cell_index.checked_add(1).unwrap()) % active_cells;
-        }
-        board.board[cell_index] = BOMB_MARK;
-        board.bomb_current_position = cell_index as u8;
-        emit!(BombDropEvent {
-            game_id: board.game_id,
-            bomb_drop: board.bomb_current_position as u8,
-        });
-        Ok(())
-    }
-    pub fn callback_king_move(ctx: Context<CallbackKingMove>, randomness: [u8; 32]) -> Result<()> {
-        let board = &mut ctx.accounts.board_account;
-        let active_cells = board.active_board_cells();
-        let king_current_position = board.king_current_position;
+    pub fn request_randomness_for_random_event(
+        ctx: Context<RequestRandomnessForRandomEvent>,
+        client_seed: u8,
+        game_id: u64,
+    ) -> Result<()> {
+        msg!(
+            "Requesting VRF randomness for global event, game_id: {}",
+            game_id
+        );
+        let now = current_timestamp()?;
+        require!(
+            !ctx.accounts
+                .board_account
+                .vrf_request_pending(VRF_PENDING_GLOBAL_EVENT, now),
+            KingTilesError::VrfRequestPending
+        );
+        ctx.accounts
+            .board_account
+            .mark_vrf_requested(VRF_PENDING_GLOBAL_EVENT, now);
+        let ix = create_request_randomness_ix(RequestRandomnessParams {
+            payer: ctx.accounts.treasury_signer.key(),
+            oracle_queue: ctx.accounts.oracle_queue.key(),
+            callback_program_id: ID,
+            callback_discriminator: instruction::CallbackRandomEvent::DISCRIMINATOR.to_vec(),
+            caller_seed: mixed_caller_seed(&ctx.accounts.board_account, client_seed),
+            accounts_metas: Some(vec![
+                SerializableAccountMeta {
+                    pubkey: ctx.accounts.treasury_signer.key(),
+                    is_signer: false,
+                    is_writable: false,
+                },
+                SerializableAccountMeta {
+                    pubkey: ctx.accounts.board_account.key(),
+                    is_signer: false,
+                    is_writable: true,
+                },
+            ]),
+            ..Default::default()
+        });
+        ctx.accounts
+            .invoke_signed_vrf(&ctx.accounts.treasury_signer.to_account_info(), &ix)?;
+        Ok(())
+    }
+
+    pub fn request_randomness_for_tick(
+        ctx: Context<RequestRandomnessForTick>,
+        client_seed: u8,
+        game_id: u64,
+    ) -> Result<()> {
+        msg!(
+            "Requesting VRF randomness for game tick, game_id: {}",
+            game_id
+        );
+        let now = current_timestamp()?;
+        require!(
+            ctx.accounts.board_account.king_move_due(now),
+            KingTilesError::KingMoveTooSoon
+        );
+        require!(
+            !ctx.accounts
+                .board_account
+                .vrf_request_pending(VRF_PENDING_TICK, now),
+            KingTilesError::VrfRequestPending
+        );
+        ctx.accounts
+            .board_account
+            .mark_vrf_requested(VRF_PENDING_TICK, now);
+        let ix = create_request_randomness_ix(RequestRandomnessParams {
+            payer: ctx.accounts.treasury_signer.key(),
+            oracle_queue: ctx.accounts.oracle_queue.key(),
+            callback_program_id: ID,
+            callback_discriminator: instruction::CallbackGameTick::DISCRIMINATOR.to_vec(),
+            caller_seed: mixed_caller_seed(&ctx.accounts.board_account, client_seed),
+            accounts_metas: Some(vec![
+                SerializableAccountMeta {
+                    pubkey: ctx.accounts.treasury_signer.key(),
+                    is_signer: false,
+                    is_writable: false,
+                },
+                SerializableAccountMeta {
+                    pubkey: ctx.accounts.board_account.key(),
+                    is_signer: false,
+                    is_writable: true,
+                },
+            ]),
+            ..Default::default()
+        });
+        ctx.accounts
+            .invoke_signed_vrf(&ctx.accounts.treasury_signer.to_account_info(), &ix)?;
+        Ok(())
+    }
+
+    pub fn callback_bomb_drop(ctx: Context<CallbackBombDrop>, randomness: [u8; 32]) -> Result<()> {
+        let board = &mut ctx.accounts.board_account;
+        board.clear_vrf_pending(VRF_PENDING_BOMB);
+        if !board.is_live(current_timestamp()?) {
+            msg!(
+                "Ignoring VRF callback for finished game_id: {}",
+                board.game_id
+            );
+            return Ok(());
+        }
+        if board.in_warmup(current_timestamp()?) {
+            msg!(
+                "Ignoring bomb drop during warmup, game_id: {}",
+                board.game_id
+            );
+            return Ok(());
+        }
+        drop_bomb(board, &randomness);
+        emit_board_snapshot(board)?;
+        Ok(())
+    }
+
+    pub fn callback_random_event(
+        ctx: Context<CallbackRandomEvent>,
+        randomness: [u8; 32],
+    ) -> Result<()> {
+        let board = &mut ctx.accounts.board_account;
+        board.clear_vrf_pending(VRF_PENDING_GLOBAL_EVENT);
+        if !board.is_live(current_timestamp()?) {
+            msg!(
+                "Ignoring VRF callback for finished game_id: {}",
+                board.game_id
+            );
+            return Ok(());
+        }
+        if board.in_warmup(current_timestamp()?) {
+            msg!(
+                "Ignoring global event during warmup, game_id: {}",
+                board.game_id
+            );
+            return Ok(());
+        }
+        apply_global_event(board, &randomness)?;
+        emit_board_snapshot(board)?;
+        Ok(())
+    }
+
+    /// Permissionless halftime crank, accepted once per round from the halfway point of the
+    /// clock; the caller pays for the VRF request and collects the crank tip.
+    pub fn request_randomness_for_halftime(
+        ctx: Context<RequestRandomnessForHalftime>,
+        client_seed: u8,
+        game_id: u64,
+    ) -> Result<()> {
+        msg!(
+            "Requesting VRF randomness for halftime, game_id: {}",
+            game_id
+        );
+        let now = current_timestamp()?;
+        let board = &ctx.accounts.board_account;
+        require!(board.is_live(now), KingTilesError::GameNotActive);
+        require!(board.halftime_due(now), KingTilesError::HalftimeNotDue);
+        require!(
+            !board.vrf_request_pending(VRF_PENDING_HALFTIME, now),
+            KingTilesError::VrfRequestPending
+        );
+        ctx.accounts
+            .board_account
+            .mark_vrf_requested(VRF_PENDING_HALFTIME, now);
+        let ix = create_request_randomness_ix(RequestRandomnessParams {
+            payer: ctx.accounts.payer.key(),
+            oracle_queue: ctx.accounts.oracle_queue.key(),
+            callback_program_id: ID,
+            callback_discriminator: instruction::CallbackHalftime::DISCRIMINATOR.to_vec(),
+            caller_seed: mixed_caller_seed(&ctx.accounts.board_account, client_seed),
+            accounts_metas: Some(vec![
+                SerializableAccountMeta {
+                    pubkey: ctx.accounts.treasury.key(),
+                    is_signer: false,
+                    is_writable: false,
+                },
+                SerializableAccountMeta {
+                    pubkey: ctx.accounts.board_account.key(),
+                    is_signer: false,
+                    is_writable: true,
+                },
+            ]),
+            ..Default::default()
+        });
+        ctx.accounts
+            .invoke_signed_vrf(&ctx.accounts.payer.to_account_info(), &ix)?;
+        let tip = ctx.accounts.board_account.options.crank_tip_lamports;
+        pay_crank_tip(
+            &ctx.accounts.board_account,
+            &ctx.accounts.payer.to_account_info(),
+            tip,
+        )?;
+        Ok(())
+    }
+
+    pub fn callback_halftime(ctx: Context<CallbackHalftime>, randomness: [u8; 32]) -> Result<()> {
+        let board = &mut ctx.accounts.board_account;
+        board.clear_vrf_pending(VRF_PENDING_HALFTIME);
+        let now = current_timestamp()?;
+        if !board.is_live(now) || !board.halftime_due(now) {
+            msg!(
+                "Ignoring halftime VRF callback for game_id: {}",
+                board.game_id
+            );
+            return Ok(());
+        }
+        run_halftime(board, &randomness)?;
+        emit_board_snapshot(board)?;
+        Ok(())
+    }
+
+    pub fn callback_king_move(ctx: Context<CallbackKingMove>, randomness: [u8; 32]) -> Result<()> {
+        let board = &mut ctx.accounts.board_account;
+        board.clear_vrf_pending(VRF_PENDING_KING_MOVE);
+        if !board.is_live(current_timestamp()?) {
+            msg!(
+                "Ignoring VRF callback for finished game_id: {}",
+                board.game_id
+            );
+            return Ok(());
+        }
+        relocate_king(board, &randomness)?;
+        emit_board_snapshot(board)?;
+        Ok(())
+    }
+
+    pub fn callback_game_tick(ctx: Context<CallbackGameTick>, randomness: [u8; 32]) -> Result<()> {
+        let board = &mut ctx.accounts.board_account;
+        board.clear_vrf_pending(VRF_PENDING_TICK);
+        if !board.is_live(current_timestamp()?) {
+            msg!(
+                "Ignoring VRF callback for finished game_id: {}",
+                board.game_id
+            );
+            return Ok(());
+        }
+        // The king moves first so the item probes below see its new cell.
+        relocate_king(
+            board,
+            &expand_randomness(&randomness, KING_RANDOMNESS_DOMAIN),
+        )?;
+        if board.in_warmup(current_timestamp()?) {
+            emit_board_snapshot(board)?;
+            return Ok(());
+        }
+        spawn_powerup(
+            board,
+            &expand_randomness(&randomness, POWERUP_RANDOMNESS_DOMAIN),
+        )?;
+        drop_bomb(
+            board,
+            &expand_randomness(&randomness, BOMB_RANDOMNESS_DOMAIN),
+        );
+        spawn_jackpot(
+            board,
+            &expand_randomness(&randomness, JACKPOT_RANDOMNESS_DOMAIN),
+        );
+        emit_board_snapshot(board)?;
+        Ok(())
+    }
+
+    pub fn callback_king_start(
+        ctx: Context<CallbackKingStart>,
+        randomness: [u8; 32],
+    ) -> Result<()> {
+        let board = &mut ctx.accounts.board_account;
+        require!(
+            board.king_current_position == NO_POSITION,
+            KingTilesError::KingAlreadyPlaced
+        );
+        board.clear_vrf_pending(VRF_PENDING_KING_START);
+        let active_cells = board.active_board_cells();
         let mut cell_index = ephemeral_vrf_sdk::rnd::random_u8_with_range(
             &randomness,
             0,
             (active_cells.checked_sub(1).unwrap()) as u8,
         ) as usize;
-        if board.board[king_current_position as usize] == KING_MARK {
-            board.board[king_current_position as usize] = EMPTY;
-        }
-        while board.board[cell_index] != EMPTY {
+        // Keep the spawn slots free for players still registering.
+        let max_players = board.max_players as usize;
+        while board.cell(cell_index) != EMPTY
+            || board.spawn_positions[..max_players].contains(&(cell_index as u8))
+        {
             cell_index = (cell_index.checked_add(1).unwrap()) % active_cells;
         }
-        board.board[cell_index] = KING_MARK;
+        board.set_cell(cell_index, KING_MARK);
         board.king_current_position = cell_index as u8;
         emit!(KingMoveEvent {
             game_id: board.game_id,
-            king_move: board.king_current_position as u8,
+            king_move: board.king_current_position,
         });
+        emit_board_snapshot(board)?;
         Ok(())
     }
 
-    pub fn callback_spawn_powerup(
-        ctx: Context<CallbackPowerupMove>,
+    pub fn callback_spawn_players(
+        ctx: Context<CallbackSpawnPlayers>,
         randomness: [u8; 32],
     ) -> Result<()> {
         let board = &mut ctx.accounts.board_account;
-        let active_cells = board.active_board_cells();
-        let powerup_current_position = board.powerup_current_position;
-        let mut cell_index = ephemeral_vrf_sdk::rnd::random_u8_with_range(
-            &randomness,
-            0,
-            (active_cells.checked_sub(1).unwrap()) as u8,
-        ) as usize;
-        if board.board[powerup_current_position as usize] == POWERUP_MARK {
-            board.board[powerup_current_position as usize] = EMPTY;
+        require!(board.spawns_pending, KingTilesError::SpawnsAlreadyPlaced);
+        board.clear_vrf_pending(VRF_PENDING_SPAWNS);
+        if !board.is_live(current_timestamp()?) {
+            msg!(
+                "Ignoring VRF callback for finished game_id: {}",
+                board.game_id
+            );
+            return Ok(());
         }
-        while board.board[cell_index] != EMPTY {
-            cell_index = (cell_index.checked_add(1).unwrap()) % active_cells;
+        settle_king_occupancy(board)?;
+        let active_cells = board.active_board_cells();
+        let players_count = board.players_count as usize;
+        for (player_index, &byte) in randomness.iter().enumerate().take(players_count) {
+            let start = byte as usize % active_cells;
+            // A player with no empty cell to move to keeps their lobby spawn.
+            if let Some(cell) = probe_item_cell(board, start) {
+                new_position_is_empty(board, player_index, cell);
+                board.spawn_positions[player_index] = cell as u8;
+            }
         }
-        board.board[cell_index] = POWERUP_MARK;
-        board.powerup_current_position = cell_index as u8;
-        emit!(PowerupMoveEvent {
+        board.spawns_pending = false;
+        settle_king_occupancy(board)?;
+        emit!(PlayersSpawnedEvent {
             game_id: board.game_id,
-            powerup_move: board.powerup_current_position as u8,
+            spawn_positions: board.spawn_positions,
         });
+        emit_board_snapshot(board)?;
+        Ok(())
+    }
+
+    pub fn callback_spawn_powerup(
+        ctx: Context<CallbackPowerupMove>,
+        randomness: [u8; 32],
+    ) -> Result<()> {
+        let board = &mut ctx.accounts.board_account;
+        board.clear_vrf_pending(VRF_PENDING_POWERUP);
+        if !board.is_live(current_timestamp()?) {
+            msg!(
+                "Ignoring VRF callback for finished game_id: {}",
+                board.game_id
+            );
+            return Ok(());
+        }
+        if board.in_warmup(current_timestamp()?) {
+            msg!(
+                "Ignoring powerup spawn during warmup, game_id: {}",
+                board.game_id
+            );
+            return Ok(());
+        }
+        spawn_powerup(board, &randomness)?;
+        spawn_jackpot(
+            board,
+            &expand_randomness(&randomness, JACKPOT_RANDOMNESS_DOMAIN),
+        );
+        emit_board_snapshot(board)?;
+        Ok(())
+    }
+
+    /// Moves the king using the most recent slot hash instead of VRF. Predictable by
+    /// validators, so it is only compiled in for local and devnet test runs.
+    #[cfg(feature = "slot-hash-randomness")]
+    pub fn move_king_with_slot_hash(
+        ctx: Context<MoveKingWithSlotHash>,
+        game_id: u64,
+    ) -> Result<()> {
+        msg!("Moving king from slot hashes for game_id: {}", game_id);
+        let board = &mut ctx.accounts.board_account;
+        require!(
+            board.is_live(current_timestamp()?),
+            KingTilesError::GameNotActive
+        );
+        let data = ctx.accounts.recent_slothashes.try_borrow_data()?;
+        // Layout: u64 entry count, then (slot: u64, hash: [u8; 32]) entries, newest first.
+        require!(data.len() >= 48, KingTilesError::InvalidGameConfig);
+        let randomness = expand_randomness(
+            &data[16..48].try_into().unwrap(),
+            &board.game_id.to_le_bytes(),
+        );
+        drop(data);
+        relocate_king(board, &randomness)?;
         Ok(())
     }
 
@@ -366,22 +1090,31 @@ pub mod king_tiles {
         );
         let board = &mut ctx.accounts.board_account;
         require!(board.is_active, KingTilesError::GameNotStarted);
+        require!(
+            board.variant != GameVariant::KingPlayer,
+            KingTilesError::WrongGameVariant
+        );
         require!(
             (position as usize) < board.active_board_cells(),
-            KingTilesError::InvalidMove
+            KingTilesError::OutOfBounds
         );
 
         require!(
-            board.board[position as usize] == EMPTY,
-            KingTilesError::InvalidMove
+            board.cell(position as usize) == EMPTY,
+            KingTilesError::CellOccupied
         );
 
+        settle_king_occupancy(board)?;
+        board.king_carrier = 0;
         let old_pos = board.king_current_position as usize;
-        if board.board[old_pos] == KING_MARK {
-            board.board[old_pos] = EMPTY;
+        if board.king_current_position != NO_POSITION && board.cell(old_pos) == KING_MARK {
+            board.vacate(old_pos);
         }
-        board.board[position as usize] = KING_MARK;
+        board.set_cell(position as usize, KING_MARK);
         board.king_current_position = position;
+        board.king_needs_relocation = false;
+        board.last_king_move_timestamp = current_timestamp()?;
+        settle_king_occupancy(board)?;
 
         emit!(KingMoveEvent {
             game_id: board.game_id,
@@ -390,12 +1123,73 @@ pub mod king_tiles {
         Ok(())
     }
 
+    /// Freezes moves and VRF-driven board changes, e.g. while the ER validator is struggling.
+    pub fn pause_game(ctx: Context<PauseGame>, game_id: u64) -> Result<()> {
+        let board = &mut ctx.accounts.board_account;
+        let now = current_timestamp()?;
+        require!(board.is_live(now), KingTilesError::GameNotActive);
+        settle_king_occupancy(board)?;
+        award_evasion(board)?;
+        board.paused_at = now;
+        emit!(GamePausedEvent {
+            game_id,
+            paused_at: now,
+        });
+        Ok(())
+    }
+
+    /// Unfreezes the round and extends every clock by the time spent paused.
+    pub fn resume_game(ctx: Context<PauseGame>, game_id: u64) -> Result<()> {
+        let board = &mut ctx.accounts.board_account;
+        require!(board.paused_at > 0, KingTilesError::GameNotPaused);
+        let paused_seconds = current_timestamp()?.checked_sub(board.paused_at).unwrap();
+        board.shift_clocks(paused_seconds);
+        board.paused_at = 0;
+        emit!(GameResumedEvent {
+            game_id,
+            paused_seconds,
+            game_end_timestamp: board.game_end_timestamp,
+        });
+        Ok(())
+    }
+
+    pub fn extend_game(ctx: Context<ExtendGame>, game_id: u64, extra_seconds: i64) -> Result<()> {
+        let board = &mut ctx.accounts.board_account;
+        require!(
+            board.is_active && current_timestamp()? < board.game_end_timestamp,
+            KingTilesError::GameNotActive
+        );
+        let extended_seconds = board.extended_seconds.checked_add(extra_seconds).unwrap();
+        require!(
+            extra_seconds > 0
+                && board
+                    .game_duration_seconds
+                    .checked_add(extended_seconds)
+                    .unwrap()
+                    <= MAX_GAME_DURATION_SECONDS,
+            KingTilesError::InvalidExtension
+        );
+        board.extended_seconds = extended_seconds;
+        board.game_end_timestamp = board.game_end_timestamp.checked_add(extra_seconds).unwrap();
+        emit!(GameExtendedEvent {
+            game_id,
+            extra_seconds,
+            game_end_timestamp: board.game_end_timestamp,
+        });
+        Ok(())
+    }
+
     pub fn end_game_session<'info>(
         ctx: Context<'_, '_, '_, 'info, EndGameSession<'info>>,
         game_id: u64,
     ) -> Result<()> {
         msg!("Ending game session for game_id: {}", game_id);
-        let board = &ctx.accounts.board_account;
+        let board = &mut ctx.accounts.board_account;
+        if board.is_active {
+            settle_king_occupancy(board)?;
+            award_evasion(board)?;
+        }
+        board.delegation_state = DelegationState::CommitPending;
         board.exit(&crate::ID)?;
         commit_and_undelegate_accounts(
             &ctx.accounts.treasury.to_account_info(),
@@ -404,307 +1198,5146 @@ pub mod king_tiles {
             &ctx.accounts.magic_program,
         )?;
         emit!(UndelegateAndCommitEvent {
-            player: ctx.accounts.treasury.key().clone(),
+            player: ctx.accounts.treasury.key(),
             game_id: board.game_id,
         });
         Ok(())
     }
 
-    pub fn distribute_rewards<'info>(
-        ctx: Context<'_, '_, '_, 'info, DistributeRewards<'info>>,
-        game_id: u64,
-    ) -> Result<()> {
-        msg!("Distributing rewards for game_id: {}", game_id);
+    /// Commits the delegated board to the base layer mid-game without undelegating, so a dead
+    /// ER validator costs at most the time since the last checkpoint.
+    pub fn checkpoint_board(ctx: Context<CheckpointBoard>, game_id: u64) -> Result<()> {
         let board = &mut ctx.accounts.board_account;
-        let clock = Clock::get()?;
-        require!(
-            clock.unix_timestamp >= board.game_end_timestamp,
-            KingTilesError::GameNotOver
-        );
-        board.is_active = false;
-
-        for i in 0..(board.players_count as usize) {
-            let player = &board.players[i];
-            let player_account_info = ctx.remaining_accounts[i].clone();
-            require_keys_eq!(player_account_info.key(), player.player);
-
-            let reward = player.score.checked_mul(board.lamports_per_score).unwrap();
-            if reward == 0 {
-                continue;
-            }
-            let transfer_ix = anchor_lang::system_program::Transfer {
-                from: ctx.accounts.treasury.to_account_info(),
-                to: player_account_info,
-            };
-            anchor_lang::system_program::transfer(
-                CpiContext::new(ctx.accounts.system_program.to_account_info(), transfer_ix),
-                reward,
-            )?;
-        }
+        require!(board.is_active, KingTilesError::GameNotActive);
+        settle_king_occupancy(board)?;
+        board.last_checkpoint_timestamp = current_timestamp()?;
+        board.exit(&crate::ID)?;
+        commit_accounts(
+            &ctx.accounts.treasury.to_account_info(),
+            vec![&board.to_account_info()],
+            &ctx.accounts.magic_context,
+            &ctx.accounts.magic_program,
+        )?;
+        emit!(BoardCheckpointEvent {
+            game_id,
+            timestamp: board.last_checkpoint_timestamp,
+        });
         Ok(())
     }
 
-    pub fn update_player_score(ctx: Context<UpdatePlayerScore>, game_id: u64) -> Result<()> {
-        let _ = game_id;
+    /// First half of a validator failover: freezes the round, records the backup validator,
+    /// and commits and undelegates the board from the current ER.
+    pub fn redelegate_board(
+        ctx: Context<RedelegateBoard>,
+        game_id: u64,
+        backup_validator: Pubkey,
+    ) -> Result<()> {
+        msg!(
+            "Redelegating board for game_id: {} to {}",
+            game_id,
+            backup_validator
+        );
+        require!(
+            backup_validator != Pubkey::default(),
+            KingTilesError::InvalidValidator
+        );
         let board = &mut ctx.accounts.board_account;
-        let king_current_position = board.king_current_position;
-        let player_id_on_king_position = board.board[king_current_position as usize] as u8;
-        if (1..=board.players_count).contains(&player_id_on_king_position) {
-            let player_index = player_id_to_index(player_id_on_king_position);
-            board.players[player_index].score =
-                board.players[player_index].score.checked_add(1).unwrap();
+        if board.is_live(current_timestamp()?) {
+            settle_king_occupancy(board)?;
+            board.paused_at = current_timestamp()?;
+            emit!(GamePausedEvent {
+                game_id,
+                paused_at: board.paused_at,
+            });
         }
+        board.pending_validator = backup_validator;
+        board.delegation_state = DelegationState::CommitPending;
+        board.exit(&crate::ID)?;
+        commit_and_undelegate_accounts(
+            &ctx.accounts.treasury.to_account_info(),
+            vec![&board.to_account_info()],
+            &ctx.accounts.magic_context,
+            &ctx.accounts.magic_program,
+        )?;
         Ok(())
     }
 
-    pub fn close_board(ctx: Context<CloseBoard>, game_id: u64) -> Result<()> {
-        let _ = ctx;
-        msg!("Closing board for game_id: {}", game_id);
-        Ok(())
-    }
-
-    pub fn use_power(
-        ctx: Context<UsePower>,
-        game_id: u64,
-        player_id: u8,
-        direction: Direction,
-    ) -> Result<()> {
-        let _ = game_id;
+    /// Second half of a failover, on the base layer once the board is back: delegates it to
+    /// the recorded backup validator. The round stays paused until `resume_game` on the new ER.
+    pub fn complete_redelegation(ctx: Context<CompleteRedelegation>, game_id: u64) -> Result<()> {
         let board = &mut ctx.accounts.board_account;
-        let player_index = player_id_to_index(player_id);
-        require!(
-            player_index < board.players_count as usize,
-            KingTilesError::NotPlayer
-        );
+        let validator = board.pending_validator;
         require!(
-            board.players[player_index].powerup_score > 0,
-            KingTilesError::NoPowerup
+            validator != Pubkey::default(),
+            KingTilesError::InvalidValidator
         );
-        let power_use_direction = direction.offset(board.board_side_len);
+        board.pending_validator = Pubkey::default();
+        board.mark_delegated(current_timestamp()?);
+        board.exit(&crate::ID)?;
+        ctx.accounts.delegate_pda(
+            &ctx.accounts.treasury_signer,
+            &[
+                b"board",
+                ctx.accounts.treasury_signer.key().as_ref(),
+                &game_id.to_le_bytes(),
+            ],
+            DelegateConfig {
+                validator: Some(validator),
+                ..Default::default()
+            },
+        )?;
+        emit!(BoardRedelegatedEvent { game_id, validator });
+        Ok(())
+    }
+
+    /// Admin escape hatch for a wedged session: cuts the round short at the last settled
+    /// moment, clears pending VRF and pause state, then commits and undelegates the board so
+    /// `distribute_rewards` can run on the base layer. Works without the treasury key, but
+    /// needs a reachable validator; `refund_stranded_board` covers one that is gone.
+    pub fn force_recover_board(ctx: Context<ForceRecoverBoard>, game_id: u64) -> Result<()> {
+        msg!("Force-recovering board for game_id: {}", game_id);
+        let board = &mut ctx.accounts.board_account;
+        if board.game_end_timestamp > 0 {
+            let cutoff = if board.paused_at > 0 {
+                board.paused_at
+            } else {
+                settle_king_occupancy(board)?;
+                current_timestamp()?
+            };
+            board.game_end_timestamp = board.game_end_timestamp.min(cutoff);
+        }
+        board.paused_at = 0;
+        board.vrf_pending = 0;
+        board.delegation_state = DelegationState::CommitPending;
+        board.exit(&crate::ID)?;
+        commit_and_undelegate_accounts(
+            &ctx.accounts.admin.to_account_info(),
+            vec![&board.to_account_info()],
+            &ctx.accounts.magic_context,
+            &ctx.accounts.magic_program,
+        )?;
+        emit!(BoardForceRecoveredEvent {
+            game_id,
+            admin: ctx.accounts.admin.key(),
+            game_end_timestamp: board.game_end_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Base-layer fallback for when the validator is gone for good. The delegation program
+    /// only undelegates through the validator, so the board stays stranded. Still, once its
+    /// round has been over for `STRANDED_BOARD_GRACE_SECONDS`, this refunds every seat's
+    /// registration fee from the treasury vault and any wager stake from its escrow. Players
+    /// are remaining accounts, all of them in join order. The round's `StrandedRefund` stops
+    /// `distribute_rewards` from paying it again if the board ever comes back.
+    pub fn refund_stranded_board<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RefundStrandedBoard<'info>>,
+        game_id: u64,
+        round: u32,
+    ) -> Result<()> {
+        msg!("Refunding stranded board for game_id: {}", game_id);
+        let board_info = &ctx.accounts.board_account;
+        require_keys_eq!(
+            *board_info.owner,
+            ephemeral_rollups_sdk::id(),
+            KingTilesError::BoardNotStranded
+        );
+        // The delegation program holds the last state the validator committed.
+        let board = Board::try_deserialize(&mut &board_info.try_borrow_data()?[..])?;
+        require!(board.round == round, KingTilesError::BoardNotStranded);
+        require!(board.game_end_timestamp > 0, KingTilesError::GameNotStarted);
+        let now = current_timestamp()?;
+        require!(
+            now >= board
+                .game_end_timestamp
+                .checked_add(STRANDED_BOARD_GRACE_SECONDS)
+                .unwrap(),
+            KingTilesError::BoardNotStranded
+        );
+        let players_count = board.players_count as usize;
+        require!(
+            ctx.remaining_accounts.len() == players_count,
+            KingTilesError::InvalidPayoutPage
+        );
+        let mut wager_escrow = None;
+        if board.options.wager_lamports > 0 {
+            let escrow = ctx
+                .accounts
+                .wager_escrow
+                .as_mut()
+                .ok_or(KingTilesError::WagerEscrowMissing)?;
+            if !escrow.settled {
+                wager_escrow = Some(escrow);
+            }
+        }
+
+        let mut refunded_lamports: u64 = 0;
+        for (i, player) in board.players[..players_count].iter().enumerate() {
+            let player_info = &ctx.remaining_accounts[i];
+            require_keys_eq!(
+                player_info.key(),
+                player.player,
+                KingTilesError::InvalidPayoutAccount
+            );
+            if let Some(escrow) = wager_escrow.as_ref() {
+                escrow.to_account_info().sub_lamports(escrow.stakes[i])?;
+                player_info.add_lamports(escrow.stakes[i])?;
+            }
+            if !board.options.free_play {
+                let fee = board.registration_fee_for_seat(i as u8);
+                pay_from_treasury_vault(&mut ctx.accounts.treasury_vault, player_info, fee)?;
+                refunded_lamports = refunded_lamports.checked_add(fee).unwrap();
+            }
+        }
+        if let Some(escrow) = wager_escrow {
+            escrow.settled = true;
+        }
+
+        let record = &mut ctx.accounts.stranded_refund;
+        record.game_id = game_id;
+        record.round = round;
+        record.refunded_lamports = refunded_lamports;
+        record.refunded_at = now;
+        emit!(StrandedBoardRefundedEvent {
+            game_id,
+            round,
+            admin: ctx.accounts.admin.key(),
+            refunded_lamports,
+        });
+        Ok(())
+    }
+
+    pub fn distribute_rewards<'info>(
+        ctx: Context<'_, '_, '_, 'info, DistributeRewards<'info>>,
+        game_id: u64,
+    ) -> Result<()> {
+        msg!("Distributing rewards for game_id: {}", game_id);
+        require!(
+            ctx.accounts.stranded_refund.data_is_empty(),
+            KingTilesError::RoundRefunded
+        );
+        let board = &mut ctx.accounts.board_account;
+        let clock = Clock::get()?;
+        // A lobby that never started has no round to pay out.
+        require!(board.game_end_timestamp > 0, KingTilesError::GameNotStarted);
+        require!(
+            clock.unix_timestamp >= board.game_end_timestamp,
+            KingTilesError::GameNotOver
+        );
+        require!(board.paused_at == 0, KingTilesError::GamePaused);
+        // Only runs on the base layer, so any commit has landed by now.
+        board.delegation_state = DelegationState::Undelegated;
+        settle_king_occupancy(board)?;
+        board.is_active = false;
+
+        // Remaining accounts are the next page of players, in join order, starting at
+        // `next_payout_index`; calls after the last page pay nothing.
+        let start = board.next_payout_index as usize;
+        let players_count = board.players_count as usize;
+        let end = start.checked_add(ctx.remaining_accounts.len()).unwrap();
+        require!(
+            end <= players_count && (end > start || start == players_count),
+            KingTilesError::InvalidPayoutPage
+        );
+        for (offset, account) in ctx.remaining_accounts.iter().enumerate() {
+            require!(
+                account.owner == &anchor_lang::system_program::ID,
+                KingTilesError::InvalidPayoutAccount
+            );
+            require!(
+                !ctx.remaining_accounts[..offset]
+                    .iter()
+                    .any(|earlier| earlier.key == account.key),
+                KingTilesError::DuplicatePayoutAccount
+            );
+        }
+
+        let players = &board.players[..players_count];
+        let top_score = players.iter().map(|p| p.score).max().unwrap_or(0);
+        let wager_winner =
+            if players.len() == 2 && players.iter().filter(|p| p.score == top_score).count() == 1 {
+                players.iter().position(|p| p.score == top_score)
+            } else {
+                None
+            };
+        let mut wager_escrow = None;
+        if board.options.wager_lamports > 0 {
+            let escrow = ctx
+                .accounts
+                .wager_escrow
+                .as_mut()
+                .ok_or(KingTilesError::WagerEscrowMissing)?;
+            if !escrow.settled && escrow.total_staked > 0 {
+                wager_escrow = Some(escrow);
+            }
+        }
+        let wager_rake = wager_escrow
+            .as_ref()
+            .map(|escrow| {
+                escrow
+                    .total_staked
+                    .checked_mul(board.options.wager_rake_bps as u64)
+                    .unwrap()
+                    .checked_div(MAX_BPS as u64)
+                    .unwrap()
+            })
+            .unwrap_or(0);
+
+        let mut sponsor_vault = None;
+        if board.options.free_play {
+            let vault = ctx
+                .accounts
+                .sponsor_vault
+                .as_mut()
+                .ok_or(KingTilesError::InsufficientSponsorFunds)?;
+            let outstanding = players[start..]
+                .iter()
+                .map(|player| board.reward_for(player.score))
+                .fold(0u64, |acc, reward| acc.checked_add(reward).unwrap());
+            let vault_info = vault.to_account_info();
+            let rent_floor = Rent::get()?.minimum_balance(vault_info.data_len());
+            require!(
+                vault_info.lamports().saturating_sub(rent_floor) >= outstanding,
+                KingTilesError::InsufficientSponsorFunds
+            );
+            sponsor_vault = Some(vault);
+        }
+
+        for i in start..end {
+            let player = board.players[i];
+            let player_account_info = &ctx.remaining_accounts[i.checked_sub(start).unwrap()];
+            require_keys_eq!(player_account_info.key(), player.player);
+
+            if board.jackpot_winner == player.id {
+                let jackpot_vault = ctx
+                    .accounts
+                    .jackpot_vault
+                    .as_mut()
+                    .ok_or(KingTilesError::JackpotVaultMissing)?;
+                pay_jackpot(jackpot_vault, player_account_info, board.game_id)?;
+                board.jackpot_winner = 0;
+            }
+
+            if let Some(escrow) = wager_escrow.as_ref() {
+                let share = match wager_winner {
+                    Some(winner_index) if winner_index == i => {
+                        escrow.total_staked.checked_sub(wager_rake).unwrap()
+                    }
+                    Some(_) => 0,
+                    None => escrow.stakes[i],
+                };
+                if share > 0 {
+                    escrow.to_account_info().sub_lamports(share)?;
+                    player_account_info.add_lamports(share)?;
+                }
+            }
+
+            let reward = board.reward_for(player.score);
+            if reward == 0 {
+                continue;
+            }
+            if let Some(vault) = sponsor_vault.as_mut() {
+                vault.to_account_info().sub_lamports(reward)?;
+                player_account_info.add_lamports(reward)?;
+                vault.paid_lamports = vault.paid_lamports.checked_add(reward).unwrap();
+            } else {
+                pay_from_treasury_vault(
+                    &mut ctx.accounts.treasury_vault,
+                    player_account_info,
+                    reward,
+                )?;
+            }
+            board.rewards_paid_lamports = board.rewards_paid_lamports.checked_add(reward).unwrap();
+            emit!(RewardPaidEvent {
+                player: player.player,
+                game_id: board.game_id,
+                score: player.score,
+                lamports: reward,
+            });
+        }
+        board.next_payout_index = end as u8;
+
+        if end == players_count && start < players_count {
+            emit!(DistributionCompleteEvent {
+                game_id: board.game_id,
+                players_paid: board.players_count,
+                total_lamports: board.rewards_paid_lamports,
+            });
+        }
+        if end == players_count {
+            board.rewards_distributed = true;
+            if let Some(escrow) = wager_escrow {
+                match wager_winner {
+                    Some(winner_index) => {
+                        escrow.to_account_info().sub_lamports(wager_rake)?;
+                        let treasury_vault = &mut ctx.accounts.treasury_vault;
+                        treasury_vault.add_lamports(wager_rake)?;
+                        treasury_vault.total_deposited_lamports = treasury_vault
+                            .total_deposited_lamports
+                            .checked_add(wager_rake)
+                            .unwrap();
+                        emit!(WagerSettledEvent {
+                            game_id: board.game_id,
+                            winner: board.players[winner_index].player,
+                            payout: escrow.total_staked.checked_sub(wager_rake).unwrap(),
+                            rake: wager_rake,
+                        });
+                    }
+                    None => emit!(WagerRefundedEvent {
+                        game_id: board.game_id,
+                        lamports: escrow.total_staked,
+                    }),
+                }
+                escrow.settled = true;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the finished round's `GameSummary` once every page of `distribute_rewards` has
+    /// paid out; the summary PDA is per round, so this succeeds once per round.
+    pub fn record_game_summary(ctx: Context<RecordGameSummary>, game_id: u64) -> Result<()> {
+        msg!("Recording game summary for game_id: {}", game_id);
+        let board = &ctx.accounts.board_account;
+        require!(
+            !board.is_active && board.game_end_timestamp > 0,
+            KingTilesError::GameNotOver
+        );
+        require!(board.rewards_distributed, KingTilesError::RewardsPending);
+        write_game_summary(&mut ctx.accounts.game_summary, board)
+    }
+
+    /// Tops up the reward pool of a free-play game; anyone can sponsor.
+    pub fn fund_sponsor_vault(
+        ctx: Context<FundSponsorVault>,
+        game_id: u64,
+        lamports: u64,
+    ) -> Result<()> {
+        msg!(
+            "Funding sponsor vault for game_id {} with {} lamports",
+            game_id,
+            lamports
+        );
+        let vault = &mut ctx.accounts.sponsor_vault;
+        vault.game_id = game_id;
+        vault.sponsored_lamports = vault.sponsored_lamports.checked_add(lamports).unwrap();
+        let transfer_ix = anchor_lang::system_program::Transfer {
+            from: ctx.accounts.sponsor.to_account_info(),
+            to: vault.to_account_info(),
+        };
+        anchor_lang::system_program::transfer(
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), transfer_ix),
+            lamports,
+        )?;
+        emit!(SponsorVaultFundedEvent {
+            game_id,
+            sponsor: ctx.accounts.sponsor.key(),
+            lamports,
+        });
+        Ok(())
+    }
+
+    /// Mints a 1/1 Metaplex trophy NFT to the round's top scorer (first in join order on ties).
+    /// The `trophy_authority` PDA is the mint, freeze, and update authority and the verified creator.
+    pub fn mint_trophy(ctx: Context<MintTrophy>, game_id: u64, uri: String) -> Result<()> {
+        msg!("Minting trophy for game_id: {}", game_id);
+        let board = &mut ctx.accounts.board_account;
+        require!(
+            !board.is_active && board.game_end_timestamp > 0,
+            KingTilesError::GameNotOver
+        );
+        require!(!board.trophy_minted, KingTilesError::TrophyAlreadyMinted);
+        require!(
+            uri.len() <= MAX_TROPHY_URI_LEN,
+            KingTilesError::InvalidTrophyUri
+        );
+        let winner = board.players[..board.players_count as usize]
+            .iter()
+            .fold(None::<Player>, |best, player| match best {
+                Some(best) if best.score >= player.score => Some(best),
+                _ => Some(*player),
+            })
+            .ok_or(KingTilesError::NotPlayer)?;
+        require_keys_eq!(ctx.accounts.winner.key(), winner.player);
+        let score = winner.score;
+        board.trophy_minted = true;
+
+        let mint = ctx.accounts.trophy_mint.key();
+        let authority = ctx.accounts.trophy_authority.key();
+        let payer = ctx.accounts.treasury.key();
+        let game_id_bytes = game_id.to_le_bytes();
+        let game_end_bytes = board.game_end_timestamp.to_le_bytes();
+        let mint_seeds: &[&[u8]] = &[
+            b"trophy",
+            &game_id_bytes,
+            &game_end_bytes,
+            &[ctx.bumps.trophy_mint],
+        ];
+        let authority_seeds: &[&[u8]] = &[b"trophy_authority", &[ctx.bumps.trophy_authority]];
+
+        anchor_lang::system_program::create_account(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::CreateAccount {
+                    from: ctx.accounts.treasury.to_account_info(),
+                    to: ctx.accounts.trophy_mint.to_account_info(),
+                },
+                &[mint_seeds],
+            ),
+            Rent::get()?.minimum_balance(SPL_MINT_LEN),
+            SPL_MINT_LEN as u64,
+            &SPL_TOKEN_PROGRAM_ID,
+        )?;
+        invoke(
+            &initialize_mint_ix(&mint, &authority),
+            &[
+                ctx.accounts.trophy_mint.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+            ],
+        )?;
+        invoke(
+            &create_associated_token_account_ix(&payer, &winner.player, &mint),
+            &[
+                ctx.accounts.treasury.to_account_info(),
+                ctx.accounts.winner_token_account.to_account_info(),
+                ctx.accounts.winner.to_account_info(),
+                ctx.accounts.trophy_mint.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.associated_token_program.to_account_info(),
+            ],
+        )?;
+        invoke_signed(
+            &mint_one_ix(&mint, &ctx.accounts.winner_token_account.key(), &authority),
+            &[
+                ctx.accounts.trophy_mint.to_account_info(),
+                ctx.accounts.winner_token_account.to_account_info(),
+                ctx.accounts.trophy_authority.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+            ],
+            &[authority_seeds],
+        )?;
+        invoke_signed(
+            &create_metadata_ix(
+                &mint,
+                &authority,
+                &payer,
+                &format!("King Tiles #{}", game_id),
+                &uri,
+            ),
+            &[
+                ctx.accounts.metadata.to_account_info(),
+                ctx.accounts.trophy_mint.to_account_info(),
+                ctx.accounts.trophy_authority.to_account_info(),
+                ctx.accounts.treasury.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.token_metadata_program.to_account_info(),
+            ],
+            &[authority_seeds],
+        )?;
+        invoke_signed(
+            &create_master_edition_ix(&mint, &authority, &payer),
+            &[
+                ctx.accounts.master_edition.to_account_info(),
+                ctx.accounts.trophy_mint.to_account_info(),
+                ctx.accounts.trophy_authority.to_account_info(),
+                ctx.accounts.treasury.to_account_info(),
+                ctx.accounts.metadata.to_account_info(),
+                ctx.accounts.token_program.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.token_metadata_program.to_account_info(),
+            ],
+            &[authority_seeds],
+        )?;
+        emit!(TrophyMintedEvent {
+            game_id,
+            player: winner.player,
+            mint,
+            score,
+            minted_at: current_timestamp()?,
+        });
+        Ok(())
+    }
+
+    /// Mints `config.xp_per_score` XP per point of final score to every player with a nonzero
+    /// score, once per round after `distribute_rewards`. Remaining accounts are each player's
+    /// wallet followed by their associated token account for the XP mint, in join order; the
+    /// token accounts are created if missing.
+    pub fn mint_xp<'info>(
+        ctx: Context<'_, '_, 'info, 'info, MintXp<'info>>,
+        game_id: u64,
+    ) -> Result<()> {
+        msg!("Minting XP for game_id: {}", game_id);
+        let xp_per_score = ctx.accounts.config.xp_per_score;
+        require!(xp_per_score > 0, KingTilesError::XpDisabled);
+        let board = &mut ctx.accounts.board_account;
+        require!(board.rewards_distributed, KingTilesError::GameNotOver);
+        require!(!board.xp_minted, KingTilesError::XpAlreadyMinted);
+        require!(
+            ctx.remaining_accounts.len() == 2 * board.players_count as usize,
+            KingTilesError::NotPlayer
+        );
+        board.xp_minted = true;
+
+        let mint = ctx.accounts.xp_mint.key();
+        let authority = ctx.accounts.xp_authority.key();
+        let payer = ctx.accounts.treasury.key();
+        let authority_seeds: &[&[u8]] = &[b"xp_authority", &[ctx.bumps.xp_authority]];
+        for (player, accounts) in board
+            .players
+            .iter()
+            .zip(ctx.remaining_accounts.chunks_exact(2))
+        {
+            let (wallet, token_account) = (&accounts[0], &accounts[1]);
+            require_keys_eq!(wallet.key(), player.player);
+            require_keys_eq!(
+                token_account.key(),
+                associated_token_address(&player.player, &mint)
+            );
+            let amount = player.score.checked_mul(xp_per_score).unwrap();
+            if amount == 0 {
+                continue;
+            }
+            invoke(
+                &create_associated_token_account_ix(&payer, &player.player, &mint),
+                &[
+                    ctx.accounts.treasury.to_account_info(),
+                    token_account.clone(),
+                    wallet.clone(),
+                    ctx.accounts.xp_mint.to_account_info(),
+                    ctx.accounts.system_program.to_account_info(),
+                    ctx.accounts.token_program.to_account_info(),
+                    ctx.accounts.associated_token_program.to_account_info(),
+                ],
+            )?;
+            invoke_signed(
+                &mint_to_ix(&mint, &token_account.key(), &authority, amount),
+                &[
+                    ctx.accounts.xp_mint.to_account_info(),
+                    token_account.clone(),
+                    ctx.accounts.xp_authority.to_account_info(),
+                    ctx.accounts.token_program.to_account_info(),
+                ],
+                &[authority_seeds],
+            )?;
+            emit!(XpMintedEvent {
+                game_id,
+                player: player.player,
+                amount,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn create_player_profile(ctx: Context<CreatePlayerProfile>) -> Result<()> {
+        msg!(
+            "Creating player profile for {}",
+            ctx.accounts.authority.key()
+        );
+        let profile = &mut ctx.accounts.profile;
+        profile.authority = ctx.accounts.authority.key();
+        profile.games_played = 0;
+        profile.wins = 0;
+        profile.total_score = 0;
+        profile.best_score = 0;
+        profile.created_at = current_timestamp()?;
+        profile.rating = DEFAULT_RATING;
+        profile.kings_captured = 0;
+        profile.bombs_hit = 0;
+        profile.achievements = 0;
+        profile.powerup_credits = [0; POWERUP_KIND_COUNT];
+        profile.display_name = String::new();
+        profile.metadata_uri = String::new();
+        Ok(())
+    }
+
+    /// Sets the profile's display name and avatar metadata URI, growing profiles created
+    /// before they existed at the owner's expense.
+    pub fn set_player_identity(
+        ctx: Context<SetPlayerIdentity>,
+        display_name: String,
+        metadata_uri: String,
+    ) -> Result<()> {
+        require!(
+            !display_name.is_empty()
+                && display_name.len() <= MAX_DISPLAY_NAME_LEN
+                && metadata_uri.len() <= MAX_METADATA_URI_LEN,
+            KingTilesError::InvalidPlayerIdentity
+        );
+        let info = ctx.accounts.profile.to_account_info();
+        grow_account(
+            &info,
+            &ctx.accounts.authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            8 + PlayerProfile::INIT_SPACE,
+        )?;
+        let mut profile = PlayerProfile::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require_keys_eq!(
+            profile.authority,
+            ctx.accounts.authority.key(),
+            KingTilesError::NotPlayer
+        );
+        profile.display_name = display_name;
+        profile.metadata_uri = metadata_uri;
+        profile.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+        emit!(PlayerIdentitySetEvent {
+            player: profile.authority,
+            display_name: profile.display_name,
+            metadata_uri: profile.metadata_uri,
+        });
+        Ok(())
+    }
+
+    /// Folds a finished game into the profiles passed as remaining accounts. Players without
+    /// a profile are simply left out; every top scorer is credited with a win. A powerup still
+    /// held at the end is banked as a credit.
+    pub fn update_player_profiles<'info>(
+        ctx: Context<'_, '_, 'info, 'info, UpdatePlayerProfiles<'info>>,
+        game_id: u64,
+    ) -> Result<()> {
+        msg!("Updating player profiles for game_id: {}", game_id);
+        let board = &mut ctx.accounts.board_account;
+        require!(
+            !board.is_active && board.game_end_timestamp > 0,
+            KingTilesError::GameNotOver
+        );
+        let top_score = board.players.iter().map(|p| p.score).max().unwrap_or(0);
+        for profile_info in ctx.remaining_accounts.iter() {
+            grow_account(
+                profile_info,
+                &ctx.accounts.treasury.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                8 + PlayerProfile::INIT_SPACE,
+            )?;
+            let mut profile: Account<PlayerProfile> = Account::try_from(profile_info)?;
+            let (expected, _) =
+                Pubkey::find_program_address(&[b"profile", profile.authority.as_ref()], &crate::ID);
+            require_keys_eq!(profile_info.key(), expected);
+            let player_index = board
+                .players
+                .iter()
+                .position(|p| p.player == profile.authority)
+                .ok_or(KingTilesError::NotPlayer)?;
+            let bit = 1u8 << player_index;
+            require!(
+                board.profiles_recorded_mask & bit == 0,
+                KingTilesError::ProfilesAlreadyRecorded
+            );
+            board.profiles_recorded_mask |= bit;
+
+            let player = &board.players[player_index];
+            let score = player.score;
+            profile.games_played = profile.games_played.checked_add(1).unwrap();
+            profile.total_score = profile.total_score.checked_add(score).unwrap();
+            profile.best_score = profile.best_score.max(score);
+            profile.kings_captured = profile
+                .kings_captured
+                .checked_add(player.kings_captured)
+                .unwrap();
+            profile.bombs_hit = profile.bombs_hit.checked_add(player.bombs_hit).unwrap();
+            if top_score > 0 && score == top_score {
+                profile.wins = profile.wins.checked_add(1).unwrap();
+            }
+            if let Some(kind) = player.powerup {
+                if !player.powerup_expired(board.game_end_timestamp) {
+                    let credits = profile.powerup_credits[kind.index()]
+                        .saturating_add(1)
+                        .min(MAX_POWERUP_CREDITS);
+                    profile.powerup_credits[kind.index()] = credits;
+                    emit!(PowerupCreditEarnedEvent {
+                        player: profile.authority,
+                        game_id: board.game_id,
+                        kind,
+                        credits,
+                    });
+                }
+            }
+            profile.exit(&crate::ID)?;
+            emit!(PlayerProfileUpdatedEvent {
+                player: profile.authority,
+                game_id: board.game_id,
+                games_played: profile.games_played,
+                wins: profile.wins,
+                total_score: profile.total_score,
+            });
+        }
+        Ok(())
+    }
+
+    /// Debits one banked credit of `kind` from the caller's profile into a reservation for
+    /// round `round` of the game. Runs on the base layer, where the profile lives; a
+    /// reservation that is never redeemed is forfeited.
+    pub fn reserve_powerup_credit(
+        ctx: Context<ReservePowerupCredit>,
+        game_id: u64,
+        round: u32,
+        kind: PowerupKind,
+    ) -> Result<()> {
+        let profile = &mut ctx.accounts.profile;
+        let credits = profile.powerup_credits[kind.index()];
+        require!(credits > 0, KingTilesError::NoPowerupCredit);
+        profile.powerup_credits[kind.index()] = credits.checked_sub(1).unwrap();
+        let reservation = &mut ctx.accounts.reservation;
+        reservation.player = ctx.accounts.payer.key();
+        reservation.game_id = game_id;
+        reservation.round = round;
+        reservation.kind = kind;
+        emit!(PowerupCreditReservedEvent {
+            player: reservation.player,
+            game_id,
+            round,
+            kind,
+            credits: profile.powerup_credits[kind.index()],
+        });
+        Ok(())
+    }
+
+    /// Returns a reservation's rent to its owner. The credit itself is not refunded, since
+    /// the base layer cannot tell whether it was already redeemed on the ER.
+    pub fn close_credit_reservation(
+        _ctx: Context<CloseCreditReservation>,
+        game_id: u64,
+        round: u32,
+    ) -> Result<()> {
+        let _ = (game_id, round);
+        Ok(())
+    }
+
+    /// Starts the round holding the powerup reserved for it by `reserve_powerup_credit`.
+    /// Warmup only, one credit per player per round.
+    pub fn redeem_powerup_credit(
+        ctx: Context<RedeemPowerupCredit>,
+        game_id: u64,
+        player_id: u8,
+    ) -> Result<()> {
+        let _ = game_id;
+        let kind = ctx.accounts.reservation.kind;
+        let board = &mut ctx.accounts.board_account;
+        let now = current_timestamp()?;
+        require!(board.is_active, KingTilesError::GameNotStarted);
+        require!(board.in_warmup(now), KingTilesError::GameAlreadyStarted);
+        let player_index = player_id_to_index(player_id);
+        require!(
+            player_index < board.players_count as usize,
+            KingTilesError::NotPlayer
+        );
+        require!(
+            board.players[player_index].player == ctx.accounts.payer.key(),
+            KingTilesError::NotPlayer
+        );
+        require!(
+            board.redeemed_credits[player_index].is_none()
+                && board.players[player_index].powerup.is_none(),
+            KingTilesError::PowerupCreditUnavailable
+        );
+        board.redeemed_credits[player_index] = Some(kind);
+        board.players[player_index].powerup = Some(kind);
+        // The powerup cannot be used during warmup, so its lifetime starts when warmup ends.
+        let ttl = board.options.powerup_ttl_seconds;
+        board.players[player_index].powerup_expires_at = if ttl > 0 {
+            board.warmup_ends_at.checked_add(ttl).unwrap()
+        } else {
+            0
+        };
+        emit!(PowerupCreditRedeemedEvent {
+            player: ctx.accounts.payer.key(),
+            game_id: board.game_id,
+            kind,
+        });
+        Ok(())
+    }
+
+    /// Broadcasts a seated player's emote; touches nothing but the seat's cooldown.
+    pub fn send_emote(
+        ctx: Context<SendEmote>,
+        game_id: u64,
+        player_id: u8,
+        emote_id: u8,
+    ) -> Result<()> {
+        let board = &mut ctx.accounts.board_account;
+        let now = current_timestamp()?;
+        require!(emote_id < EMOTE_COUNT, KingTilesError::InvalidEmote);
+        let player_index = player_id_to_index(player_id);
+        require!(
+            player_index < board.players_count as usize,
+            KingTilesError::NotPlayer
+        );
+        require!(
+            board.players[player_index].player == ctx.accounts.payer.key(),
+            KingTilesError::NotPlayer
+        );
+        require!(
+            now >= board.last_emote_at[player_index]
+                .checked_add(EMOTE_COOLDOWN_SECONDS)
+                .unwrap(),
+            KingTilesError::EmoteCooldown
+        );
+        board.last_emote_at[player_index] = now;
+        emit!(EmoteEvent {
+            game_id,
+            player_id,
+            emote_id,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    pub fn create_achievement(
+        ctx: Context<CreateAchievement>,
+        achievement_id: u8,
+        kind: AchievementKind,
+        threshold: u64,
+        name: String,
+    ) -> Result<()> {
+        msg!("Creating achievement {}", achievement_id);
+        require!(
+            achievement_id < MAX_ACHIEVEMENTS
+                && threshold > 0
+                && !name.is_empty()
+                && name.len() <= MAX_ACHIEVEMENT_NAME_LEN,
+            KingTilesError::InvalidAchievement
+        );
+        let achievement = &mut ctx.accounts.achievement;
+        achievement.id = achievement_id;
+        achievement.kind = kind;
+        achievement.threshold = threshold;
+        achievement.name = name;
+        Ok(())
+    }
+
+    /// Permissionless: unlocks an achievement on a profile whose stats meet its condition.
+    pub fn award_achievement(
+        ctx: Context<AwardAchievement>,
+        achievement_id: u8,
+        player: Pubkey,
+    ) -> Result<()> {
+        let achievement = &ctx.accounts.achievement;
+        let profile = &mut ctx.accounts.profile;
+        let bit = 1u64 << achievement_id;
+        require!(
+            profile.achievements & bit == 0,
+            KingTilesError::AchievementAlreadyUnlocked
+        );
+        require!(
+            achievement.is_earned(profile),
+            KingTilesError::AchievementNotEarned
+        );
+        profile.achievements |= bit;
+        emit!(AchievementUnlockedEvent {
+            player,
+            achievement_id,
+            name: achievement.name.clone(),
+        });
+        Ok(())
+    }
+
+    /// Applies Elo deltas from the final standings to the profiles passed as remaining
+    /// accounts. Only players with a profile are rated, against the others in the same call;
+    /// a seat already settled this round is rejected, so a player whose profile was missing
+    /// can still be rated later.
+    pub fn settle_ratings<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleRatings<'info>>,
+        game_id: u64,
+    ) -> Result<()> {
+        msg!("Settling ratings for game_id: {}", game_id);
+        let board = &mut ctx.accounts.board_account;
+        require!(
+            !board.is_active && board.game_end_timestamp > 0,
+            KingTilesError::GameNotOver
+        );
+        require!(
+            ctx.remaining_accounts.len() <= MAX_PLAYERS,
+            KingTilesError::NotPlayer
+        );
+
+        let mut profiles: Vec<Account<PlayerProfile>> = Vec::new();
+        let mut ratings = Vec::new();
+        let mut scores = Vec::new();
+        for profile_info in ctx.remaining_accounts.iter() {
+            let profile: Account<PlayerProfile> = Account::try_from(profile_info)?;
+            let (expected, _) =
+                Pubkey::find_program_address(&[b"profile", profile.authority.as_ref()], &crate::ID);
+            require_keys_eq!(profile_info.key(), expected);
+            let player_index = board
+                .players
+                .iter()
+                .position(|p| p.player == profile.authority)
+                .ok_or(KingTilesError::NotPlayer)?;
+            let bit = 1u8 << player_index;
+            require!(
+                board.ratings_settled_mask & bit == 0,
+                KingTilesError::RatingsAlreadySettled
+            );
+            board.ratings_settled_mask |= bit;
+            ratings.push(profile.rating);
+            scores.push(board.players[player_index].score);
+            profiles.push(profile);
+        }
+
+        let deltas = rating_deltas(&ratings, &scores);
+        for (profile, delta) in profiles.iter_mut().zip(deltas) {
+            let old_rating = profile.rating;
+            profile.rating = (old_rating as i64).checked_add(delta).unwrap().max(0) as u32;
+            profile.exit(&crate::ID)?;
+            emit!(RatingUpdatedEvent {
+                player: profile.authority,
+                game_id: board.game_id,
+                old_rating,
+                new_rating: profile.rating,
+            });
+        }
+        Ok(())
+    }
+
+    pub fn create_jackpot_vault(ctx: Context<CreateJackpotVault>) -> Result<()> {
+        msg!("Creating jackpot vault");
+        let jackpot_vault = &mut ctx.accounts.jackpot_vault;
+        jackpot_vault.total_contributed_lamports = 0;
+        jackpot_vault.total_paid_lamports = 0;
+        jackpot_vault.last_winner = Pubkey::default();
+        jackpot_vault.last_game_id = 0;
+        Ok(())
+    }
+
+    pub fn start_season(
+        ctx: Context<StartSeason>,
+        season_id: u32,
+        start_timestamp: i64,
+        end_timestamp: i64,
+    ) -> Result<()> {
+        msg!("Starting season {}", season_id);
+        require!(
+            start_timestamp < end_timestamp,
+            KingTilesError::InvalidSeasonWindow
+        );
+        let season = &mut ctx.accounts.season;
+        season.season_id = season_id;
+        season.start_timestamp = start_timestamp;
+        season.end_timestamp = end_timestamp;
+        season.games_recorded = 0;
+        emit!(SeasonStartedEvent {
+            season_id,
+            start_timestamp,
+            end_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Anyone may top up a season's prize pool.
+    pub fn fund_season(ctx: Context<FundSeason>, season_id: u32, lamports: u64) -> Result<()> {
+        msg!("Funding season {} with {} lamports", season_id, lamports);
+        let transfer_ix = anchor_lang::system_program::Transfer {
+            from: ctx.accounts.funder.to_account_info(),
+            to: ctx.accounts.season.to_account_info(),
+        };
+        anchor_lang::system_program::transfer(
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), transfer_ix),
+            lamports,
+        )
+    }
+
+    /// Adds one player's final score from a finished game to their season entry.
+    pub fn record_season_result(
+        ctx: Context<RecordSeasonResult>,
+        season_id: u32,
+        game_id: u64,
+        player: Pubkey,
+    ) -> Result<()> {
+        msg!(
+            "Recording season {} result for game_id: {}",
+            season_id,
+            game_id
+        );
+        let board = &mut ctx.accounts.board_account;
+        let season = &mut ctx.accounts.season;
+        require!(
+            !board.is_active && board.game_end_timestamp > 0,
+            KingTilesError::GameNotOver
+        );
+        require!(
+            board.game_end_timestamp >= season.start_timestamp
+                && board.game_end_timestamp <= season.end_timestamp,
+            KingTilesError::InvalidSeasonWindow
+        );
+        let player_index = board
+            .players
+            .iter()
+            .position(|p| p.player == player)
+            .ok_or(KingTilesError::NotPlayer)?;
+        let bit = 1u8 << player_index;
+        require!(
+            board.season_recorded_mask & bit == 0,
+            KingTilesError::SeasonResultAlreadyRecorded
+        );
+        if board.season_recorded_mask == 0 {
+            season.games_recorded = season.games_recorded.checked_add(1).unwrap();
+        }
+        board.season_recorded_mask |= bit;
+
+        let points = board.players[player_index].score;
+        let entry = &mut ctx.accounts.season_entry;
+        entry.season_id = season_id;
+        entry.player = player;
+        entry.points = entry.points.checked_add(points).unwrap();
+        entry.games_played = entry.games_played.checked_add(1).unwrap();
+        emit!(SeasonResultRecordedEvent {
+            season_id,
+            game_id: board.game_id,
+            player,
+            points,
+            season_points: entry.points,
+        });
+        Ok(())
+    }
+
+    /// Treasury-gated payout from the season pool once the season window has closed.
+    pub fn pay_season_prize(
+        ctx: Context<PaySeasonPrize>,
+        season_id: u32,
+        lamports: u64,
+    ) -> Result<()> {
+        msg!("Paying season {} prize of {} lamports", season_id, lamports);
+        let season = &ctx.accounts.season;
+        require!(
+            current_timestamp()? > season.end_timestamp,
+            KingTilesError::SeasonNotOver
+        );
+        let entry = &mut ctx.accounts.season_entry;
+        let season_info = season.to_account_info();
+        let rent_floor = Rent::get()?.minimum_balance(season_info.data_len());
+        require!(
+            !entry.prize_paid
+                && lamports > 0
+                && season_info.lamports().saturating_sub(rent_floor) >= lamports,
+            KingTilesError::InvalidSeasonPrize
+        );
+        entry.prize_paid = true;
+        season_info.sub_lamports(lamports)?;
+        ctx.accounts.player.add_lamports(lamports)?;
+        emit!(SeasonPrizePaidEvent {
+            season_id,
+            player: entry.player,
+            lamports,
+        });
+        Ok(())
+    }
+
+    pub fn create_referral_account(ctx: Context<CreateReferralAccount>) -> Result<()> {
+        msg!(
+            "Creating referral account for {}",
+            ctx.accounts.referrer.key()
+        );
+        let referral = &mut ctx.accounts.referral_account;
+        referral.referrer = ctx.accounts.referrer.key();
+        referral.referrals = 0;
+        referral.credits_lamports = 0;
+        referral.claimed_lamports = 0;
+        referral.created_at = current_timestamp()?;
+        Ok(())
+    }
+
+    /// Pays out every referral credit accrued since the last claim.
+    pub fn claim_referral_rewards(ctx: Context<ClaimReferralRewards>) -> Result<()> {
+        let referral = &mut ctx.accounts.referral_account;
+        let lamports = referral.credits_lamports;
+        require!(lamports > 0, KingTilesError::NoReferralRewards);
+        referral.credits_lamports = 0;
+        referral.claimed_lamports = referral.claimed_lamports.checked_add(lamports).unwrap();
+        referral.sub_lamports(lamports)?;
+        ctx.accounts.referrer.add_lamports(lamports)?;
+        emit!(ReferralRewardsClaimedEvent {
+            referrer: referral.referrer,
+            lamports,
+        });
+        Ok(())
+    }
+
+    /// Daily crank: rolls a quest slot over to today with a new challenge and tops up its
+    /// reward vault from the treasury.
+    pub fn refresh_daily_quest(
+        ctx: Context<RefreshDailyQuest>,
+        quest_id: u8,
+        kind: QuestKind,
+        target: u64,
+        reward_lamports: u64,
+        budget_lamports: u64,
+    ) -> Result<()> {
+        require!(target > 0, KingTilesError::InvalidQuest);
+        let day = current_timestamp()?.checked_div(SECONDS_PER_DAY).unwrap();
+        msg!("Refreshing daily quest {} for day {}", quest_id, day);
+        let quest = &mut ctx.accounts.quest;
+        quest.quest_id = quest_id;
+        quest.day = day;
+        quest.kind = kind;
+        quest.target = target;
+        quest.reward_lamports = reward_lamports;
+        let transfer_ix = anchor_lang::system_program::Transfer {
+            from: ctx.accounts.treasury.to_account_info(),
+            to: quest.to_account_info(),
+        };
+        anchor_lang::system_program::transfer(
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), transfer_ix),
+            budget_lamports,
+        )?;
+        emit!(DailyQuestRefreshedEvent {
+            quest_id,
+            day,
+            target,
+            reward_lamports,
+        });
+        Ok(())
+    }
+
+    /// Counts one finished game towards a player's progress on today's quest.
+    pub fn record_quest_progress(
+        ctx: Context<RecordQuestProgress>,
+        quest_id: u8,
+        game_id: u64,
+        player: Pubkey,
+    ) -> Result<()> {
+        let board = &ctx.accounts.board_account;
+        let quest = &ctx.accounts.quest;
+        require!(
+            !board.is_active && board.game_end_timestamp > 0,
+            KingTilesError::GameNotOver
+        );
+        require!(
+            board
+                .game_end_timestamp
+                .checked_div(SECONDS_PER_DAY)
+                .unwrap()
+                == quest.day,
+            KingTilesError::QuestNotActive
+        );
+        let player_index = board
+            .players
+            .iter()
+            .position(|p| p.player == player)
+            .ok_or(KingTilesError::NotPlayer)?;
+        let progress = &mut ctx.accounts.quest_progress;
+        require!(
+            progress.last_game_id != game_id
+                || progress.last_game_end_timestamp != board.game_end_timestamp,
+            KingTilesError::QuestProgressAlreadyRecorded
+        );
+        if progress.day != quest.day {
+            progress.day = quest.day;
+            progress.progress = 0;
+            progress.claimed = false;
+        }
+        progress.quest_id = quest_id;
+        progress.player = player;
+        progress.last_game_id = game_id;
+        progress.last_game_end_timestamp = board.game_end_timestamp;
+        progress.progress = progress
+            .progress
+            .checked_add(quest.progress_from(board, player_index))
+            .unwrap();
+        emit!(QuestProgressEvent {
+            quest_id,
+            player,
+            progress: progress.progress,
+            target: quest.target,
+        });
+        Ok(())
+    }
+
+    /// Player-signed: pays today's reward from the quest vault once the target is met.
+    pub fn claim_quest_reward(ctx: Context<ClaimQuestReward>, quest_id: u8) -> Result<()> {
+        let quest = &ctx.accounts.quest;
+        let progress = &mut ctx.accounts.quest_progress;
+        require!(progress.day == quest.day, KingTilesError::QuestNotActive);
+        require!(!progress.claimed, KingTilesError::QuestAlreadyClaimed);
+        require!(
+            progress.progress >= quest.target,
+            KingTilesError::QuestNotComplete
+        );
+        let lamports = quest.reward_lamports;
+        let quest_info = quest.to_account_info();
+        let rent_floor = Rent::get()?.minimum_balance(quest_info.data_len());
+        require!(
+            quest_info.lamports().saturating_sub(rent_floor) >= lamports,
+            KingTilesError::QuestRewardUnavailable
+        );
+        progress.claimed = true;
+        quest_info.sub_lamports(lamports)?;
+        ctx.accounts.player.add_lamports(lamports)?;
+        emit!(QuestRewardClaimedEvent {
+            quest_id,
+            player: progress.player,
+            lamports,
+        });
+        Ok(())
+    }
+
+    /// Spectator wager on `on_player` (player id). Only open while the lobby is still filling,
+    /// so no bet can be placed once play, and therefore scoring, has begun.
+    pub fn place_bet(
+        ctx: Context<PlaceBet>,
+        game_id: u64,
+        on_player: u8,
+        amount: u64,
+    ) -> Result<()> {
+        let board = &ctx.accounts.board_account;
+        require!(
+            !board.is_active && board.game_end_timestamp == 0,
+            KingTilesError::BettingClosed
+        );
+        require!(
+            amount > 0 && on_player >= 1 && on_player <= board.players_count,
+            KingTilesError::InvalidBet
+        );
+        let pool = &mut ctx.accounts.betting_pool;
+        require!(!pool.settled, KingTilesError::BettingClosed);
+        let bet = &mut ctx.accounts.bet;
+        require!(
+            bet.amount == 0 || bet.on_player == on_player,
+            KingTilesError::InvalidBet
+        );
+        pool.game_id = game_id;
+        pool.round = board.round;
+        bet.game_id = game_id;
+        bet.round = board.round;
+        bet.bettor = ctx.accounts.bettor.key();
+        bet.on_player = on_player;
+        bet.amount = bet.amount.checked_add(amount).unwrap();
+        let slot = player_id_to_index(on_player);
+        pool.staked_on[slot] = pool.staked_on[slot].checked_add(amount).unwrap();
+        pool.total_staked = pool.total_staked.checked_add(amount).unwrap();
+
+        let transfer_ix = anchor_lang::system_program::Transfer {
+            from: ctx.accounts.bettor.to_account_info(),
+            to: pool.to_account_info(),
+        };
+        anchor_lang::system_program::transfer(
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), transfer_ix),
+            amount,
+        )?;
+        emit!(BetPlacedEvent {
+            game_id,
+            round: board.round,
+            bettor: bet.bettor,
+            on_player,
+            amount,
+            total_staked: pool.total_staked,
+        });
+        Ok(())
+    }
+
+    /// Permissionless once the round is over: fixes the winners from the final scores. A pool
+    /// from an earlier round, left unsettled when the board was reset or rematched, has lost
+    /// its scores and is refunded instead.
+    pub fn settle_bets(ctx: Context<SettleBets>, game_id: u64, round: u32) -> Result<()> {
+        let board = &ctx.accounts.board_account;
+        let pool = &mut ctx.accounts.betting_pool;
+        require!(!pool.settled, KingTilesError::BetsAlreadySettled);
+        let mut winner_mask = 0u8;
+        let mut winners_staked = 0u64;
+        if round == board.round {
+            require!(
+                board.game_end_timestamp > 0 && current_timestamp()? >= board.game_end_timestamp,
+                KingTilesError::GameNotOver
+            );
+            let players = &board.players[..board.players_count as usize];
+            let top_score = players.iter().map(|p| p.score).max().unwrap_or(0);
+            for (i, player) in players.iter().enumerate() {
+                if player.score == top_score {
+                    winner_mask |= 1 << i;
+                    winners_staked = winners_staked.checked_add(pool.staked_on[i]).unwrap();
+                }
+            }
+        }
+        pool.settled = true;
+        pool.winner_mask = winner_mask;
+        pool.winners_staked = winners_staked;
+        pool.refund = winners_staked == 0;
+        emit!(BetsSettledEvent {
+            game_id,
+            round,
+            winner_mask,
+            total_staked: pool.total_staked,
+            winners_staked,
+            refund: pool.refund,
+        });
+        Ok(())
+    }
+
+    /// Pays a winning bet its pro-rata share of the whole pool, or refunds it if nobody won.
+    pub fn claim_bet(ctx: Context<ClaimBet>, game_id: u64, round: u32) -> Result<()> {
+        let _ = round;
+        let pool = &ctx.accounts.betting_pool;
+        let bet = &mut ctx.accounts.bet;
+        require!(pool.settled, KingTilesError::BetsNotSettled);
+        require!(!bet.claimed, KingTilesError::BetAlreadyClaimed);
+        let lamports = if pool.refund {
+            bet.amount
+        } else {
+            let slot = player_id_to_index(bet.on_player);
+            require!(pool.winner_mask & (1 << slot) != 0, KingTilesError::BetLost);
+            u64::try_from(
+                (bet.amount as u128)
+                    .checked_mul(pool.total_staked as u128)
+                    .unwrap()
+                    .checked_div(pool.winners_staked as u128)
+                    .unwrap(),
+            )
+            .unwrap()
+        };
+        bet.claimed = true;
+        pool.to_account_info().sub_lamports(lamports)?;
+        ctx.accounts.bettor.add_lamports(lamports)?;
+        emit!(BetClaimedEvent {
+            game_id,
+            bettor: bet.bettor,
+            lamports,
+        });
+        Ok(())
+    }
+
+    /// Registers the caller as a spectator of an open lobby, paying `board.spectator_fee_lamports`
+    /// into the treasury vault. Spectating is tracked on the base layer, so the lobby is the only window.
+    pub fn join_as_spectator(ctx: Context<JoinAsSpectator>, game_id: u64) -> Result<()> {
+        let board = &mut ctx.accounts.board_account;
+        require!(
+            !board.is_active && board.game_end_timestamp == 0,
+            KingTilesError::SpectatingClosed
+        );
+        let fee_lamports = board.spectator_fee_lamports;
+        if fee_lamports > 0 {
+            collect_into_treasury_vault(
+                &mut ctx.accounts.treasury_vault,
+                &ctx.accounts.wallet.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                fee_lamports,
+            )?;
+        }
+        board.viewer_count = board.viewer_count.checked_add(1).unwrap();
+        let spectator = &mut ctx.accounts.spectator;
+        spectator.game_id = game_id;
+        spectator.wallet = ctx.accounts.wallet.key();
+        spectator.joined_at = current_timestamp()?;
+        spectator.fee_lamports = fee_lamports;
+        emit!(SpectatorJoinedEvent {
+            game_id,
+            spectator: spectator.wallet,
+            fee_lamports,
+            viewer_count: board.viewer_count,
+        });
+        Ok(())
+    }
+
+    /// Closes the caller's spectator account, returning its rent but not the fee. Only while
+    /// the board is on the base layer.
+    pub fn leave_spectator(ctx: Context<LeaveSpectator>, game_id: u64) -> Result<()> {
+        let board = &mut ctx.accounts.board_account;
+        board.viewer_count = board.viewer_count.saturating_sub(1);
+        emit!(SpectatorLeftEvent {
+            game_id,
+            spectator: ctx.accounts.wallet.key(),
+            viewer_count: board.viewer_count,
+        });
+        Ok(())
+    }
+
+    /// Restarts a finished board with the same players, optionally charging each of them
+    /// the registration fee again (players sign as remaining accounts, in join order).
+    pub fn rematch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Rematch<'info>>,
+        game_id: u64,
+        collect_fee: bool,
+    ) -> Result<()> {
+        msg!("Starting rematch for game_id: {}", game_id);
+        let board = &mut ctx.accounts.board_account;
+        require!(
+            !board.is_active && board.game_end_timestamp > 0,
+            KingTilesError::GameNotOver
+        );
+        if collect_fee {
+            require!(
+                ctx.remaining_accounts.len() == board.players_count as usize,
+                KingTilesError::NotPlayer
+            );
+            let mut fees_collected: u64 = 0;
+            for (seat, (player, payer_info)) in
+                board.players.iter().zip(ctx.remaining_accounts).enumerate()
+            {
+                require_keys_eq!(payer_info.key(), player.player);
+                require!(payer_info.is_signer, KingTilesError::NotPlayer);
+                // Seats keep their join order, so each pays the fee it paid to register.
+                let fee = board.registration_fee_for_seat(seat as u8);
+                collect_into_treasury_vault(
+                    &mut ctx.accounts.treasury_vault,
+                    payer_info,
+                    &ctx.accounts.system_program.to_account_info(),
+                    fee,
+                )?;
+                fees_collected = fees_collected.checked_add(fee).unwrap();
+            }
+            // A free rematch keeps the previous round's prize pool.
+            board.fees_collected_lamports = fees_collected;
+            if !board.options.free_play {
+                board.max_total_payout = board.fees_collected_lamports;
+            }
+        }
+
+        board.reset_round();
+        board.round = board.round.checked_add(1).unwrap();
+        for index in 0..board.players_count as usize {
+            let previous = board.players[index];
+            let spawn = board.spawn_positions[index];
+            board.players[index] = Player::new(previous.player, previous.id, spawn, [0; 32]);
+            board.set_cell(spawn as usize, previous.id);
+        }
+        // Rematches always use the layout's king start; the previous round's random spawns
+        // may now sit on it, so probe forward to the next free cell.
+        let active_cells = board.active_board_cells();
+        let mut king_cell = board.king_start_position as usize;
+        while board.cell(king_cell) != EMPTY {
+            king_cell = (king_cell.checked_add(1).unwrap()) % active_cells;
+        }
+        board.set_cell(king_cell, KING_MARK);
+        board.king_current_position = king_cell as u8;
+        activate_round(board)?;
+        emit!(RematchStartedEvent {
+            game_id: board.game_id,
+            fee_collected: collect_fee,
+        });
+        require!(
+            ctx.accounts.registry.sync(&ctx.accounts.board_account),
+            KingTilesError::RegistryFull
+        );
+        Ok(())
+    }
+
+    /// Returns a finished board to an open lobby under the same `game_id`, with fresh fees.
+    pub fn reset_board(
+        ctx: Context<ResetBoard>,
+        game_id: u64,
+        registration_fee_lamports: u64,
+        lamports_per_score: u64,
+    ) -> Result<()> {
+        msg!("Resetting board for game_id: {}", game_id);
+        let board = &mut ctx.accounts.board_account;
+        require!(
+            !board.is_active && board.game_end_timestamp > 0,
+            KingTilesError::GameNotOver
+        );
+        require!(
+            (registration_fee_lamports > 0) != board.options.free_play && lamports_per_score > 0,
+            KingTilesError::InvalidGameConfig
+        );
+        board.reset_round();
+        board.round = board.round.checked_add(1).unwrap();
+        board.players.clear();
+        board.players_count = 0;
+        board.king_player = 0;
+        board.fees_collected_lamports = 0;
+        board.registration_fee_lamports = registration_fee_lamports;
+        board.lamports_per_score = lamports_per_score;
+        if board.options.spawn_layout == SpawnLayout::Random {
+            // The last round's VRF spawns may sit anywhere; registration starts from the top row.
+            board.spawn_positions = default_spawn_positions();
+        }
+        if !board.options.random_king_start {
+            let king_cell = board.king_start_position;
+            board.set_cell(king_cell as usize, KING_MARK);
+            board.king_current_position = king_cell;
+        }
+        emit!(BoardResetEvent {
+            game_id: board.game_id,
+            registration_fee_lamports,
+            lamports_per_score,
+        });
+        require!(
+            ctx.accounts.registry.sync(&ctx.accounts.board_account),
+            KingTilesError::RegistryFull
+        );
+        Ok(())
+    }
+
+    /// Settles the king's occupant up to now. Scores also settle on every board mutation,
+    /// so this crank only keeps idle boards' leaderboards fresh.
+    pub fn update_player_score(ctx: Context<UpdatePlayerScore>, game_id: u64) -> Result<()> {
+        let _ = game_id;
+        let board = &mut ctx.accounts.board_account;
+        if !board.is_active {
+            return Ok(());
+        }
+        let settled = settle_king_occupancy(board)?
+            .checked_add(award_evasion(board)?)
+            .unwrap();
+        let now = current_timestamp()?;
+        if board.is_live(now)
+            && now
+                >= board
+                    .last_guard_step_timestamp
+                    .checked_add(GUARD_STEP_SECONDS)
+                    .unwrap()
+        {
+            step_guards(board)?;
+            board.last_guard_step_timestamp = now;
+        }
+        if board.is_live(now)
+            && !board.in_warmup(now)
+            && now
+                >= board
+                    .last_camping_tick_timestamp
+                    .checked_add(CAMPING_TICK_SECONDS)
+                    .unwrap()
+        {
+            tick_camping_decay(board);
+            board.last_camping_tick_timestamp = now;
+        }
+        if settled.checked_add(award_king_streak(board)).unwrap() > 0 {
+            let tip = board.options.crank_tip_lamports;
+            pay_crank_tip(board, &ctx.accounts.payer.to_account_info(), tip)?;
+        }
+        Ok(())
+    }
+
+    /// Drops a poison source on an empty cell of a live game.
+    pub fn seed_poison(ctx: Context<SeedPoison>, game_id: u64, cell: u8) -> Result<()> {
+        let _ = game_id;
+        let board = &mut ctx.accounts.board_account;
+        require!(
+            board.is_live(current_timestamp()?),
+            KingTilesError::GameNotActive
+        );
+        require!(
+            (cell as usize) < board.active_board_cells(),
+            KingTilesError::OutOfBounds
+        );
+        require!(
+            board.cell(cell as usize) == EMPTY,
+            KingTilesError::CellOccupied
+        );
+        require!(
+            empty_cell_count(board) > POISON_MIN_EMPTY_CELLS,
+            KingTilesError::PoisonSaturated
+        );
+        poison_cell(board, cell as usize);
+        Ok(())
+    }
+
+    /// Poison crank: at most once per `POISON_SPREAD_SECONDS`, drains everyone standing on
+    /// poison and grows the patch by one cell.
+    pub fn spread_poison(ctx: Context<SpreadPoison>, game_id: u64) -> Result<()> {
+        let _ = game_id;
+        let board = &mut ctx.accounts.board_account;
+        let now = current_timestamp()?;
+        if !board.is_live(now)
+            || board.in_warmup(now)
+            || board.poison == Bitboard::default()
+            || now
+                < board
+                    .last_poison_spread_timestamp
+                    .checked_add(POISON_SPREAD_SECONDS)
+                    .unwrap()
+        {
+            return Ok(());
+        }
+        board.last_poison_spread_timestamp = now;
+        settle_king_occupancy(board)?;
+        drain_poisoned_players(board);
+        // Crank-timed rather than VRF: the spread is cosmetic pressure, not a prize draw.
+        let randomness = expand_randomness(&board.seed_mix, &now.to_le_bytes());
+        if spread_poison_once(board, &randomness) {
+            let tip = board.options.crank_tip_lamports;
+            pay_crank_tip(board, &ctx.accounts.payer.to_account_info(), tip)?;
+        }
+        Ok(())
+    }
+
+    pub fn initialize_config(
+        ctx: Context<InitializeConfig>,
+        admin: Pubkey,
+        withdraw_cap_per_epoch: u64,
+        param_change_delay_seconds: i64,
+        validator: Pubkey,
+    ) -> Result<()> {
+        msg!("Initializing config with admin {}", admin);
+        require!(
+            param_change_delay_seconds >= 0,
+            KingTilesError::InvalidGameConfig
+        );
+        let config = &mut ctx.accounts.config;
+        config.param_change_delay_seconds = param_change_delay_seconds;
+        config.admin = admin;
+        config.withdraw_cap_per_epoch = withdraw_cap_per_epoch;
+        config.withdraw_epoch = Clock::get()?.epoch;
+        config.withdrawn_in_epoch = 0;
+        config.validator = validator;
+        Ok(())
+    }
+
+    /// Points `mint_xp` at `xp_mint` and sets the XP paid per point of score; `xp_per_score` 0
+    /// turns XP off. Also grows config accounts created before XP existed to the current size.
+    pub fn set_xp_config(ctx: Context<SetXpConfig>, xp_per_score: u64) -> Result<()> {
+        let info = ctx.accounts.config.to_account_info();
+        grow_account(
+            &info,
+            &ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            8 + Config::INIT_SPACE,
+        )?;
+        let mut config = Config::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require_keys_eq!(
+            ctx.accounts.admin.key(),
+            config.admin,
+            KingTilesError::NotAdmin
+        );
+        let xp_mint = ctx.accounts.xp_mint.key();
+        if xp_per_score > 0 {
+            require!(
+                mint_authority(&ctx.accounts.xp_mint) == Some(ctx.accounts.xp_authority.key()),
+                KingTilesError::InvalidXpMint
+            );
+        }
+        config.xp_mint = xp_mint;
+        config.xp_per_score = xp_per_score;
+        config.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+        emit!(XpConfigSetEvent {
+            xp_mint,
+            xp_per_score,
+        });
+        Ok(())
+    }
+
+    /// Sweeps treasury funds into the program-owned `["treasury_vault"]` PDA, creating it on
+    /// first use, so they can only leave again through `withdraw_treasury`.
+    pub fn deposit_treasury(ctx: Context<DepositTreasury>, amount: u64) -> Result<()> {
+        msg!("Depositing {} lamports into the treasury vault", amount);
+        let transfer_ix = anchor_lang::system_program::Transfer {
+            from: ctx.accounts.treasury.to_account_info(),
+            to: ctx.accounts.treasury_vault.to_account_info(),
+        };
+        anchor_lang::system_program::transfer(
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), transfer_ix),
+            amount,
+        )?;
+        let vault = &mut ctx.accounts.treasury_vault;
+        vault.total_deposited_lamports =
+            vault.total_deposited_lamports.checked_add(amount).unwrap();
+        emit!(TreasuryDepositEvent {
+            amount,
+            total_deposited_lamports: vault.total_deposited_lamports,
+        });
+        Ok(())
+    }
+
+    /// Moves funds out of the treasury vault; only the config admin can sign, withdrawals are
+    /// capped per epoch, and each leaves an `AdminWithdrawEvent`.
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        let epoch = Clock::get()?.epoch;
+        if config.withdraw_epoch != epoch {
+            config.withdraw_epoch = epoch;
+            config.withdrawn_in_epoch = 0;
+        }
+        let withdrawn = config.withdrawn_in_epoch.checked_add(amount).unwrap();
+        require!(
+            withdrawn <= config.withdraw_cap_per_epoch,
+            KingTilesError::WithdrawCapExceeded
+        );
+        config.withdrawn_in_epoch = withdrawn;
+        let vault = &mut ctx.accounts.treasury_vault;
+        let vault_info = vault.to_account_info();
+        let rent_floor = Rent::get()?.minimum_balance(vault_info.data_len());
+        require!(
+            vault_info.lamports().saturating_sub(rent_floor) >= amount,
+            KingTilesError::InsufficientTreasuryVault
+        );
+        vault_info.sub_lamports(amount)?;
+        ctx.accounts.destination.add_lamports(amount)?;
+        vault.total_withdrawn_lamports =
+            vault.total_withdrawn_lamports.checked_add(amount).unwrap();
+        emit!(AdminWithdrawEvent {
+            admin: ctx.accounts.admin.key(),
+            destination: ctx.accounts.destination.key(),
+            amount,
+            epoch,
+            withdrawn_in_epoch: withdrawn,
+        });
+        Ok(())
+    }
+
+    /// Queues a board parameter change that only `execute_param_change` can apply, and only
+    /// after the configured delay. A new proposal replaces any pending one.
+    pub fn propose_param_change(
+        ctx: Context<ProposeParamChange>,
+        game_id: u64,
+        param: GameParam,
+        value: u64,
+    ) -> Result<()> {
+        let now = current_timestamp()?;
+        let executable_at = now
+            .checked_add(ctx.accounts.config.param_change_delay_seconds)
+            .unwrap();
+        let change = &mut ctx.accounts.param_change;
+        change.game_id = game_id;
+        change.param = param;
+        change.value = value;
+        change.proposed_at = now;
+        change.executable_at = executable_at;
+        emit!(ParamChangeProposedEvent {
+            game_id,
+            param,
+            value,
+            executable_at,
+        });
+        Ok(())
+    }
+
+    /// Permissionless once the timelock has passed; never applies mid-round.
+    pub fn execute_param_change(ctx: Context<ExecuteParamChange>, game_id: u64) -> Result<()> {
+        let change = &ctx.accounts.param_change;
+        require!(
+            current_timestamp()? >= change.executable_at,
+            KingTilesError::ParamChangeLocked
+        );
+        let board = &mut ctx.accounts.board_account;
+        require!(!board.is_active, KingTilesError::GameAlreadyStarted);
+        let value = change.value;
+        match change.param {
+            GameParam::RegistrationFeeLamports => {
+                require!(
+                    (value > 0) != board.options.free_play,
+                    KingTilesError::InvalidParamChange
+                );
+                board.registration_fee_lamports = value;
+            }
+            GameParam::LamportsPerScore => {
+                require!(value > 0, KingTilesError::InvalidParamChange);
+                board.lamports_per_score = value;
+            }
+            GameParam::GameDurationSeconds => {
+                require!(
+                    value > 0 && value <= MAX_GAME_DURATION_SECONDS as u64,
+                    KingTilesError::InvalidParamChange
+                );
+                board.game_duration_seconds = value as i64;
+            }
+        }
+        emit!(ParamChangeExecutedEvent {
+            game_id,
+            param: change.param,
+            value,
+        });
+        Ok(())
+    }
+
+    pub fn register_relayer(ctx: Context<RegisterRelayer>, stake_lamports: u64) -> Result<()> {
+        msg!("Registering relayer: {}", ctx.accounts.authority.key());
+        require!(
+            stake_lamports >= MIN_RELAYER_STAKE_LAMPORTS,
+            KingTilesError::InsufficientRelayerStake
+        );
+        let transfer_ix = anchor_lang::system_program::Transfer {
+            from: ctx.accounts.authority.to_account_info(),
+            to: ctx.accounts.relayer.to_account_info(),
+        };
+        anchor_lang::system_program::transfer(
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), transfer_ix),
+            stake_lamports,
+        )?;
+        let relayer = &mut ctx.accounts.relayer;
+        relayer.authority = ctx.accounts.authority.key();
+        relayer.stake_lamports = stake_lamports;
+        relayer.slashed_lamports = 0;
+        relayer.registered_at = current_timestamp()?;
+        relayer.unbonding_at = 0;
+        emit!(RelayerRegisteredEvent {
+            relayer: relayer.authority,
+            stake_lamports,
+        });
+        Ok(())
+    }
+
+    /// Treasury-only penalty for a provably bad score tick; slashed stake goes to the treasury
+    /// vault.
+    pub fn slash_relayer(ctx: Context<SlashRelayer>, amount: u64) -> Result<()> {
+        let relayer = &mut ctx.accounts.relayer;
+        msg!(
+            "Slashing relayer {} by {} lamports",
+            relayer.authority,
+            amount
+        );
+        require!(
+            amount > 0 && amount <= relayer.stake_lamports,
+            KingTilesError::InvalidSlashAmount
+        );
+        relayer.stake_lamports = relayer.stake_lamports.checked_sub(amount).unwrap();
+        relayer.slashed_lamports = relayer.slashed_lamports.checked_add(amount).unwrap();
+        relayer.sub_lamports(amount)?;
+        let treasury_vault = &mut ctx.accounts.treasury_vault;
+        treasury_vault.add_lamports(amount)?;
+        treasury_vault.total_deposited_lamports = treasury_vault
+            .total_deposited_lamports
+            .checked_add(amount)
+            .unwrap();
+        emit!(RelayerSlashedEvent {
+            relayer: relayer.authority,
+            amount,
+            remaining_stake: relayer.stake_lamports,
+        });
+        Ok(())
+    }
+
+    /// Stops a relayer cranking and starts its `RELAYER_UNBONDING_SECONDS` cooldown, during
+    /// which the treasury can still slash it.
+    pub fn unregister_relayer(ctx: Context<UnregisterRelayer>) -> Result<()> {
+        let relayer = &mut ctx.accounts.relayer;
+        msg!("Unregistering relayer: {}", relayer.authority);
+        require!(relayer.unbonding_at == 0, KingTilesError::RelayerUnbonding);
+        relayer.unbonding_at = current_timestamp()?;
+        emit!(RelayerUnbondingEvent {
+            relayer: relayer.authority,
+            withdrawable_at: relayer
+                .unbonding_at
+                .checked_add(RELAYER_UNBONDING_SECONDS)
+                .unwrap(),
+        });
+        Ok(())
+    }
+
+    /// Closes an unbonded relayer PDA, returning what is left of the stake and the rent.
+    pub fn withdraw_relayer_stake(ctx: Context<WithdrawRelayerStake>) -> Result<()> {
+        let relayer = &ctx.accounts.relayer;
+        msg!("Withdrawing stake for relayer: {}", relayer.authority);
+        require!(
+            relayer.unbonding_at > 0
+                && current_timestamp()?
+                    >= relayer
+                        .unbonding_at
+                        .checked_add(RELAYER_UNBONDING_SECONDS)
+                        .unwrap(),
+            KingTilesError::RelayerStakeLocked
+        );
+        emit!(RelayerStakeWithdrawnEvent {
+            relayer: relayer.authority,
+            lamports: relayer.to_account_info().lamports(),
+        });
+        Ok(())
+    }
+
+    /// Returns every escrowed head-to-head stake of a lobby that never started; players are
+    /// passed as remaining accounts in join order.
+    pub fn cancel_wager<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CancelWager<'info>>,
+        game_id: u64,
+    ) -> Result<()> {
+        msg!("Cancelling wager for game_id: {}", game_id);
+        let board = &ctx.accounts.board_account;
+        require!(
+            !board.is_active && board.game_end_timestamp == 0,
+            KingTilesError::GameAlreadyStarted
+        );
+        let escrow = &mut ctx.accounts.wager_escrow;
+        require!(!escrow.settled, KingTilesError::WagerAlreadySettled);
+        let players_count = board.players_count as usize;
+        require!(
+            ctx.remaining_accounts.len() == players_count,
+            KingTilesError::InvalidPayoutPage
+        );
+        let escrow_info = escrow.to_account_info();
+        for (i, player) in board.players[..players_count].iter().enumerate() {
+            let player_info = &ctx.remaining_accounts[i];
+            require_keys_eq!(
+                player_info.key(),
+                player.player,
+                KingTilesError::InvalidPayoutAccount
+            );
+            escrow_info.sub_lamports(escrow.stakes[i])?;
+            player_info.add_lamports(escrow.stakes[i])?;
+            // The treasury vault refunds the whole seat price, including any referral or
+            // jackpot cut.
+            pay_from_treasury_vault(
+                &mut ctx.accounts.treasury_vault,
+                player_info,
+                board.registration_fee_for_seat(i as u8),
+            )?;
+        }
+        escrow.settled = true;
+        ctx.accounts.registry.remove(game_id);
+        emit!(WagerRefundedEvent {
+            game_id,
+            lamports: escrow.total_staked,
+        });
+        Ok(())
+    }
+
+    /// Rewrites a board created before versioning under the current `Board` layout, growing
+    /// the account to fit. Any other account fails with `InvalidBoardLayout`.
+    pub fn migrate_board(ctx: Context<MigrateBoard>, game_id: u64) -> Result<()> {
+        msg!("Migrating board for game_id: {}", game_id);
+        let info = ctx.accounts.board_account.to_account_info();
+        let legacy = {
+            let data = info.try_borrow_data()?;
+            require!(
+                data.len() == 8 + LegacyBoardV0::INIT_SPACE && data[..8] == *Board::DISCRIMINATOR,
+                KingTilesError::InvalidBoardLayout
+            );
+            LegacyBoardV0::deserialize(&mut &data[8..])
+                .map_err(|_| error!(KingTilesError::InvalidBoardLayout))?
+        };
+        require!(
+            valid_mode(legacy.board_side_len, legacy.max_players)
+                && legacy.players.len() == legacy.players_count as usize
+                && (legacy.king_current_position as usize) < BOARD_SIZE
+                && (legacy.bomb_current_position as usize) < BOARD_SIZE,
+            KingTilesError::InvalidBoardLayout
+        );
+
+        grow_account(
+            &info,
+            &ctx.accounts.treasury.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            Board::space(legacy.max_players),
+        )?;
+        // All-zero fields decode as empty defaults, giving a blank board to fill in.
+        info.try_borrow_mut_data()?[8..].fill(0);
+        let mut board = Board::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        upgrade_legacy_board(&legacy, &mut board, current_timestamp()?);
+        board.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+        emit!(BoardMigratedEvent {
+            game_id,
+            from_version: 0,
+            to_version: BOARD_VERSION,
+        });
+        Ok(())
+    }
+
+    /// Moves an open lobby up to a larger mode before it fills, growing the board account to
+    /// fit the extra seats.
+    pub fn expand_lobby(
+        ctx: Context<ExpandLobby>,
+        game_id: u64,
+        board_side_len: u8,
+        max_players: u8,
+    ) -> Result<()> {
+        msg!(
+            "Expanding lobby for game_id: {} to {} players",
+            game_id,
+            max_players
+        );
+        let board = &mut ctx.accounts.board_account;
+        require!(!board.is_active, KingTilesError::GameAlreadyStarted);
+        require!(
+            board.players_count < board.max_players,
+            KingTilesError::MaxPlayersReached
+        );
+        require!(
+            max_players > board.max_players
+                && valid_mode(board_side_len, max_players)
+                && board.options.wager_lamports == 0,
+            KingTilesError::InvalidGameConfig
+        );
+        // Map-template spawns only exist for the original player count.
+        require!(
+            board.spawn_positions
+                == layout_spawn_positions(board.options.spawn_layout, board.board_side_len),
+            KingTilesError::InvalidMapTemplate
+        );
+        relayout_lobby(board, board_side_len, max_players)?;
+        emit!(LobbyExpandedEvent {
+            game_id,
+            board_side_len,
+            max_players,
+        });
+        require!(
+            ctx.accounts.registry.sync(&ctx.accounts.board_account),
+            KingTilesError::RegistryFull
+        );
+        Ok(())
+    }
+
+    /// Switches an empty lobby between the classic king tile and the king-player variant. In
+    /// the latter the first player to register becomes the king.
+    pub fn set_game_variant(
+        ctx: Context<SetGameVariant>,
+        game_id: u64,
+        variant: GameVariant,
+    ) -> Result<()> {
+        let board = &mut ctx.accounts.board_account;
+        require!(!board.is_active, KingTilesError::GameAlreadyStarted);
+        require!(board.players_count == 0, KingTilesError::LobbyNotEmpty);
+        board.variant = variant;
+        board.king_player = 0;
+        emit!(GameVariantSetEvent { game_id, variant });
+        Ok(())
+    }
+
+    /// Sets how many score ticks a player may hold one cell before losing `decay_points` per
+    /// tick; `idle_ticks` of 0 turns camping decay off. Empty lobby only.
+    pub fn set_camping_decay(
+        ctx: Context<SetCampingDecay>,
+        game_id: u64,
+        idle_ticks: u16,
+        decay_points: u64,
+    ) -> Result<()> {
+        let board = &mut ctx.accounts.board_account;
+        require!(!board.is_active, KingTilesError::GameAlreadyStarted);
+        require!(board.players_count == 0, KingTilesError::LobbyNotEmpty);
+        require!(
+            idle_ticks == 0 || decay_points > 0,
+            KingTilesError::InvalidGameConfig
+        );
+        board.camping_decay_ticks = idle_ticks;
+        board.camping_decay_points = decay_points;
+        emit!(CampingDecaySetEvent {
+            game_id,
+            idle_ticks,
+            decay_points,
+        });
+        Ok(())
+    }
+
+    /// Sets the points deducted, floored at zero, from anyone stepping on a bomb; 0 turns the
+    /// penalty off. Empty lobby only.
+    pub fn set_bomb_penalty(ctx: Context<SetBombPenalty>, game_id: u64, points: u64) -> Result<()> {
+        let board = &mut ctx.accounts.board_account;
+        require!(!board.is_active, KingTilesError::GameAlreadyStarted);
+        require!(board.players_count == 0, KingTilesError::LobbyNotEmpty);
+        board.bomb_penalty_points = points;
+        emit!(BombPenaltySetEvent { game_id, points });
+        Ok(())
+    }
+
+    /// Overrides the board-size default for how far the `Push` powerup shoves. Empty lobby only.
+    pub fn set_powerup_push_distance(
+        ctx: Context<SetPowerupPushDistance>,
+        game_id: u64,
+        distance: u8,
+    ) -> Result<()> {
+        let board = &mut ctx.accounts.board_account;
+        require!(!board.is_active, KingTilesError::GameAlreadyStarted);
+        require!(board.players_count == 0, KingTilesError::LobbyNotEmpty);
+        require!(
+            distance > 0 && distance < board.board_side_len,
+            KingTilesError::InvalidGameConfig
+        );
+        board.powerup_push_distance = distance;
+        emit!(PowerupPushDistanceSetEvent { game_id, distance });
+        Ok(())
+    }
+
+    /// Caps the pushes one player can take per `PUSH_CAP_WINDOW_SECONDS`; further pushes
+    /// against them fail with `PushCapReached`. 0 lifts the cap. Empty lobby only.
+    pub fn set_push_cap(ctx: Context<SetPushCap>, game_id: u64, cap: u8) -> Result<()> {
+        let board = &mut ctx.accounts.board_account;
+        require!(!board.is_active, KingTilesError::GameAlreadyStarted);
+        require!(board.players_count == 0, KingTilesError::LobbyNotEmpty);
+        board.push_cap = cap;
+        emit!(PushCapSetEvent { game_id, cap });
+        Ok(())
+    }
+
+    /// Opens each round with `seconds` of free movement before the clock starts: no scoring,
+    /// bombs, powerups, or global events until it ends. 0 turns warmup off. Empty lobby only.
+    pub fn set_warmup(ctx: Context<SetWarmup>, game_id: u64, seconds: u16) -> Result<()> {
+        let board = &mut ctx.accounts.board_account;
+        require!(!board.is_active, KingTilesError::GameAlreadyStarted);
+        require!(board.players_count == 0, KingTilesError::LobbyNotEmpty);
+        require!(
+            seconds as i64 <= MAX_WARMUP_SECONDS,
+            KingTilesError::InvalidGameConfig
+        );
+        board.warmup_seconds = seconds;
+        emit!(WarmupSetEvent { game_id, seconds });
+        Ok(())
+    }
+
+    /// Turns on a once-per-round halftime at the midpoint of the clock, freezing everyone for
+    /// `freeze_seconds` while the king, powerup, and bombs are redrawn. 0 turns it off.
+    /// Empty lobby only.
+    pub fn set_halftime(ctx: Context<SetHalftime>, game_id: u64, freeze_seconds: u8) -> Result<()> {
+        let board = &mut ctx.accounts.board_account;
+        require!(!board.is_active, KingTilesError::GameAlreadyStarted);
+        require!(board.players_count == 0, KingTilesError::LobbyNotEmpty);
+        require!(
+            freeze_seconds <= MAX_HALFTIME_FREEZE_SECONDS,
+            KingTilesError::InvalidGameConfig
+        );
+        board.halftime_freeze_seconds = freeze_seconds;
+        emit!(HalftimeSetEvent {
+            game_id,
+            freeze_seconds,
+        });
+        Ok(())
+    }
+
+    /// Raises the registration fee by `escalation_bps` of the base fee for each earlier
+    /// registrant, so later seats cost more. 0 keeps the fee flat. Empty lobby only.
+    pub fn set_fee_escalation(
+        ctx: Context<SetFeeEscalation>,
+        game_id: u64,
+        escalation_bps: u16,
+    ) -> Result<()> {
+        let board = &mut ctx.accounts.board_account;
+        require!(!board.is_active, KingTilesError::GameAlreadyStarted);
+        require!(board.players_count == 0, KingTilesError::LobbyNotEmpty);
+        require!(escalation_bps <= MAX_BPS, KingTilesError::InvalidGameConfig);
+        board.fee_escalation_bps = escalation_bps;
+        emit!(FeeEscalationSetEvent {
+            game_id,
+            escalation_bps,
+        });
+        Ok(())
+    }
+
+    pub fn set_spectator_fee(
+        ctx: Context<SetSpectatorFee>,
+        game_id: u64,
+        fee_lamports: u64,
+    ) -> Result<()> {
+        let board = &mut ctx.accounts.board_account;
+        require!(!board.is_active, KingTilesError::GameAlreadyStarted);
+        require!(board.players_count == 0, KingTilesError::LobbyNotEmpty);
+        require!(board.viewer_count == 0, KingTilesError::LobbyNotEmpty);
+        board.spectator_fee_lamports = fee_lamports;
+        emit!(SpectatorFeeSetEvent {
+            game_id,
+            fee_lamports,
+        });
+        Ok(())
+    }
+
+    pub fn close_board(ctx: Context<CloseBoard>, game_id: u64) -> Result<()> {
+        msg!("Closing board for game_id: {}", game_id);
+        let board = &ctx.accounts.board_account;
+        require!(
+            board.players_count == 0 || board.rewards_distributed,
+            KingTilesError::RewardsPending
+        );
+        ctx.accounts.registry.remove(game_id);
+        Ok(())
+    }
+
+    pub fn use_power(
+        ctx: Context<UsePower>,
+        game_id: u64,
+        player_id: u8,
+        direction: Direction,
+    ) -> Result<()> {
+        let _ = game_id;
+        let board = &mut ctx.accounts.board_account;
+        let player_index = player_id_to_index(player_id);
+        require!(
+            player_index < board.players_count as usize,
+            KingTilesError::NotPlayer
+        );
+        let authority = ctx.accounts.authority.key();
+        require!(
+            authority == TREASURY || authority == board.players[player_index].player,
+            KingTilesError::NotPlayer
+        );
+        require!(board.paused_at == 0, KingTilesError::GamePaused);
+        let now = current_timestamp()?;
+        require!(!board.in_warmup(now), KingTilesError::WarmupInProgress);
+        require!(
+            board.delegation_state == DelegationState::Delegated,
+            KingTilesError::BoardNotDelegated
+        );
+        require_not_stunned(board, player_index, now)?;
+        let powerup = board.players[player_index]
+            .powerup
+            .ok_or(KingTilesError::NoPowerup)?;
+        require!(
+            !board.players[player_index].powerup_expired(now),
+            KingTilesError::PowerupExpired
+        );
+
+        settle_king_occupancy(board)?;
+        let mut beam = BeamTrace::default();
+        match powerup {
+            PowerupKind::Push => {
+                let power_use_direction = direction.offset(board.board_side_len);
+                beam = use_power_with_direction(board, player_index, power_use_direction)?;
+                require!(!beam.capped, KingTilesError::PushCapReached);
+            }
+            PowerupKind::Shield => {
+                let clock = Clock::get()?;
+                let player = &mut board.players[player_index];
+                player.shield_expiry = clock
+                    .unix_timestamp
+                    .checked_add(SHIELD_DURATION_SECONDS)
+                    .unwrap();
+                player.shield_charges = SHIELD_CHARGES;
+                player.powerup = None;
+            }
+            PowerupKind::Magnet => {
+                require!(
+                    pull_king_toward_player(board, player_index),
+                    KingTilesError::InvalidPowerupMove
+                );
+                board.players[player_index].powerup = None;
+            }
+            PowerupKind::DoubleScore => {
+                let clock = Clock::get()?;
+                let player = &mut board.players[player_index];
+                player.score_multiplier_expiry = clock
+                    .unix_timestamp
+                    .checked_add(DOUBLE_SCORE_DURATION_SECONDS)
+                    .unwrap();
+                player.powerup = None;
+            }
+            PowerupKind::Teleport => {
+                return err!(KingTilesError::InvalidPowerupMove);
+            }
+        }
+
+        settle_king_occupancy(board)?;
+        let powerups_used = board.players[player_index].powerups_used;
+        board.players[player_index].powerups_used = powerups_used.checked_add(1).unwrap();
+
+        emit!(PowerUsedEvent {
+            player: player_id,
+            game_id: board.game_id,
+            kind: powerup,
+            scanned_cells: beam.scanned_cells,
+            victim: beam.victim,
+            victim_from: beam.victim_from,
+            victim_to: beam.victim_to,
+        });
+        #[cfg(feature = "compute-telemetry")]
+        emit_compute_units(board.game_id, "use_power");
+        Ok(())
+    }
+}
+
+/// Starts the 60s round once the lobby is full and the king is on the board.
+fn seat_player(
+    ctx: Context<RegisterPlayer>,
+    game_id: u64,
+    player: Pubkey,
+    seed_commitment: [u8; 32],
+    allowlist_proof: Vec<[u8; 32]>,
+    referrer: Option<Pubkey>,
+) -> Result<()> {
+    msg!("Registering player for game_id: {}", game_id);
+    let options = ctx.accounts.board_account.options;
+    if options.entry_fee_usd_cents > 0 {
+        let price_update = ctx
+            .accounts
+            .price_update
+            .as_ref()
+            .ok_or(KingTilesError::InvalidPriceFeed)?;
+        let (price, exponent) = pyth_price(price_update, &options.pyth_feed_id)?;
+        let fee = usd_cents_to_lamports(options.entry_fee_usd_cents, price, exponent)
+            .ok_or(KingTilesError::InvalidPriceFeed)?;
+        msg!(
+            "Entry fee of {} USD cents converts to {} lamports",
+            options.entry_fee_usd_cents,
+            fee
+        );
+        ctx.accounts.board_account.registration_fee_lamports = fee;
+    }
+    let fee = ctx
+        .accounts
+        .board_account
+        .registration_fee_for_seat(ctx.accounts.board_account.players_count);
+    let referral_lamports = match referrer {
+        Some(referrer) => {
+            require!(referrer != player, KingTilesError::InvalidReferrer);
+            let referral = ctx
+                .accounts
+                .referral_account
+                .as_mut()
+                .ok_or(KingTilesError::InvalidReferrer)?;
+            require!(
+                referral.referrer == referrer,
+                KingTilesError::InvalidReferrer
+            );
+            let bps = ctx.accounts.board_account.options.referral_fee_bps;
+            let lamports = fee
+                .checked_mul(bps as u64)
+                .unwrap()
+                .checked_div(MAX_BPS as u64)
+                .unwrap();
+            referral.referrals = referral.referrals.checked_add(1).unwrap();
+            referral.credits_lamports = referral.credits_lamports.checked_add(lamports).unwrap();
+            lamports
+        }
+        None => 0,
+    };
+    let jackpot_lamports = fee
+        .checked_mul(ctx.accounts.board_account.options.jackpot_fee_bps as u64)
+        .unwrap()
+        .checked_div(MAX_BPS as u64)
+        .unwrap();
+    let board_account = &mut ctx.accounts.board_account;
+    let allowlist_root = board_account.options.allowlist_root;
+    require!(
+        allowlist_root == [0; 32] || allowlist_contains(&allowlist_root, &player, &allowlist_proof),
+        KingTilesError::NotAllowlisted
+    );
+    if options.token_gate_min_amount > 0 {
+        let balance = ctx
+            .accounts
+            .gate_token_account
+            .as_ref()
+            .and_then(|account| token_balance(account, &player, &options.token_gate_mint));
+        require!(
+            balance.unwrap_or(0) >= options.token_gate_min_amount,
+            KingTilesError::TokenGateNotMet
+        );
+    }
+    require!(
+        board_account.players_count < board_account.max_players,
+        KingTilesError::MaxPlayersReached
+    );
+    require!(!board_account.is_active, KingTilesError::GameAlreadyStarted);
+    if !board_account.options.free_play {
+        let treasury_lamports = fee
+            .checked_sub(referral_lamports)
+            .unwrap()
+            .checked_sub(jackpot_lamports)
+            .unwrap();
+        collect_into_treasury_vault(
+            &mut ctx.accounts.treasury_vault,
+            &ctx.accounts.payer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            treasury_lamports,
+        )?;
+        board_account.fees_collected_lamports = board_account
+            .fees_collected_lamports
+            .checked_add(treasury_lamports)
+            .unwrap();
+    }
+    if jackpot_lamports > 0 {
+        let jackpot_vault = ctx
+            .accounts
+            .jackpot_vault
+            .as_mut()
+            .ok_or(KingTilesError::JackpotVaultMissing)?;
+        jackpot_vault.total_contributed_lamports = jackpot_vault
+            .total_contributed_lamports
+            .checked_add(jackpot_lamports)
+            .unwrap();
+        let transfer_ix = anchor_lang::system_program::Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: jackpot_vault.to_account_info(),
+        };
+        anchor_lang::system_program::transfer(
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), transfer_ix),
+            jackpot_lamports,
+        )?;
+    }
+    if referral_lamports > 0 {
+        let referral = ctx.accounts.referral_account.as_ref().unwrap();
+        let transfer_ix = anchor_lang::system_program::Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: referral.to_account_info(),
+        };
+        anchor_lang::system_program::transfer(
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), transfer_ix),
+            referral_lamports,
+        )?;
+        emit!(ReferralCreditedEvent {
+            referrer: referral.referrer,
+            player,
+            game_id,
+            lamports: referral_lamports,
+        });
+    }
+    if options.wager_lamports > 0 {
+        let escrow = ctx
+            .accounts
+            .wager_escrow
+            .as_mut()
+            .ok_or(KingTilesError::WagerEscrowMissing)?;
+        if escrow.settled {
+            escrow.stakes = [0; MAX_PLAYERS];
+            escrow.total_staked = 0;
+            escrow.settled = false;
+        }
+        escrow.game_id = game_id;
+        escrow.stakes[board_account.players_count as usize] = options.wager_lamports;
+        escrow.total_staked = escrow
+            .total_staked
+            .checked_add(options.wager_lamports)
+            .unwrap();
+        let transfer_ix = anchor_lang::system_program::Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: escrow.to_account_info(),
+        };
+        anchor_lang::system_program::transfer(
+            CpiContext::new(ctx.accounts.system_program.to_account_info(), transfer_ix),
+            options.wager_lamports,
+        )?;
+    }
+
+    let players_count = board_account.players_count;
+
+    let seat = Player::new(
+        player,
+        players_count.checked_add(1).unwrap(),
+        board_account.spawn_positions[players_count as usize],
+        seed_commitment,
+    );
+    board_account.players.push(seat);
+    board_account.set_cell(seat.current_position as usize, seat.id);
+    board_account.players_count = players_count.checked_add(1).unwrap();
+    if board_account.variant == GameVariant::KingPlayer && board_account.king_player == 0 {
+        board_account.king_player = seat.id;
+        emit!(KingRoleAssignedEvent {
+            player,
+            game_id,
+            player_id: seat.id,
+        });
+    }
+
+    if board_account.players_count == board_account.max_players {
+        require!(
+            board_account.king_current_position != NO_POSITION,
+            KingTilesError::KingNotPlaced
+        );
+        let prize_funds = if board_account.options.free_play {
+            match ctx.accounts.sponsor_vault.as_ref() {
+                Some(vault) => {
+                    let vault_info = vault.to_account_info();
+                    let rent_floor = Rent::get()?.minimum_balance(vault_info.data_len());
+                    vault_info.lamports().saturating_sub(rent_floor)
+                }
+                None => 0,
+            }
+        } else {
+            ctx.accounts.treasury.lamports()
+        };
+        require!(
+            prize_funds >= board_account.max_prize_payout(),
+            KingTilesError::InsufficientPrizeFunds
+        );
+        board_account.max_total_payout = if board_account.options.free_play {
+            prize_funds
+        } else {
+            board_account.fees_collected_lamports
+        };
+        if board_account.options.delegate_on_activation {
+            emit!(LobbyFilledEvent { game_id });
+        } else {
+            activate_round(board_account)?;
+        }
+    }
+    let display_name = match &ctx.accounts.profile {
+        Some(profile) => {
+            require_keys_eq!(profile.authority, player, KingTilesError::NotPlayer);
+            profile.display_name.clone()
+        }
+        None => String::new(),
+    };
+    emit!(PlayerRegisteredEvent {
+        player,
+        sponsor: ctx.accounts.payer.key(),
+        game_id: ctx.accounts.board_account.game_id,
+        fee_lamports: fee,
+        display_name,
+    });
+    require!(
+        ctx.accounts.registry.sync(&ctx.accounts.board_account),
+        KingTilesError::RegistryFull
+    );
+    Ok(())
+}
+
+/// Validates `args` and lays out a freshly initialized board for `start_game_session` and
+/// `start_partner_game_session`; `funder` pays the crank tip budget.
+fn open_game_session<'info>(
+    board_account: &mut Account<'info, Board>,
+    registry: &mut Account<'info, GameRegistry>,
+    map_template: Option<&Account<'info, MapTemplate>>,
+    funder: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    game_id: u64,
+    args: GameSessionArgs,
+) -> Result<()> {
+    let GameSessionArgs {
+        board_side_len,
+        max_players,
+        registration_fee_lamports,
+        lamports_per_score,
+        walls,
+        options,
+    } = args;
+    require!(
+        valid_mode(board_side_len, max_players),
+        KingTilesError::InvalidGameConfig
+    );
+    require!(
+        (registration_fee_lamports > 0) != options.free_play && lamports_per_score > 0,
+        KingTilesError::InvalidGameConfig
+    );
+    require!(walls.len() <= MAX_WALLS, KingTilesError::InvalidGameConfig);
+    require!(
+        options.powerup_ttl_seconds >= 0
+            && (options.referral_fee_bps as u32)
+                .checked_add(options.jackpot_fee_bps as u32)
+                .unwrap()
+                <= MAX_BPS as u32,
+        KingTilesError::InvalidGameConfig
+    );
+    require!(
+        !(options.free_play && options.entry_fee_usd_cents > 0),
+        KingTilesError::InvalidGameConfig
+    );
+    require!(
+        options.participation_refund_lamports == 0
+            || (options.min_payout_score > 0
+                && (options.free_play
+                    || options.participation_refund_lamports <= registration_fee_lamports)),
+        KingTilesError::InvalidGameConfig
+    );
+    require!(
+        options.wager_lamports == 0
+            || (max_players == 2 && !options.free_play && options.wager_rake_bps <= MAX_BPS),
+        KingTilesError::InvalidGameConfig
+    );
+
+    board_account.game_id = game_id;
+    board_account.board_side_len = board_side_len;
+    board_account.powerup_push_distance = default_push_distance(board_side_len);
+    board_account.max_players = max_players;
+    board_account.registration_fee_lamports = registration_fee_lamports;
+    board_account.lamports_per_score = lamports_per_score;
+    board_account.options = options;
+    board_account.game_duration_seconds = DEFAULT_GAME_DURATION_SECONDS;
+    board_account.version = BOARD_VERSION;
+    board_account.players.clear();
+    board_account.players_count = 0;
+    board_account.board = [EMPTY; BOARD_SIZE];
+    board_account.reset_round();
+
+    let (king_position, spawn_positions, walls) = match map_template {
+        Some(template) => {
+            require!(
+                template.board_side_len == board_side_len
+                    && template.spawn_points.len() == max_players as usize
+                    && walls.is_empty(),
+                KingTilesError::InvalidMapTemplate
+            );
+            let mut spawn_positions = [0u8; MAX_PLAYERS];
+            spawn_positions[..template.spawn_points.len()].copy_from_slice(&template.spawn_points);
+            (
+                template.king_start as usize,
+                spawn_positions,
+                template.walls.clone(),
+            )
+        }
+        None => (
+            king_starting_position(board_side_len),
+            layout_spawn_positions(options.spawn_layout, board_side_len),
+            walls,
+        ),
+    };
+    board_account.spawn_positions = spawn_positions;
+    board_account.king_start_position = king_position as u8;
+    // With `random_king_start` the king stays unplaced until `callback_king_start`.
+    if !options.random_king_start {
+        board_account.king_current_position = king_position as u8;
+        board_account.set_cell(king_position, KING_MARK);
+    }
+
+    let active_cells = board_account.active_board_cells();
+    for wall in walls {
+        let cell = wall as usize;
+        // Spawn slots must stay free for registration.
+        require!(
+            cell < active_cells
+                && !spawn_positions[..max_players as usize].contains(&wall)
+                && board_account.cell(cell) == EMPTY,
+            KingTilesError::InvalidWallCell
+        );
+        board_account.set_cell(cell, WALL_MARK);
+    }
+
+    if options.crank_tip_budget_lamports > 0 {
+        let transfer_ix = anchor_lang::system_program::Transfer {
+            from: funder,
+            to: board_account.to_account_info(),
+        };
+        anchor_lang::system_program::transfer(
+            CpiContext::new(system_program, transfer_ix),
+            options.crank_tip_budget_lamports,
+        )?;
+    }
+    require!(registry.sync(board_account), KingTilesError::RegistryFull);
+    Ok(())
+}
+
+/// Profiling hook for hot ER paths; only compiled in with the `compute-telemetry` feature.
+#[cfg(feature = "compute-telemetry")]
+fn emit_compute_units(game_id: u64, instruction: &str) {
+    let remaining_units = solana_program::compute_units::sol_remaining_compute_units();
+    emit!(ComputeUnitsEvent {
+        game_id,
+        instruction: instruction.to_string(),
+        remaining_units,
+    });
+}
+
+fn activate_round(board: &mut Board) -> Result<()> {
+    let now = current_timestamp()?;
+    board.is_active = true;
+    board.spawns_pending = board.options.spawn_layout == SpawnLayout::Random;
+    board.warmup_ends_at = now.checked_add(board.warmup_seconds as i64).unwrap();
+    board.game_end_timestamp = board
+        .warmup_ends_at
+        .checked_add(board.game_duration_seconds)
+        .unwrap();
+    board.halftime_at = if board.halftime_freeze_seconds > 0 {
+        board
+            .warmup_ends_at
+            .checked_add(board.game_duration_seconds / 2)
+            .unwrap()
+    } else {
+        0
+    };
+    if board.variant == GameVariant::KingPlayer {
+        // The king-player is the king; the tile only held the slot through the lobby.
+        let king_cell = board.king_current_position;
+        if king_cell != NO_POSITION && board.cell(king_cell as usize) == KING_MARK {
+            board.vacate(king_cell as usize);
+        }
+        board.king_current_position = NO_POSITION;
+        board.last_evasion_award_timestamp = board.warmup_ends_at;
+    }
+    emit!(GameStartedEvent {
+        game_id: board.game_id,
+    });
+    if board.warmup_seconds > 0 {
+        emit!(WarmupStartedEvent {
+            game_id: board.game_id,
+            warmup_ends_at: board.warmup_ends_at,
+        });
+    }
+    emit_board_snapshot(board)?;
+    Ok(())
+}
+
+fn emit_board_snapshot(board: &Board) -> Result<()> {
+    emit!(BoardSnapshotEvent {
+        game_id: board.game_id,
+        board_side_len: board.board_side_len,
+        cells: (0..board.active_board_cells())
+            .map(|cell| board.cell(cell))
+            .collect(),
+        timestamp: current_timestamp()?,
+    });
+    Ok(())
+}
+
+fn write_game_summary(summary: &mut GameSummary, board: &Board) -> Result<()> {
+    let players = &board.players[..board.players_count as usize];
+    let top_score = players.iter().map(|p| p.score).max().unwrap_or(0);
+    summary.game_id = board.game_id;
+    summary.game_end_timestamp = board.game_end_timestamp;
+    summary.winner = players
+        .iter()
+        .find(|p| p.score == top_score)
+        .map(|p| p.player)
+        .unwrap_or_default();
+    summary.players_count = board.players_count;
+    for (i, player) in players.iter().enumerate() {
+        summary.players[i] = player.player;
+        summary.scores[i] = player.score;
+    }
+    summary.duration_seconds = board
+        .game_end_timestamp
+        .checked_sub(board.warmup_ends_at)
+        .unwrap();
+    summary.fees_collected_lamports = board.fees_collected_lamports;
+    summary.rewards_paid_lamports = board.rewards_paid_lamports;
+    summary.recorded_at = current_timestamp()?;
+    Ok(())
+}
+
+/// Moves program income from a system-owned `payer` into the treasury vault.
+fn collect_into_treasury_vault<'info>(
+    treasury_vault: &mut Account<'info, TreasuryVault>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    lamports: u64,
+) -> Result<()> {
+    let transfer_ix = anchor_lang::system_program::Transfer {
+        from: payer.clone(),
+        to: treasury_vault.to_account_info(),
+    };
+    anchor_lang::system_program::transfer(
+        CpiContext::new(system_program.clone(), transfer_ix),
+        lamports,
+    )?;
+    treasury_vault.total_deposited_lamports = treasury_vault
+        .total_deposited_lamports
+        .checked_add(lamports)
+        .unwrap();
+    Ok(())
+}
+
+/// Pays a reward or refund out of the treasury vault, never below its rent.
+fn pay_from_treasury_vault<'info>(
+    treasury_vault: &mut Account<'info, TreasuryVault>,
+    recipient: &AccountInfo<'info>,
+    lamports: u64,
+) -> Result<()> {
+    let vault_info = treasury_vault.to_account_info();
+    let rent_floor = Rent::get()?.minimum_balance(vault_info.data_len());
+    require!(
+        vault_info.lamports().saturating_sub(rent_floor) >= lamports,
+        KingTilesError::InsufficientTreasuryVault
+    );
+    vault_info.sub_lamports(lamports)?;
+    recipient.add_lamports(lamports)?;
+    treasury_vault.total_paid_lamports = treasury_vault
+        .total_paid_lamports
+        .checked_add(lamports)
+        .unwrap();
+    Ok(())
+}
+
+/// Pays half of the jackpot vault above rent to `winner`; the rest rolls over.
+fn pay_jackpot<'info>(
+    jackpot_vault: &mut Account<'info, JackpotVault>,
+    winner: &AccountInfo<'info>,
+    game_id: u64,
+) -> Result<()> {
+    let vault_info = jackpot_vault.to_account_info();
+    let rent_floor = Rent::get()?.minimum_balance(vault_info.data_len());
+    let payout = vault_info
+        .lamports()
+        .saturating_sub(rent_floor)
+        .checked_mul(JACKPOT_PAYOUT_BPS as u64)
+        .unwrap()
+        .checked_div(MAX_BPS as u64)
+        .unwrap();
+    vault_info.sub_lamports(payout)?;
+    winner.add_lamports(payout)?;
+    jackpot_vault.total_paid_lamports = jackpot_vault
+        .total_paid_lamports
+        .checked_add(payout)
+        .unwrap();
+    jackpot_vault.last_winner = winner.key();
+    jackpot_vault.last_game_id = game_id;
+    emit!(JackpotPaidEvent {
+        player: winner.key(),
+        game_id,
+        lamports: payout,
+    });
+    Ok(())
+}
+
+/// Grows a program-owned account to `space` bytes, topping its rent up from `payer`, so it can
+/// be decoded under a layout that has appended fields. Accounts already that large are untouched.
+fn grow_account<'info>(
+    info: &AccountInfo<'info>,
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    space: usize,
+) -> Result<()> {
+    if info.data_len() >= space {
+        return Ok(());
+    }
+    let shortfall = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(info.lamports());
+    if shortfall > 0 {
+        let transfer_ix = anchor_lang::system_program::Transfer {
+            from: payer.clone(),
+            to: info.clone(),
+        };
+        anchor_lang::system_program::transfer(
+            CpiContext::new(system_program.clone(), transfer_ix),
+            shortfall,
+        )?;
+    }
+    info.resize(space)?;
+    Ok(())
+}
+
+/// Pays a crank tip out of the board's own balance, skipping it once only rent is left.
+fn pay_crank_tip<'info>(
+    board: &Account<'info, Board>,
+    cranker: &AccountInfo<'info>,
+    tip: u64,
+) -> Result<()> {
+    if tip == 0 {
+        return Ok(());
+    }
+    let board_info = board.to_account_info();
+    let rent_floor = Rent::get()?.minimum_balance(board_info.data_len());
+    if board_info.lamports().saturating_sub(rent_floor) < tip {
+        return Ok(());
+    }
+    board_info.sub_lamports(tip)?;
+    cranker.add_lamports(tip)?;
+    emit!(CrankTipPaidEvent {
+        cranker: cranker.key(),
+        game_id: board.game_id,
+        lamports: tip,
+    });
+    Ok(())
+}
+
+/// Verifies a sorted-pair sha256 Merkle proof for `sha256(player)` against `root`.
+fn allowlist_contains(root: &[u8; 32], player: &Pubkey, proof: &[[u8; 32]]) -> bool {
+    let mut node = hashv(&[player.as_ref()]).to_bytes();
+    for sibling in proof {
+        node = if node <= *sibling {
+            hashv(&[&node, sibling]).to_bytes()
+        } else {
+            hashv(&[sibling, &node]).to_bytes()
+        };
+    }
+    node == *root
+}
+
+/// Amount held in an SPL Token or Token-2022 account, if it belongs to `owner` and holds `mint`.
+fn token_balance(account: &AccountInfo, owner: &Pubkey, mint: &Pubkey) -> Option<u64> {
+    if *account.owner != SPL_TOKEN_PROGRAM_ID && *account.owner != SPL_TOKEN_2022_PROGRAM_ID {
+        return None;
+    }
+    let data = account.try_borrow_data().ok()?;
+    if data.len() < SPL_TOKEN_ACCOUNT_LEN || data[108] == 0 {
+        return None;
+    }
+    if data[0..32] != mint.to_bytes() || data[32..64] != owner.to_bytes() {
+        return None;
+    }
+    Some(u64::from_le_bytes(data[64..72].try_into().unwrap()))
+}
+
+/// Price and exponent from a fully verified Pyth `PriceUpdateV2` account for `feed_id`.
+fn pyth_price(account: &AccountInfo, feed_id: &[u8; 32]) -> Result<(i64, i32)> {
+    require!(
+        *account.owner == PYTH_RECEIVER_PROGRAM_ID || *account.owner == PYTH_PUSH_ORACLE_PROGRAM_ID,
+        KingTilesError::InvalidPriceFeed
+    );
+    let data = account.try_borrow_data()?;
+    // discriminator, write authority, then a `VerificationLevel` where 1 is `Full`.
+    require!(
+        data.len() >= 8 + 32 + 1 + 84
+            && data[0..8] == PYTH_PRICE_UPDATE_DISCRIMINATOR
+            && data[40] == 1,
+        KingTilesError::InvalidPriceFeed
+    );
+    let message = &data[41..];
+    require!(message[0..32] == *feed_id, KingTilesError::InvalidPriceFeed);
+    let price = i64::from_le_bytes(message[32..40].try_into().unwrap());
+    let exponent = i32::from_le_bytes(message[48..52].try_into().unwrap());
+    let publish_time = i64::from_le_bytes(message[52..60].try_into().unwrap());
+    require!(price > 0, KingTilesError::InvalidPriceFeed);
+    require!(
+        current_timestamp()?.saturating_sub(publish_time) <= MAX_PRICE_AGE_SECONDS,
+        KingTilesError::StalePriceFeed
+    );
+    Ok((price, exponent))
+}
+
+/// `usd_cents` at a SOL/USD price of `price * 10^exponent`, in lamports.
+fn usd_cents_to_lamports(usd_cents: u64, price: i64, exponent: i32) -> Option<u64> {
+    let scale = 10u128.checked_pow(exponent.unsigned_abs())?;
+    let numerator = (usd_cents as u128).checked_mul(LAMPORTS_PER_SOL as u128)?;
+    let denominator = (price as u128).checked_mul(100)?;
+    let lamports = if exponent < 0 {
+        numerator.checked_mul(scale)?.checked_div(denominator)?
+    } else {
+        numerator.checked_div(denominator.checked_mul(scale)?)?
+    };
+    u64::try_from(lamports).ok()
+}
+
+fn layout_spawn_positions(layout: SpawnLayout, board_side_len: u8) -> [u8; MAX_PLAYERS] {
+    if layout == SpawnLayout::Corners {
+        corner_spawn_positions(board_side_len)
+    } else {
+        default_spawn_positions()
+    }
+}
+
+/// Moves an unfilled lobby onto a larger mode's grid: walls keep their row and column, seated
+/// players move to their slot's new spawn cell, and a fixed-start king re-centres.
+fn relayout_lobby(board: &mut Board, board_side_len: u8, max_players: u8) -> Result<()> {
+    let old_side = board.board_side_len as usize;
+    let side = board_side_len as usize;
+    let remap = |cell: usize| {
+        cell.checked_div(old_side)
+            .unwrap()
+            .checked_mul(side)
+            .unwrap()
+            .checked_add(cell % old_side)
+            .unwrap()
+    };
+    let spawn_positions = layout_spawn_positions(board.options.spawn_layout, board_side_len);
+    let spawns = &spawn_positions[..max_players as usize];
+    let king_start = king_starting_position(board_side_len);
+
+    let mut grid = [EMPTY; BOARD_SIZE];
+    let king_position = match board.king_current_position {
+        NO_POSITION => NO_POSITION,
+        _ if !board.options.random_king_start => king_start as u8,
+        position => remap(position as usize) as u8,
+    };
+    if king_position != NO_POSITION {
+        require!(
+            !spawns.contains(&king_position),
+            KingTilesError::InvalidGameConfig
+        );
+        grid[king_position as usize] = KING_MARK;
+    }
+    for (slot, player) in board.players.iter_mut().enumerate() {
+        player.current_position = spawn_positions[slot] as i16;
+        grid[spawn_positions[slot] as usize] = player.id;
+    }
+    for cell in 0..board.active_board_cells() {
+        if board.cell(cell) == WALL_MARK {
+            let target = remap(cell);
+            require!(
+                grid[target] == EMPTY && !spawns.contains(&(target as u8)),
+                KingTilesError::InvalidWallCell
+            );
+            grid[target] = WALL_MARK;
+        }
+    }
+
+    board.board = grid;
+    if board.powerup_push_distance == default_push_distance(board.board_side_len) {
+        board.powerup_push_distance = default_push_distance(board_side_len);
+    }
+    board.board_side_len = board_side_len;
+    board.max_players = max_players;
+    board.spawn_positions = spawn_positions;
+    board.king_start_position = king_start as u8;
+    board.king_current_position = king_position;
+    Ok(())
+}
+
+/// Fills a blank `board` from a pre-versioning one. Settings the original program did not have
+/// take the values `start_game_session` gives a new board, a push charge becomes a held `Push`,
+/// and the single bomb moves into the first bomb slot.
+fn upgrade_legacy_board(legacy: &LegacyBoardV0, board: &mut Board, now: i64) {
+    board.reset_round();
+    board.game_id = legacy.game_id;
+    board.version = BOARD_VERSION;
+    board.board_side_len = legacy.board_side_len;
+    board.max_players = legacy.max_players;
+    board.registration_fee_lamports = legacy.registration_fee_lamports;
+    board.lamports_per_score = legacy.lamports_per_score;
+    board.powerup_push_distance = LEGACY_POWERUP_PUSH_DISTANCE;
+    board.game_duration_seconds = DEFAULT_GAME_DURATION_SECONDS;
+    board.spawn_positions = layout_spawn_positions(SpawnLayout::TopRow, legacy.board_side_len);
+    board.king_start_position = king_starting_position(legacy.board_side_len) as u8;
+    for (cell, &mark) in legacy.board.iter().enumerate() {
+        board.set_cell(cell, mark);
+    }
+
+    board.players = legacy
+        .players
+        .iter()
+        .map(|legacy_player| {
+            let mut player = Player::new(
+                legacy_player.player,
+                legacy_player.id,
+                legacy_player.current_position as u8,
+                [0; 32],
+            );
+            player.score = legacy_player.score;
+            if legacy_player.powerup_score > 0 {
+                player.powerup = Some(PowerupKind::Push);
+            }
+            player
+        })
+        .collect();
+    board.players_count = legacy.players_count;
+    board.fees_collected_lamports = legacy
+        .registration_fee_lamports
+        .checked_mul(legacy.players_count as u64)
+        .unwrap();
+    if legacy.players_count == legacy.max_players {
+        board.max_total_payout = board.fees_collected_lamports;
+    }
+
+    board.is_active = legacy.is_active;
+    board.last_move_timestamp = legacy.last_move_timestamp;
+    board.game_end_timestamp = legacy.game_end_timestamp;
+    board.king_current_position = legacy.king_current_position;
+    // Occupancy now accrues by time, so a player already on the king starts earning from here.
+    if legacy.is_active
+        && (1..=MAX_PLAYERS as u8).contains(&legacy.board[legacy.king_current_position as usize])
+    {
+        board.king_occupied_since = now;
+    }
+    board.powerup_current_position = legacy.powerup_current_position;
+    let bomb = legacy.bomb_current_position as usize;
+    if legacy.board[bomb] == BOMB_MARK {
+        board.arm_bomb(bomb);
+    }
+}
+
+/// A third of the side: 2 cells on 8x8, 3 on 10x10, 4 on 12x12.
+fn default_push_distance(board_side_len: u8) -> u8 {
+    board_side_len / 3
+}
+
+fn valid_mode(board_side_len: u8, max_players: u8) -> bool {
+    (board_side_len == 8 && max_players == 2)
+        || (board_side_len == 10 && max_players == 4)
+        || (board_side_len == 12 && max_players == 6)
+}
+
+/// King start, spawn points, and walls must be in bounds and pairwise distinct.
+fn valid_map_layout(board_side_len: u8, king_start: u8, spawn_points: &[u8], walls: &[u8]) -> bool {
+    let active_cells = (board_side_len as usize)
+        .checked_mul(board_side_len as usize)
+        .unwrap();
+    let mut cells = [EMPTY; BOARD_SIZE];
+    for &cell in [king_start].iter().chain(spawn_points).chain(walls) {
+        let cell = cell as usize;
+        if cell >= active_cells || cells[cell] != EMPTY {
+            return false;
+        }
+        cells[cell] = WALL_MARK;
+    }
+    true
+}
+
+#[vrf]
+#[derive(Accounts)]
+#[instruction(client_seed: u8, game_id: u64)]
+pub struct RequestRandomnessForRandomEvent<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury_signer: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury_signer.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+
+    /// CHECK: The oracle queue
+    #[account(mut, address = ephemeral_vrf_sdk::consts::DEFAULT_EPHEMERAL_QUEUE)]
+    pub oracle_queue: AccountInfo<'info>,
+}
+
+#[vrf]
+#[derive(Accounts)]
+#[instruction(client_seed: u8, game_id: u64)]
+pub struct RequestRandomnessForBombDrop<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury_signer: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury_signer.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+
+    /// CHECK: The oracle queue
+    #[account(mut, address = ephemeral_vrf_sdk::consts::DEFAULT_EPHEMERAL_QUEUE)]
+    pub oracle_queue: AccountInfo<'info>,
+}
+
+#[vrf]
+#[derive(Accounts)]
+#[instruction(client_seed: u8, game_id: u64)]
+pub struct RequestRandomnessForTick<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury_signer: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury_signer.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+
+    /// CHECK: The oracle queue
+    #[account(mut, address = ephemeral_vrf_sdk::consts::DEFAULT_EPHEMERAL_QUEUE)]
+    pub oracle_queue: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct UsePower<'info> {
+    /// CHECK: Treasury pubkey validated by address constraint, used only for PDA derivation
+    #[account(address = TREASURY)]
+    pub treasury: AccountInfo<'info>,
+
+    /// Either the treasury (relayer path) or the player who owns the powerup.
+    pub authority: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+#[derive(Accounts)]
+#[instruction(game_id: u64, board_side_len: u8, max_players: u8)]
+pub struct ExpandLobby<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(
+        mut,
+        realloc = Board::space(max_players),
+        realloc::payer = treasury,
+        realloc::zero = false,
+        seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()],
+        bump
+    )]
+    pub board_account: Account<'info, Board>,
+
+    #[account(mut, seeds = [b"registry"], bump)]
+    pub registry: Account<'info, GameRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct SetGameVariant<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct SetCampingDecay<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct SetBombPenalty<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct SetPowerupPushDistance<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct SetPushCap<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct SetWarmup<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct SetHalftime<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct SetFeeEscalation<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct SetSpectatorFee<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct MigrateBoard<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    /// CHECK: May not deserialize under the current layout until migrated; owner and seeds are
+    /// checked here and the discriminator when `migrate_board` decodes it.
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()],
+        bump
+    )]
+    pub board_account: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct CloseBoard<'info> {
+    #[account(
+        mut,
+        close = treasury,
+        seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()],
+        bump
+    )]
+    pub board_account: Account<'info, Board>,
+
+    #[account(mut)]
+    pub treasury: Signer<'info>,
+
+    #[account(mut, seeds = [b"registry"], bump)]
+    pub registry: Account<'info, GameRegistry>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct UpdatePlayerScore<'info> {
+    /// Any staked relayer may crank; tips only accrue on calls that credit time.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [b"relayer", payer.key().as_ref()],
+        bump,
+        constraint = relayer.stake_lamports >= MIN_RELAYER_STAKE_LAMPORTS @ KingTilesError::InsufficientRelayerStake,
+        constraint = relayer.unbonding_at == 0 @ KingTilesError::RelayerUnbonding
+    )]
+    pub relayer: Account<'info, Relayer>,
+
+    /// CHECK: Treasury pubkey validated by address constraint, used only for PDA derivation
+    #[account(address = TREASURY)]
+    pub treasury: AccountInfo<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct SeedPoison<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct SpreadPoison<'info> {
+    /// Any staked relayer may crank; tips only accrue on calls that spread.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [b"relayer", payer.key().as_ref()],
+        bump,
+        constraint = relayer.stake_lamports >= MIN_RELAYER_STAKE_LAMPORTS @ KingTilesError::InsufficientRelayerStake,
+        constraint = relayer.unbonding_at == 0 @ KingTilesError::RelayerUnbonding
+    )]
+    pub relayer: Account<'info, Relayer>,
+
+    /// CHECK: Treasury pubkey validated by address constraint, used only for PDA derivation
+    #[account(address = TREASURY)]
+    pub treasury: AccountInfo<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct FundSponsorVault<'info> {
+    #[account(mut)]
+    pub sponsor: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = sponsor,
+        space = 8 + SponsorVault::INIT_SPACE,
+        seeds = [b"sponsor_vault".as_ref(), &game_id.to_le_bytes()],
+        bump
+    )]
+    pub sponsor_vault: Account<'info, SponsorVault>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct MintTrophy<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+
+    /// CHECK: Must be the round's top scorer, checked in the handler
+    pub winner: AccountInfo<'info>,
+
+    /// CHECK: Fresh trophy mint, one per round; created and initialised in the handler
+    #[account(
+        mut,
+        seeds = [
+            b"trophy".as_ref(),
+            &game_id.to_le_bytes(),
+            &board_account.game_end_timestamp.to_le_bytes()
+        ],
+        bump
+    )]
+    pub trophy_mint: AccountInfo<'info>,
+
+    /// CHECK: Winner's associated token account, created by the associated token program
+    #[account(mut, address = associated_token_address(&winner.key(), &trophy_mint.key()))]
+    pub winner_token_account: AccountInfo<'info>,
+
+    /// CHECK: Metaplex metadata PDA of the trophy mint
+    #[account(mut, address = metadata_address(&trophy_mint.key()))]
+    pub metadata: AccountInfo<'info>,
+
+    /// CHECK: Metaplex master edition PDA of the trophy mint
+    #[account(mut, address = master_edition_address(&trophy_mint.key()))]
+    pub master_edition: AccountInfo<'info>,
+
+    /// CHECK: PDA signing as mint, freeze, and update authority of every trophy
+    #[account(seeds = [b"trophy_authority"], bump)]
+    pub trophy_authority: AccountInfo<'info>,
+
+    /// CHECK: SPL Token program, validated by address
+    #[account(address = SPL_TOKEN_PROGRAM_ID)]
+    pub token_program: AccountInfo<'info>,
+
+    /// CHECK: Associated token program, validated by address
+    #[account(address = ASSOCIATED_TOKEN_PROGRAM_ID)]
+    pub associated_token_program: AccountInfo<'info>,
+
+    /// CHECK: Metaplex Token Metadata program, validated by address
+    #[account(address = TOKEN_METADATA_PROGRAM_ID)]
+    pub token_metadata_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct MintXp<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    /// CHECK: The configured XP mint
+    #[account(mut, address = config.xp_mint)]
+    pub xp_mint: AccountInfo<'info>,
+
+    /// CHECK: PDA signing as mint authority of the XP mint
+    #[account(seeds = [b"xp_authority"], bump)]
+    pub xp_authority: AccountInfo<'info>,
+
+    /// CHECK: SPL Token program, validated by address
+    #[account(address = SPL_TOKEN_PROGRAM_ID)]
+    pub token_program: AccountInfo<'info>,
+
+    /// CHECK: Associated token program, validated by address
+    #[account(address = ASSOCIATED_TOKEN_PROGRAM_ID)]
+    pub associated_token_program: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(achievement_id: u8)]
+pub struct CreateAchievement<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(
+        init,
+        payer = treasury,
+        space = 8 + Achievement::INIT_SPACE,
+        seeds = [b"achievement".as_ref(), &[achievement_id]],
+        bump
+    )]
+    pub achievement: Account<'info, Achievement>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(achievement_id: u8, player: Pubkey)]
+pub struct AwardAchievement<'info> {
+    #[account(seeds = [b"achievement".as_ref(), &[achievement_id]], bump)]
+    pub achievement: Account<'info, Achievement>,
+
+    #[account(mut, seeds = [b"profile", player.as_ref()], bump)]
+    pub profile: Account<'info, PlayerProfile>,
+}
+
+#[derive(Accounts)]
+pub struct CreatePlayerProfile<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + PlayerProfile::INIT_SPACE,
+        seeds = [b"profile", authority.key().as_ref()],
+        bump
+    )]
+    pub profile: Account<'info, PlayerProfile>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct SetPlayerIdentity<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: May predate the identity fields; owner and seeds are checked here and the
+    /// discriminator when `set_player_identity` decodes it.
+    #[account(mut, owner = crate::ID, seeds = [b"profile", authority.key().as_ref()], bump)]
+    pub profile: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateReferralAccount<'info> {
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+
+    #[account(
+        init,
+        payer = referrer,
+        space = 8 + ReferralAccount::INIT_SPACE,
+        seeds = [b"referral", referrer.key().as_ref()],
+        bump
+    )]
+    pub referral_account: Account<'info, ReferralAccount>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferralRewards<'info> {
+    #[account(mut)]
+    pub referrer: Signer<'info>,
+
+    #[account(mut, seeds = [b"referral", referrer.key().as_ref()], bump)]
+    pub referral_account: Account<'info, ReferralAccount>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct UpdatePlayerProfiles<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct RedeemPowerupCredit<'info> {
+    /// CHECK: Treasury pubkey validated by address constraint, used only for PDA derivation
+    #[account(address = TREASURY)]
+    pub treasury: AccountInfo<'info>,
+
+    pub payer: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+
+    /// Read only: it lives on the base layer while the board is delegated.
+    #[account(
+        seeds = [
+            b"credit_reservation".as_ref(),
+            payer.key().as_ref(),
+            &game_id.to_le_bytes(),
+            &board_account.round.to_le_bytes()
+        ],
+        bump
+    )]
+    pub reservation: Account<'info, CreditReservation>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64, round: u32)]
+pub struct ReservePowerupCredit<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut, seeds = [b"profile", payer.key().as_ref()], bump)]
+    pub profile: Account<'info, PlayerProfile>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + CreditReservation::INIT_SPACE,
+        seeds = [
+            b"credit_reservation".as_ref(),
+            payer.key().as_ref(),
+            &game_id.to_le_bytes(),
+            &round.to_le_bytes()
+        ],
+        bump
+    )]
+    pub reservation: Account<'info, CreditReservation>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64, round: u32)]
+pub struct CloseCreditReservation<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        close = payer,
+        seeds = [
+            b"credit_reservation".as_ref(),
+            payer.key().as_ref(),
+            &game_id.to_le_bytes(),
+            &round.to_le_bytes()
+        ],
+        bump
+    )]
+    pub reservation: Account<'info, CreditReservation>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct SendEmote<'info> {
+    /// CHECK: Treasury pubkey validated by address constraint, used only for PDA derivation
+    #[account(address = TREASURY)]
+    pub treasury: AccountInfo<'info>,
+
+    pub payer: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct SettleRatings<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+pub struct CreateJackpotVault<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(
+        init,
+        payer = treasury,
+        space = 8 + JackpotVault::INIT_SPACE,
+        seeds = [b"jackpot"],
+        bump
+    )]
+    pub jackpot_vault: Account<'info, JackpotVault>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(season_id: u32)]
+pub struct StartSeason<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(
+        init,
+        payer = treasury,
+        space = 8 + Season::INIT_SPACE,
+        seeds = [b"season".as_ref(), &season_id.to_le_bytes()],
+        bump
+    )]
+    pub season: Account<'info, Season>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(season_id: u32)]
+pub struct FundSeason<'info> {
+    #[account(mut)]
+    pub funder: Signer<'info>,
+
+    #[account(mut, seeds = [b"season".as_ref(), &season_id.to_le_bytes()], bump)]
+    pub season: Account<'info, Season>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(season_id: u32, game_id: u64, player: Pubkey)]
+pub struct RecordSeasonResult<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(mut, seeds = [b"season".as_ref(), &season_id.to_le_bytes()], bump)]
+    pub season: Account<'info, Season>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+
+    #[account(
+        init_if_needed,
+        payer = treasury,
+        space = 8 + SeasonEntry::INIT_SPACE,
+        seeds = [b"season_entry".as_ref(), &season_id.to_le_bytes(), player.as_ref()],
+        bump
+    )]
+    pub season_entry: Account<'info, SeasonEntry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(quest_id: u8)]
+pub struct RefreshDailyQuest<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = treasury,
+        space = 8 + DailyQuest::INIT_SPACE,
+        seeds = [b"quest".as_ref(), &[quest_id]],
+        bump
+    )]
+    pub quest: Account<'info, DailyQuest>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(quest_id: u8, game_id: u64, player: Pubkey)]
+pub struct RecordQuestProgress<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(seeds = [b"quest".as_ref(), &[quest_id]], bump)]
+    pub quest: Account<'info, DailyQuest>,
+
+    #[account(seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+
+    #[account(
+        init_if_needed,
+        payer = treasury,
+        space = 8 + QuestProgress::INIT_SPACE,
+        seeds = [b"quest_progress".as_ref(), &[quest_id], player.as_ref()],
+        bump
+    )]
+    pub quest_progress: Account<'info, QuestProgress>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(quest_id: u8)]
+pub struct ClaimQuestReward<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(mut, seeds = [b"quest".as_ref(), &[quest_id]], bump)]
+    pub quest: Account<'info, DailyQuest>,
+
+    #[account(mut, seeds = [b"quest_progress".as_ref(), &[quest_id], player.key().as_ref()], bump)]
+    pub quest_progress: Account<'info, QuestProgress>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct PlaceBet<'info> {
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+
+    /// CHECK: Treasury pubkey validated by address constraint, used only for PDA derivation
+    #[account(address = TREASURY)]
+    pub treasury: AccountInfo<'info>,
+
+    #[account(seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+
+    #[account(
+        init_if_needed,
+        payer = bettor,
+        space = 8 + BettingPool::INIT_SPACE,
+        seeds = [
+            b"betting_pool".as_ref(),
+            &game_id.to_le_bytes(),
+            &board_account.round.to_le_bytes()
+        ],
+        bump
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    #[account(
+        init_if_needed,
+        payer = bettor,
+        space = 8 + Bet::INIT_SPACE,
+        seeds = [
+            b"bet".as_ref(),
+            &game_id.to_le_bytes(),
+            &board_account.round.to_le_bytes(),
+            bettor.key().as_ref()
+        ],
+        bump
+    )]
+    pub bet: Account<'info, Bet>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64, round: u32)]
+pub struct SettleBets<'info> {
+    /// CHECK: Treasury pubkey validated by address constraint, used only for PDA derivation
+    #[account(address = TREASURY)]
+    pub treasury: AccountInfo<'info>,
+
+    #[account(seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+
+    #[account(
+        mut,
+        seeds = [b"betting_pool".as_ref(), &game_id.to_le_bytes(), &round.to_le_bytes()],
+        bump
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64, round: u32)]
+pub struct ClaimBet<'info> {
+    #[account(mut)]
+    pub bettor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"betting_pool".as_ref(), &game_id.to_le_bytes(), &round.to_le_bytes()],
+        bump
+    )]
+    pub betting_pool: Account<'info, BettingPool>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"bet".as_ref(),
+            &game_id.to_le_bytes(),
+            &round.to_le_bytes(),
+            bettor.key().as_ref()
+        ],
+        bump
+    )]
+    pub bet: Account<'info, Bet>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct JoinAsSpectator<'info> {
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// CHECK: Treasury pubkey validated by address constraint, used only for PDA derivation
+    #[account(address = TREASURY)]
+    pub treasury: AccountInfo<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+
+    #[account(
+        init,
+        payer = wallet,
+        space = 8 + Spectator::INIT_SPACE,
+        seeds = [b"spectator".as_ref(), &game_id.to_le_bytes(), wallet.key().as_ref()],
+        bump
+    )]
+    pub spectator: Account<'info, Spectator>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Receives the spectator fee.
+    #[account(mut, seeds = [b"treasury_vault"], bump)]
+    pub treasury_vault: Account<'info, TreasuryVault>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct LeaveSpectator<'info> {
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// CHECK: Treasury pubkey validated by address constraint, used only for PDA derivation
+    #[account(address = TREASURY)]
+    pub treasury: AccountInfo<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+
+    #[account(
+        mut,
+        close = wallet,
+        seeds = [b"spectator".as_ref(), &game_id.to_le_bytes(), wallet.key().as_ref()],
+        bump
+    )]
+    pub spectator: Account<'info, Spectator>,
+}
+
+#[derive(Accounts)]
+#[instruction(season_id: u32)]
+pub struct PaySeasonPrize<'info> {
+    #[account(address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(mut, seeds = [b"season".as_ref(), &season_id.to_le_bytes()], bump)]
+    pub season: Account<'info, Season>,
+
+    #[account(mut, seeds = [b"season_entry".as_ref(), &season_id.to_le_bytes(), season_entry.player.as_ref()], bump)]
+    pub season_entry: Account<'info, SeasonEntry>,
+
+    /// CHECK: Prize recipient, pinned to the entry's player
+    #[account(mut, address = season_entry.player)]
+    pub player: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct Rematch<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(mut, seeds = [b"registry"], bump)]
+    pub registry: Account<'info, GameRegistry>,
+
+    #[account(mut, seeds = [b"treasury_vault"], bump)]
+    pub treasury_vault: Account<'info, TreasuryVault>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct ResetBoard<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+
+    #[account(mut, seeds = [b"registry"], bump)]
+    pub registry: Account<'info, GameRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(
+        init,
+        payer = treasury,
+        space = 8 + Config::INIT_SPACE,
+        seeds = [b"config"],
+        bump
+    )]
+    pub config: Account<'info, Config>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DepositTreasury<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = treasury,
+        space = 8 + TreasuryVault::INIT_SPACE,
+        seeds = [b"treasury_vault"],
+        bump
+    )]
+    pub treasury_vault: Account<'info, TreasuryVault>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    #[account(address = config.admin @ KingTilesError::NotAdmin)]
+    pub admin: Signer<'info>,
+
+    #[account(mut, seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(mut, seeds = [b"treasury_vault"], bump)]
+    pub treasury_vault: Account<'info, TreasuryVault>,
+
+    /// CHECK: Any account may receive a withdrawal; it is recorded in the event
+    #[account(mut)]
+    pub destination: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetXpConfig<'info> {
+    /// Checked against `config.admin` in the handler, once the config is decoded.
+    pub admin: Signer<'info>,
+
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    /// CHECK: May predate the XP fields; owner and seeds are checked here and the
+    /// discriminator when `set_xp_config` decodes it.
+    #[account(mut, owner = crate::ID, seeds = [b"config"], bump)]
+    pub config: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in the handler when XP is being turned on
+    pub xp_mint: AccountInfo<'info>,
+
+    /// CHECK: PDA that must hold the XP mint's authority
+    #[account(seeds = [b"xp_authority"], bump)]
+    pub xp_authority: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct ProposeParamChange<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init_if_needed,
+        payer = treasury,
+        space = 8 + ParamChange::INIT_SPACE,
+        seeds = [b"param_change".as_ref(), &game_id.to_le_bytes()],
+        bump
+    )]
+    pub param_change: Account<'info, ParamChange>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct ExecuteParamChange<'info> {
+    /// CHECK: Treasury validated by address - used for PDA derivation and receives the proposal rent
+    #[account(mut, address = TREASURY)]
+    pub treasury: AccountInfo<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+
+    #[account(
+        mut,
+        close = treasury,
+        seeds = [b"param_change".as_ref(), &game_id.to_le_bytes()],
+        bump
+    )]
+    pub param_change: Account<'info, ParamChange>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterRelayer<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Relayer::INIT_SPACE,
+        seeds = [b"relayer", authority.key().as_ref()],
+        bump
+    )]
+    pub relayer: Account<'info, Relayer>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UnregisterRelayer<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(mut, seeds = [b"relayer", authority.key().as_ref()], bump)]
+    pub relayer: Account<'info, Relayer>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawRelayerStake<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, close = authority, seeds = [b"relayer", authority.key().as_ref()], bump)]
+    pub relayer: Account<'info, Relayer>,
+}
+
+#[derive(Accounts)]
+pub struct SlashRelayer<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(mut, seeds = [b"relayer", relayer.authority.as_ref()], bump)]
+    pub relayer: Account<'info, Relayer>,
+
+    #[account(mut, seeds = [b"treasury_vault"], bump)]
+    pub treasury_vault: Account<'info, TreasuryVault>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct SetKingPosition<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct PauseGame<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct ExtendGame<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[cfg(feature = "slot-hash-randomness")]
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct MoveKingWithSlotHash<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+
+    /// CHECK: Slot hashes sysvar, read raw to avoid deserializing every entry
+    #[account(address = SlotHashes::id())]
+    pub recent_slothashes: AccountInfo<'info>,
+}
+
+#[vrf]
+#[derive(Accounts)]
+#[instruction(client_seed: u8, game_id: u64)]
+pub struct RequestRandomnessForKingMove<'info> {
+    /// Anyone may crank; they pay for the VRF request and collect the tip.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Treasury pubkey validated by address constraint, used only for PDA derivation
+    #[account(address = TREASURY)]
+    pub treasury: AccountInfo<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+
+    /// CHECK: The oracle queue
+    #[account(mut, address = ephemeral_vrf_sdk::consts::DEFAULT_EPHEMERAL_QUEUE)]
+    pub oracle_queue: AccountInfo<'info>,
+}
+
+#[vrf]
+#[derive(Accounts)]
+#[instruction(client_seed: u8, game_id: u64)]
+pub struct RequestRandomnessForHalftime<'info> {
+    /// Anyone may crank; they pay for the VRF request and collect the tip.
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Treasury pubkey validated by address constraint, used only for PDA derivation
+    #[account(address = TREASURY)]
+    pub treasury: AccountInfo<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+
+    /// CHECK: The oracle queue
+    #[account(mut, address = ephemeral_vrf_sdk::consts::DEFAULT_EPHEMERAL_QUEUE)]
+    pub oracle_queue: AccountInfo<'info>,
+}
+
+#[vrf]
+#[derive(Accounts)]
+#[instruction(client_seed: u8, game_id: u64)]
+pub struct RequestRandomnessForKingStart<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury_signer: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury_signer.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+
+    /// CHECK: The base-layer oracle queue; the board is not delegated before activation
+    #[account(mut, address = ephemeral_vrf_sdk::consts::DEFAULT_QUEUE)]
+    pub oracle_queue: AccountInfo<'info>,
+}
 
-        use_power_with_direction(board, player_index, power_use_direction);
+#[vrf]
+#[derive(Accounts)]
+#[instruction(client_seed: u8, game_id: u64)]
+pub struct RequestRandomnessForSpawnPlayers<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury_signer: Signer<'info>,
 
-        emit!(PowerUsedEvent {
-            player: player_id,
-            game_id: board.game_id,
-        });
-        Ok(())
-    }
-}
+    #[account(mut, seeds = [b"board", treasury_signer.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
 
-fn valid_mode(board_side_len: u8, max_players: u8) -> bool {
-    (board_side_len == 8 && max_players == 2)
-        || (board_side_len == 10 && max_players == 4)
-        || (board_side_len == 12 && max_players == 6)
+    /// CHECK: The oracle queue
+    #[account(mut, address = ephemeral_vrf_sdk::consts::DEFAULT_EPHEMERAL_QUEUE)]
+    pub oracle_queue: AccountInfo<'info>,
 }
 
 #[vrf]
 #[derive(Accounts)]
 #[instruction(client_seed: u8, game_id: u64)]
-pub struct RequestRandomnessForBombDrop<'info> {
+pub struct RequestRandomnessForPowerupMove<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury_signer: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury_signer.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+
+    /// CHECK: The oracle queue
+    #[account(mut, address = ephemeral_vrf_sdk::consts::DEFAULT_EPHEMERAL_QUEUE)]
+    pub oracle_queue: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CallbackRandomEvent<'info> {
+    #[account(address = ephemeral_vrf_sdk::consts::VRF_PROGRAM_IDENTITY)]
+    pub vrf_program_identity: Signer<'info>,
+
+    /// CHECK: Treasury key passed as non-signer; used only to derive the board PDA
+    #[account(address = TREASURY)]
+    pub treasury: AccountInfo<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &board_account.game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+pub struct CallbackBombDrop<'info> {
+    #[account(address = ephemeral_vrf_sdk::consts::VRF_PROGRAM_IDENTITY)]
+    pub vrf_program_identity: Signer<'info>,
+
+    /// CHECK: Treasury key passed as non-signer; used only to derive the board PDA
+    #[account(address = TREASURY)]
+    pub treasury: AccountInfo<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &board_account.game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+pub struct CallbackGameTick<'info> {
+    #[account(address = ephemeral_vrf_sdk::consts::VRF_PROGRAM_IDENTITY)]
+    pub vrf_program_identity: Signer<'info>,
+
+    /// CHECK: Treasury key passed as non-signer; used only to derive the board PDA
+    #[account(address = TREASURY)]
+    pub treasury: AccountInfo<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &board_account.game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+pub struct CallbackHalftime<'info> {
+    #[account(address = ephemeral_vrf_sdk::consts::VRF_PROGRAM_IDENTITY)]
+    pub vrf_program_identity: Signer<'info>,
+
+    /// CHECK: Treasury key passed as non-signer; used only to derive the board PDA
+    #[account(address = TREASURY)]
+    pub treasury: AccountInfo<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &board_account.game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+pub struct CallbackKingMove<'info> {
+    #[account(address = ephemeral_vrf_sdk::consts::VRF_PROGRAM_IDENTITY)]
+    pub vrf_program_identity: Signer<'info>,
+
+    /// CHECK: Treasury key passed as non-signer; used only to derive the board PDA
+    #[account(address = TREASURY)]
+    pub treasury: AccountInfo<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &board_account.game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+pub struct CallbackKingStart<'info> {
+    #[account(address = ephemeral_vrf_sdk::consts::VRF_PROGRAM_IDENTITY)]
+    pub vrf_program_identity: Signer<'info>,
+
+    /// CHECK: Treasury key passed as non-signer; used only to derive the board PDA
+    #[account(address = TREASURY)]
+    pub treasury: AccountInfo<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &board_account.game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+pub struct CallbackSpawnPlayers<'info> {
+    #[account(address = ephemeral_vrf_sdk::consts::VRF_PROGRAM_IDENTITY)]
+    pub vrf_program_identity: Signer<'info>,
+
+    /// CHECK: Treasury key passed as non-signer; used only to derive the board PDA
+    #[account(address = TREASURY)]
+    pub treasury: AccountInfo<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &board_account.game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+pub struct CallbackPowerupMove<'info> {
+    #[account(address = ephemeral_vrf_sdk::consts::VRF_PROGRAM_IDENTITY)]
+    pub vrf_program_identity: Signer<'info>,
+
+    /// CHECK: Treasury key passed as non-signer; used only to derive the board PDA
+    #[account(address = TREASURY)]
+    pub treasury: AccountInfo<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &board_account.game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct RegisterPlayer<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(mut,seeds=[b"board",treasury.key().as_ref(),&game_id.to_le_bytes()],bump)]
+    pub board_account: Account<'info, Board>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Treasury pubkey validated by address constraint, used only for PDA derivation
+    #[account(address = TREASURY)]
+    pub treasury: AccountInfo<'info>,
+
+    /// Receives the treasury's share of registration fees.
+    #[account(mut, seeds = [b"treasury_vault"], bump)]
+    pub treasury_vault: Account<'info, TreasuryVault>,
+
+    #[account(mut, seeds = [b"registry"], bump)]
+    pub registry: Account<'info, GameRegistry>,
+
+    /// CHECK: Parsed by `token_balance`, which checks the token program owner, mint, and holder
+    pub gate_token_account: Option<UncheckedAccount<'info>>,
+
+    #[account(mut)]
+    pub referral_account: Option<Account<'info, ReferralAccount>>,
+
+    /// CHECK: Parsed by `pyth_price`, which checks the Pyth owner, discriminator, and feed id
+    pub price_update: Option<UncheckedAccount<'info>>,
+
+    #[account(mut, seeds = [b"jackpot"], bump)]
+    pub jackpot_vault: Option<Account<'info, JackpotVault>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + WagerEscrow::INIT_SPACE,
+        seeds = [b"wager".as_ref(), &game_id.to_le_bytes()],
+        bump
+    )]
+    pub wager_escrow: Option<Account<'info, WagerEscrow>>,
+
+    #[account(seeds = [b"sponsor_vault".as_ref(), &game_id.to_le_bytes()], bump)]
+    pub sponsor_vault: Option<Account<'info, SponsorVault>>,
+
+    /// The seated player's profile, for the display name in `PlayerRegisteredEvent`.
+    pub profile: Option<Account<'info, PlayerProfile>>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct CancelWager<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
+
+    #[account(
+        mut,
+        close = treasury,
+        seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()],
+        bump
+    )]
+    pub board_account: Account<'info, Board>,
+
+    #[account(
+        mut,
+        close = treasury,
+        seeds = [b"wager".as_ref(), &game_id.to_le_bytes()],
+        bump
+    )]
+    pub wager_escrow: Account<'info, WagerEscrow>,
+
+    #[account(mut, seeds = [b"registry"], bump)]
+    pub registry: Account<'info, GameRegistry>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(mut, seeds = [b"treasury_vault"], bump)]
+    pub treasury_vault: Account<'info, TreasuryVault>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct MakeMove<'info> {
+    /// CHECK: Treasury pubkey validated by address constraint, used only for PDA derivation
+    #[account(address = TREASURY)]
+    pub treasury: AccountInfo<'info>,
+
+    pub payer: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct RevealSeed<'info> {
+    /// CHECK: Treasury pubkey validated by address constraint, used only for PDA derivation
+    #[account(address = TREASURY)]
+    pub treasury: AccountInfo<'info>,
+
+    pub payer: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct PlaceBomb<'info> {
+    /// CHECK: Treasury pubkey validated by address constraint, used only for PDA derivation
+    #[account(address = TREASURY)]
+    pub treasury: AccountInfo<'info>,
+
+    pub payer: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct UseTeleport<'info> {
+    /// CHECK: Treasury pubkey validated by address constraint, used only for PDA derivation
+    #[account(address = TREASURY)]
+    pub treasury: AccountInfo<'info>,
+
+    pub payer: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64, args: GameSessionArgs)]
+pub struct StartGameSession<'info> {
     #[account(mut, address = TREASURY)]
     pub treasury_signer: Signer<'info>,
 
-    #[account(mut, seeds = [b"board", treasury_signer.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    #[account(init,payer=treasury_signer,space=Board::space(args.max_players),seeds=[b"board",treasury_signer.key().as_ref(),&game_id.to_le_bytes()],bump)]
+    pub board_account: Account<'info, Board>,
+
+    pub system_program: Program<'info, System>,
+
+    pub map_template: Option<Account<'info, MapTemplate>>,
+
+    #[account(
+        init_if_needed,
+        payer = treasury_signer,
+        space = 8 + GameRegistry::INIT_SPACE,
+        seeds = [b"registry"],
+        bump
+    )]
+    pub registry: Account<'info, GameRegistry>,
+
+    /// Created with the first board so registration always has somewhere to pay fees.
+    #[account(
+        init_if_needed,
+        payer = treasury_signer,
+        space = 8 + TreasuryVault::INIT_SPACE,
+        seeds = [b"treasury_vault"],
+        bump
+    )]
+    pub treasury_vault: Account<'info, TreasuryVault>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_id: u64, args: GameSessionArgs)]
+pub struct StartPartnerGameSession<'info> {
+    /// Registered partner; may be a PDA signing through `invoke_signed`.
+    pub partner_authority: Signer<'info>,
+
+    #[account(mut, seeds = [b"partner", partner_authority.key().as_ref()], bump)]
+    pub partner: Account<'info, Partner>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(init,payer=payer,space=Board::space(args.max_players),seeds=[b"board",TREASURY.as_ref(),&game_id.to_le_bytes()],bump)]
     pub board_account: Account<'info, Board>,
 
-    /// CHECK: The oracle queue
-    #[account(mut, address = ephemeral_vrf_sdk::consts::DEFAULT_EPHEMERAL_QUEUE)]
-    pub oracle_queue: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+
+    pub map_template: Option<Account<'info, MapTemplate>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + GameRegistry::INIT_SPACE,
+        seeds = [b"registry"],
+        bump
+    )]
+    pub registry: Account<'info, GameRegistry>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + TreasuryVault::INIT_SPACE,
+        seeds = [b"treasury_vault"],
+        bump
+    )]
+    pub treasury_vault: Account<'info, TreasuryVault>,
 }
 
 #[derive(Accounts)]
-#[instruction(game_id: u64)]
-pub struct UsePower<'info> {
+#[instruction(authority: Pubkey)]
+pub struct RegisterPartner<'info> {
     #[account(mut, address = TREASURY)]
     pub treasury: Signer<'info>,
 
-    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
-    pub board_account: Account<'info, Board>,
-}
-#[derive(Accounts)]
-#[instruction(game_id: u64)]
-pub struct CloseBoard<'info> {
     #[account(
-        mut,
-        close = treasury,
-        seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()],
+        init,
+        payer = treasury,
+        space = 8 + Partner::INIT_SPACE,
+        seeds = [b"partner", authority.as_ref()],
         bump
     )]
-    pub board_account: Account<'info, Board>,
+    pub partner: Account<'info, Partner>,
 
-    #[account(mut)]
-    pub treasury: Signer<'info>,
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-#[instruction(game_id: u64)]
-pub struct UpdatePlayerScore<'info> {
+pub struct RemovePartner<'info> {
     #[account(mut, address = TREASURY)]
     pub treasury: Signer<'info>,
 
-    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
-    pub board_account: Account<'info, Board>,
+    #[account(mut, close = treasury, seeds = [b"partner", partner.authority.as_ref()], bump)]
+    pub partner: Account<'info, Partner>,
 }
 
 #[derive(Accounts)]
-#[instruction(game_id: u64)]
-pub struct SetKingPosition<'info> {
-    #[account(mut, address = TREASURY)]
-    pub treasury: Signer<'info>,
+#[instruction(name: String)]
+pub struct CreateMapTemplate<'info> {
+    #[account(mut)]
+    pub creator: Signer<'info>,
 
-    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
-    pub board_account: Account<'info, Board>,
+    #[account(
+        init,
+        payer = creator,
+        space = 8 + MapTemplate::INIT_SPACE,
+        seeds = [b"map_template", creator.key().as_ref(), name.as_bytes()],
+        bump
+    )]
+    pub map_template: Account<'info, MapTemplate>,
+
+    pub system_program: Program<'info, System>,
 }
 
-#[vrf]
+#[delegate]
 #[derive(Accounts)]
-#[instruction(client_seed: u8, game_id: u64)]
-pub struct RequestRandomnessForKingMove<'info> {
+#[instruction(game_id: u64)]
+pub struct DelegateBoard<'info> {
     #[account(mut, address = TREASURY)]
     pub treasury_signer: Signer<'info>,
 
-    #[account(mut, seeds = [b"board", treasury_signer.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    #[account(mut, seeds=[b"board",treasury_signer.key().as_ref(),&game_id.to_le_bytes()],bump)]
     pub board_account: Account<'info, Board>,
 
-    /// CHECK: The oracle queue
-    #[account(mut, address = ephemeral_vrf_sdk::consts::DEFAULT_EPHEMERAL_QUEUE)]
-    pub oracle_queue: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Delegated PDA account; constrained to match `board_account`.
+    #[account(mut, del, constraint = pda.key() == board_account.key())]
+    pub pda: AccountInfo<'info>,
 }
 
-#[vrf]
+#[delegate]
 #[derive(Accounts)]
-#[instruction(client_seed: u8, game_id: u64)]
-pub struct RequestRandomnessForPowerupMove<'info> {
+#[instruction(game_id: u64)]
+pub struct ActivateAndDelegate<'info> {
     #[account(mut, address = TREASURY)]
     pub treasury_signer: Signer<'info>,
 
     #[account(mut, seeds = [b"board", treasury_signer.key().as_ref(), &game_id.to_le_bytes()], bump)]
     pub board_account: Account<'info, Board>,
 
-    /// CHECK: The oracle queue
-    #[account(mut, address = ephemeral_vrf_sdk::consts::DEFAULT_EPHEMERAL_QUEUE)]
-    pub oracle_queue: AccountInfo<'info>,
-}
+    #[account(seeds = [b"config"], bump)]
+    pub config: Option<Account<'info, Config>>,
 
-#[derive(Accounts)]
-pub struct CallbackBombDrop<'info> {
-    #[account(address = ephemeral_vrf_sdk::consts::VRF_PROGRAM_IDENTITY)]
-    pub vrf_program_identity: Signer<'info>,
+    #[account(mut, seeds = [b"registry"], bump)]
+    pub registry: Account<'info, GameRegistry>,
 
-    /// CHECK: Treasury key passed as non-signer; used only to derive the board PDA
-    #[account(address = TREASURY)]
-    pub treasury: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
 
-    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &board_account.game_id.to_le_bytes()], bump)]
-    pub board_account: Account<'info, Board>,
+    /// CHECK: Delegated PDA account; constrained to match `board_account`.
+    #[account(mut, del, constraint = pda.key() == board_account.key())]
+    pub pda: AccountInfo<'info>,
 }
 
+#[commit]
 #[derive(Accounts)]
-pub struct CallbackKingMove<'info> {
-    #[account(address = ephemeral_vrf_sdk::consts::VRF_PROGRAM_IDENTITY)]
-    pub vrf_program_identity: Signer<'info>,
-
-    /// CHECK: Treasury key passed as non-signer; used only to derive the board PDA
-    #[account(address = TREASURY)]
-    pub treasury: AccountInfo<'info>,
+#[instruction(game_id: u64)]
+pub struct RedelegateBoard<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
 
-    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &board_account.game_id.to_le_bytes()], bump)]
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
     pub board_account: Account<'info, Board>,
 }
 
+#[delegate]
 #[derive(Accounts)]
-pub struct CallbackPowerupMove<'info> {
-    #[account(address = ephemeral_vrf_sdk::consts::VRF_PROGRAM_IDENTITY)]
-    pub vrf_program_identity: Signer<'info>,
-
-    /// CHECK: Treasury key passed as non-signer; used only to derive the board PDA
-    #[account(address = TREASURY)]
-    pub treasury: AccountInfo<'info>,
+#[instruction(game_id: u64)]
+pub struct CompleteRedelegation<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury_signer: Signer<'info>,
 
-    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &board_account.game_id.to_le_bytes()], bump)]
+    #[account(mut, seeds = [b"board", treasury_signer.key().as_ref(), &game_id.to_le_bytes()], bump)]
     pub board_account: Account<'info, Board>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Delegated PDA account; constrained to match `board_account`.
+    #[account(mut, del, constraint = pda.key() == board_account.key())]
+    pub pda: AccountInfo<'info>,
 }
 
+#[commit]
 #[derive(Accounts)]
 #[instruction(game_id: u64)]
-pub struct RegisterPlayer<'info> {
-    #[account(mut)]
-    pub payer: Signer<'info>,
+pub struct EndGameSession<'info> {
+    #[account(mut, address = TREASURY)]
+    pub treasury: Signer<'info>,
 
-    #[account(mut,seeds=[b"board",treasury.key().as_ref(),&game_id.to_le_bytes()],bump)]
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
     pub board_account: Account<'info, Board>,
 
     pub system_program: Program<'info, System>,
+}
 
-    /// CHECK: Treasury validated by address - receives registration fees
+#[commit]
+#[derive(Accounts)]
+#[instruction(game_id: u64)]
+pub struct CheckpointBoard<'info> {
     #[account(mut, address = TREASURY)]
-    pub treasury: AccountInfo<'info>,
+    pub treasury: Signer<'info>,
+
+    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: Account<'info, Board>,
 }
 
+#[commit]
 #[derive(Accounts)]
 #[instruction(game_id: u64)]
-pub struct MakeMove<'info> {
+pub struct ForceRecoverBoard<'info> {
+    #[account(mut, address = config.admin @ KingTilesError::NotAdmin)]
+    pub admin: Signer<'info>,
+
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
+
     /// CHECK: Treasury pubkey validated by address constraint, used only for PDA derivation
     #[account(address = TREASURY)]
     pub treasury: AccountInfo<'info>,
 
-    pub payer: Signer<'info>,
-
     #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
     pub board_account: Account<'info, Board>,
 }
 
 #[derive(Accounts)]
-#[instruction(game_id: u64)]
-pub struct StartGameSession<'info> {
-    #[account(mut, address = TREASURY)]
-    pub treasury_signer: Signer<'info>,
+#[instruction(game_id: u64, round: u32)]
+pub struct RefundStrandedBoard<'info> {
+    #[account(mut, address = config.admin @ KingTilesError::NotAdmin)]
+    pub admin: Signer<'info>,
 
-    #[account(init,payer=treasury_signer,space=8 + Board::INIT_SPACE,seeds=[b"board",treasury_signer.key().as_ref(),&game_id.to_le_bytes()],bump)]
-    pub board_account: Account<'info, Board>,
+    #[account(seeds = [b"config"], bump)]
+    pub config: Account<'info, Config>,
 
-    pub system_program: Program<'info, System>,
-}
+    /// CHECK: Owned by the delegation program while stranded; the handler checks the owner
+    /// and decodes the last committed `Board` itself.
+    #[account(seeds = [b"board", TREASURY.as_ref(), &game_id.to_le_bytes()], bump)]
+    pub board_account: UncheckedAccount<'info>,
 
-#[delegate]
-#[derive(Accounts)]
-#[instruction(game_id: u64)]
-pub struct DelegateBoard<'info> {
-    #[account(mut, address = TREASURY)]
-    pub treasury_signer: Signer<'info>,
+    #[account(mut, seeds = [b"treasury_vault"], bump)]
+    pub treasury_vault: Account<'info, TreasuryVault>,
 
-    #[account(mut, seeds=[b"board",treasury_signer.key().as_ref(),&game_id.to_le_bytes()],bump)]
-    pub board_account: Account<'info, Board>,
+    #[account(mut, seeds = [b"wager".as_ref(), &game_id.to_le_bytes()], bump)]
+    pub wager_escrow: Option<Account<'info, WagerEscrow>>,
 
-    pub system_program: Program<'info, System>,
+    #[account(
+        init,
+        payer = admin,
+        space = 8 + StrandedRefund::INIT_SPACE,
+        seeds = [
+            b"stranded_refund".as_ref(),
+            &game_id.to_le_bytes(),
+            &round.to_le_bytes()
+        ],
+        bump
+    )]
+    pub stranded_refund: Account<'info, StrandedRefund>,
 
-    /// CHECK: Delegated PDA account; constrained to match `board_account`.
-    #[account(mut, del, constraint = pda.key() == board_account.key())]
-    pub pda: AccountInfo<'info>,
+    pub system_program: Program<'info, System>,
 }
 
-#[commit]
 #[derive(Accounts)]
 #[instruction(game_id: u64)]
-pub struct EndGameSession<'info> {
+pub struct DistributeRewards<'info> {
     #[account(mut, address = TREASURY)]
     pub treasury: Signer<'info>,
 
     #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
     pub board_account: Account<'info, Board>,
 
+    /// CHECK: Must still be empty: `refund_stranded_board` creates it when it refunds the round.
+    #[account(
+        seeds = [
+            b"stranded_refund".as_ref(),
+            &game_id.to_le_bytes(),
+            &board_account.round.to_le_bytes()
+        ],
+        bump
+    )]
+    pub stranded_refund: UncheckedAccount<'info>,
+
     pub system_program: Program<'info, System>,
+
+    #[account(mut, seeds = [b"sponsor_vault".as_ref(), &game_id.to_le_bytes()], bump)]
+    pub sponsor_vault: Option<Account<'info, SponsorVault>>,
+
+    #[account(mut, seeds = [b"jackpot"], bump)]
+    pub jackpot_vault: Option<Account<'info, JackpotVault>>,
+
+    #[account(mut, seeds = [b"wager".as_ref(), &game_id.to_le_bytes()], bump)]
+    pub wager_escrow: Option<Account<'info, WagerEscrow>>,
+
+    #[account(mut, seeds = [b"treasury_vault"], bump)]
+    pub treasury_vault: Account<'info, TreasuryVault>,
 }
 
 #[derive(Accounts)]
 #[instruction(game_id: u64)]
-pub struct DistributeRewards<'info> {
+pub struct RecordGameSummary<'info> {
     #[account(mut, address = TREASURY)]
     pub treasury: Signer<'info>,
 
-    #[account(mut, seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
+    #[account(seeds = [b"board", treasury.key().as_ref(), &game_id.to_le_bytes()], bump)]
     pub board_account: Account<'info, Board>,
 
+    #[account(
+        init,
+        payer = treasury,
+        space = 8 + GameSummary::INIT_SPACE,
+        seeds = [
+            b"game_summary".as_ref(),
+            &game_id.to_le_bytes(),
+            &board_account.game_end_timestamp.to_le_bytes()
+        ],
+        bump
+    )]
+    pub game_summary: Account<'info, GameSummary>,
+
     pub system_program: Program<'info, System>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::entrypoint::SUCCESS;
+    use solana_sysvar::program_stubs::{set_syscall_stubs, SyscallStubs};
+
+    const TEST_NOW: i64 = 1_700_000_000;
+
+    /// Answers `Clock::get` off-chain so movement code that stamps stuns can run in tests.
+    struct FixedClock;
+
+    impl SyscallStubs for FixedClock {
+        fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+            let clock = Clock {
+                unix_timestamp: TEST_NOW,
+                ..Clock::default()
+            };
+            unsafe { (var_addr as *mut Clock).write_unaligned(clock) };
+            SUCCESS
+        }
+    }
+
+    /// Bytes of a 2-player 8x8 board as the original program left it mid-round: player 1 on
+    /// the king holding a push charge, player 2 on cell 9, a live bomb and a powerup on the grid.
+    fn legacy_board_fixture(first: Pubkey, second: Pubkey) -> Vec<u8> {
+        let mut grid = [EMPTY; BOARD_SIZE];
+        grid[27] = 1;
+        grid[9] = 2;
+        grid[40] = BOMB_MARK;
+        grid[50] = POWERUP_MARK;
+
+        let mut data = Board::DISCRIMINATOR.to_vec();
+        data.extend(42u64.to_le_bytes());
+        data.extend(2u32.to_le_bytes());
+        for (wallet, score, position, id, powerup_score) in
+            [(first, 7u64, 27i16, 1u8, 1u64), (second, 3, 9, 2, 0)]
+        {
+            data.extend(wallet.to_bytes());
+            data.extend(score.to_le_bytes());
+            data.extend(position.to_le_bytes());
+            data.push(id);
+            data.extend(powerup_score.to_le_bytes());
+        }
+        data.push(1);
+        data.extend(grid);
+        data.extend([8, 2]);
+        data.extend(1_000_000u64.to_le_bytes());
+        data.extend(29_000u64.to_le_bytes());
+        data.extend([2, 27]);
+        data.extend(1_700_000_000i64.to_le_bytes());
+        data.extend(1_700_000_060i64.to_le_bytes());
+        data.extend([50, 40]);
+        // The original program sized every board for six players.
+        data.resize(509, 0);
+        data
+    }
+
+    fn blank_board(max_players: u8) -> Board {
+        let mut data = vec![0u8; Board::space(max_players)];
+        data[..8].copy_from_slice(Board::DISCRIMINATOR);
+        Board::try_deserialize(&mut &data[..]).unwrap()
+    }
+
+    #[test]
+    fn legacy_board_upgrades_field_by_field() {
+        let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let data = legacy_board_fixture(first, second);
+        assert_eq!(data.len(), 8 + LegacyBoardV0::INIT_SPACE);
+        let legacy = LegacyBoardV0::deserialize(&mut &data[8..]).unwrap();
+
+        let mut board = blank_board(legacy.max_players);
+        upgrade_legacy_board(&legacy, &mut board, 1_700_000_030);
+
+        assert_eq!(board.version, BOARD_VERSION);
+        assert_eq!(board.game_id, 42);
+        assert!(board.is_active);
+        assert_eq!((board.board_side_len, board.max_players), (8, 2));
+        assert_eq!(board.registration_fee_lamports, 1_000_000);
+        assert_eq!(board.lamports_per_score, 29_000);
+        assert_eq!(board.game_end_timestamp, 1_700_000_060);
+        assert_eq!(board.fees_collected_lamports, 2_000_000);
+        assert_eq!(board.max_total_payout, 2_000_000);
+        assert_eq!(board.powerup_push_distance, LEGACY_POWERUP_PUSH_DISTANCE);
+
+        assert_eq!(board.players_count, 2);
+        assert_eq!(board.players[0].player, first);
+        assert_eq!(
+            (board.players[0].score, board.players[0].current_position),
+            (7, 27)
+        );
+        assert!(board.players[0].powerup == Some(PowerupKind::Push));
+        assert_eq!(board.players[1].player, second);
+        assert_eq!(
+            (board.players[1].score, board.players[1].current_position),
+            (3, 9)
+        );
+        assert!(board.players[1].powerup.is_none());
+
+        assert_eq!(board.king_current_position, 27);
+        assert_eq!(board.king_occupied_since, 1_700_000_030);
+        assert_eq!((board.cell(27), board.cell(9)), (1, 2));
+        assert_eq!(board.cell(50), POWERUP_MARK);
+        assert_eq!(board.powerup_current_position, 50);
+        assert_eq!(board.cell(40), BOMB_MARK);
+        assert_eq!(board.bomb_positions[0], 40);
+        assert_eq!(board.bomb_fuses[0], BOMB_FUSE_KING_MOVES);
+        assert!(board.bomb_positions[1..].iter().all(|&p| p == NO_POSITION));
+        assert_eq!(board.jackpot_position, NO_POSITION);
+
+        // The result fits and round-trips in an account sized for the current layout.
+        let mut stored = vec![0u8; Board::space(2)];
+        board.try_serialize(&mut &mut stored[..]).unwrap();
+        let reloaded = Board::try_deserialize(&mut &stored[..]).unwrap();
+        assert_eq!(reloaded.players[1].player, second);
+        assert_eq!(reloaded.cell(40), BOMB_MARK);
+    }
+
+    #[test]
+    fn current_boards_never_match_the_legacy_size() {
+        for max_players in [2, 4, 6] {
+            assert_ne!(Board::space(max_players), 8 + LegacyBoardV0::INIT_SPACE);
+        }
+    }
+
+    #[test]
+    fn poison_always_leaves_room_to_place_items() {
+        let mut board = blank_board(2);
+        board.board_side_len = 8;
+        board.board = [EMPTY; BOARD_SIZE];
+        board.king_current_position = NO_POSITION;
+        poison_cell(&mut board, 0);
+
+        let mut randomness = [0u8; 32];
+        for round in 0..BOARD_SIZE {
+            randomness[0] = round as u8;
+            spread_poison_once(&mut board, &randomness);
+        }
+
+        assert_eq!(empty_cell_count(&board), POISON_MIN_EMPTY_CELLS);
+        assert!(!spread_poison_once(&mut board, &randomness));
+        assert!(probe_item_cell(&board, 0).is_some());
+    }
+
+    /// An 8x8 board with players `1..=count` standing on cells `0..count` of the top row.
+    fn board_with_row_of_players(count: u8) -> Board {
+        set_syscall_stubs(Box::new(FixedClock));
+        let mut board = blank_board(MAX_PLAYERS as u8);
+        board.board_side_len = 8;
+        board.board = [EMPTY; BOARD_SIZE];
+        board.king_current_position = NO_POSITION;
+        for id in 1..=count {
+            let cell = id - 1;
+            board
+                .players
+                .push(Player::new(Pubkey::new_unique(), id, cell, [0; 32]));
+            board.set_cell(cell as usize, id);
+        }
+        board.players_count = count;
+        board
+    }
+
+    fn positions(board: &Board) -> Vec<i16> {
+        board.players.iter().map(|p| p.current_position).collect()
+    }
+
+    #[test]
+    fn push_cascades_down_a_line_of_players() {
+        let mut board = board_with_row_of_players(4);
+        let outcome = check_board_for_new_position(Pubkey::default(), &mut board, 0, 1, 1).unwrap();
+
+        assert!(outcome == MoveOutcome::Moved);
+        // Each victim takes the cell of the one ahead; the last is bumped two cells clear.
+        assert_eq!(positions(&board), vec![1, 2, 3, 5]);
+        assert_eq!(board.cell(0), EMPTY);
+        assert_eq!(
+            (
+                board.cell(1),
+                board.cell(2),
+                board.cell(3),
+                board.cell(4),
+                board.cell(5)
+            ),
+            (1, 2, 3, EMPTY, 4)
+        );
+        assert_eq!(board.players[0].stunned_until, 0);
+        for victim in &board.players[1..] {
+            assert_eq!(victim.stunned_until, TEST_NOW + STUN_DURATION_SECONDS);
+        }
+    }
+
+    #[test]
+    fn push_chain_stops_at_a_wall() {
+        let mut board = board_with_row_of_players(3);
+        board.set_cell(3, WALL_MARK);
+        let before = board.board;
+        let outcome = check_board_for_new_position(Pubkey::default(), &mut board, 0, 1, 1).unwrap();
+
+        assert!(outcome == MoveOutcome::PushBlocked);
+        assert_eq!(positions(&board), vec![0, 1, 2]);
+        assert_eq!(board.board, before);
+        assert!(board.players.iter().all(|p| p.stunned_until == 0));
+    }
+
+    #[test]
+    fn push_chain_longer_than_the_cap_is_blocked() {
+        let count = MAX_PUSH_CHAIN_DEPTH + 2;
+        let mut board = board_with_row_of_players(count);
+        let before = board.board;
+        let outcome = check_board_for_new_position(Pubkey::default(), &mut board, 0, 1, 1).unwrap();
+
+        assert!(outcome == MoveOutcome::PushBlocked);
+        assert_eq!(positions(&board), (0..count as i16).collect::<Vec<_>>());
+        assert_eq!(board.board, before);
+        assert!(board.players.iter().all(|p| p.stunned_until == 0));
+    }
+
+    #[test]
+    fn usd_cents_convert_at_either_exponent_sign() {
+        // $5 at $150/SOL, quoted the way Pyth does (exponent -8) and as whole dollars.
+        assert_eq!(
+            usd_cents_to_lamports(500, 15_000_000_000, -8),
+            Some(33_333_333)
+        );
+        assert_eq!(usd_cents_to_lamports(500, 150, 0), Some(33_333_333));
+        // A price of 1 * 10^2 is $100/SOL, so $5 is a twentieth of a SOL.
+        assert_eq!(
+            usd_cents_to_lamports(500, 1, 2),
+            Some(LAMPORTS_PER_SOL / 20)
+        );
+    }
+
+    #[test]
+    fn usd_cents_conversion_rejects_overflow() {
+        assert_eq!(usd_cents_to_lamports(u64::MAX, 1, -8), None);
+        assert_eq!(usd_cents_to_lamports(500, 150, -40), None);
+        assert_eq!(usd_cents_to_lamports(500, 150, 40), None);
+    }
+
+    const TEST_FEED_ID: [u8; 32] = [7; 32];
+
+    /// Bytes of a `PriceUpdateV2` account for `TEST_FEED_ID` at `price * 10^-8`.
+    fn price_update_fixture(verification_level: u8, price: i64, publish_time: i64) -> Vec<u8> {
+        let mut data = PYTH_PRICE_UPDATE_DISCRIMINATOR.to_vec();
+        data.extend(Pubkey::new_unique().to_bytes());
+        data.push(verification_level);
+        data.extend(TEST_FEED_ID);
+        data.extend(price.to_le_bytes());
+        data.extend(1_000u64.to_le_bytes());
+        data.extend((-8i32).to_le_bytes());
+        data.extend(publish_time.to_le_bytes());
+        data.extend(publish_time.to_le_bytes());
+        data.extend(price.to_le_bytes());
+        data.extend(1_000u64.to_le_bytes());
+        data.extend(0u64.to_le_bytes());
+        data
+    }
+
+    fn read_price(owner: Pubkey, mut data: Vec<u8>, feed_id: &[u8; 32]) -> Result<(i64, i32)> {
+        set_syscall_stubs(Box::new(FixedClock));
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let account = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+        pyth_price(&account, feed_id)
+    }
+
+    #[test]
+    fn pyth_price_reads_a_fresh_verified_update() {
+        for owner in [PYTH_RECEIVER_PROGRAM_ID, PYTH_PUSH_ORACLE_PROGRAM_ID] {
+            let data = price_update_fixture(1, 15_000_000_000, TEST_NOW - MAX_PRICE_AGE_SECONDS);
+            assert_eq!(
+                read_price(owner, data, &TEST_FEED_ID).unwrap(),
+                (15_000_000_000, -8)
+            );
+        }
+    }
+
+    #[test]
+    fn pyth_price_rejects_untrusted_updates() {
+        let fresh = price_update_fixture(1, 15_000_000_000, TEST_NOW);
+        let invalid: Error = KingTilesError::InvalidPriceFeed.into();
+
+        let foreign_owner = read_price(Pubkey::new_unique(), fresh.clone(), &TEST_FEED_ID);
+        assert_eq!(foreign_owner.unwrap_err(), invalid);
+
+        let mut wrong_discriminator = fresh.clone();
+        wrong_discriminator[0] ^= 1;
+        let result = read_price(PYTH_RECEIVER_PROGRAM_ID, wrong_discriminator, &TEST_FEED_ID);
+        assert_eq!(result.unwrap_err(), invalid);
+
+        // Variant 0 is `VerificationLevel::Partial`.
+        let partial = price_update_fixture(0, 15_000_000_000, TEST_NOW);
+        let result = read_price(PYTH_RECEIVER_PROGRAM_ID, partial, &TEST_FEED_ID);
+        assert_eq!(result.unwrap_err(), invalid);
+
+        let truncated = fresh[..8 + 32 + 1 + 83].to_vec();
+        let result = read_price(PYTH_RECEIVER_PROGRAM_ID, truncated, &TEST_FEED_ID);
+        assert_eq!(result.unwrap_err(), invalid);
+
+        let result = read_price(PYTH_RECEIVER_PROGRAM_ID, fresh, &[8; 32]);
+        assert_eq!(result.unwrap_err(), invalid);
+
+        let free = price_update_fixture(1, 0, TEST_NOW);
+        let result = read_price(PYTH_RECEIVER_PROGRAM_ID, free, &TEST_FEED_ID);
+        assert_eq!(result.unwrap_err(), invalid);
+    }
+
+    #[test]
+    fn pyth_price_rejects_stale_updates() {
+        let stale = price_update_fixture(1, 15_000_000_000, TEST_NOW - MAX_PRICE_AGE_SECONDS - 1);
+        let result = read_price(PYTH_RECEIVER_PROGRAM_ID, stale, &TEST_FEED_ID);
+        assert_eq!(
+            result.unwrap_err(),
+            Error::from(KingTilesError::StalePriceFeed)
+        );
+    }
+}
@@ -1,42 +1,234 @@
-use crate::constants::{BOMB_MARK, EMPTY, KING_MARK, POWERUP_MARK, POWERUP_SCORE};
-use crate::events::{PlayerScoredBombEvent, PlayerScoredEvent, PlayerScoredPowerupEvent};
-use crate::state::Board;
+use crate::constants::{
+    BOMB_KIT_MARK, BOMB_KIT_SPAWN_ODDS, BOMB_MARK, BOMB_RANDOMNESS_DOMAIN,
+    BONUS_POWERUP_RANDOMNESS_DOMAIN, DOUBLE_POWERUPS_DURATION_SECONDS, EMPTY,
+    EVASION_INTERVAL_SECONDS, EVASION_POINTS, FREEZE_ALL_SECONDS, GOLDEN_KING_DURATION_SECONDS,
+    GOLDEN_KING_ODDS, GOLDEN_KING_RANDOMNESS_DOMAIN, GUARD_MARK, GUARD_RANDOMNESS_DOMAIN,
+    JACKPOT_MARK, JACKPOT_SPAWN_ODDS, KING_CATCH_POINTS, KING_CONTEST_THRESHOLD, KING_MARK,
+    KING_RANDOMNESS_DOMAIN, KING_STEAL_BONUS_POINTS, KING_STREAK_MAX_BONUS, MAX_BOMBS,
+    MAX_BOMB_INVENTORY, MAX_GUARDS, MAX_PLAYERS, MAX_PUSH_CHAIN_DEPTH, NO_POSITION,
+    POISON_DRAIN_POINTS, POISON_MARK, POISON_MIN_EMPTY_CELLS, POWERUP_MARK,
+    POWERUP_RANDOMNESS_DOMAIN, PUSH_CAP_WINDOW_SECONDS, RESPAWN_IMMUNITY_SECONDS,
+    STUN_DURATION_SECONDS, WALL_MARK,
+};
+use crate::error::KingTilesError;
+use crate::events::{
+    BombDetonatedEvent, BombDropEvent, BombKitCollectedEvent, BombPenaltyEvent, CampingDecayEvent,
+    GlobalEventTriggeredEvent, GoldenKingEvent, GuardsMovedEvent, HalftimeEvent, JackpotHitEvent,
+    JackpotSpawnedEvent, KingContestedEvent, KingDroppedEvent, KingEvadedEvent, KingMoveEvent,
+    KingPickedUpEvent, KingPlayerCaughtEvent, KingPulledEvent, KingStolenEvent, KingStreakEvent,
+    PlayerImmuneEvent, PlayerScoredBombEvent, PlayerScoredEvent, PlayerScoredPowerupEvent,
+    PlayerStunnedEvent, PoisonDrainEvent, PoisonSpreadEvent, PowerupExpiredEvent, PowerupMoveEvent,
+    ScoreStolenEvent, ScoreTickEvent, ShieldAbsorbedEvent,
+};
+use crate::state::{Board, Direction, GameVariant, GlobalEvent, KingRelocation, PowerupKind};
 use anchor_lang::prelude::*;
+use solana_sha256_hasher::hashv;
+
+pub fn current_timestamp() -> Result<i64> {
+    Ok(Clock::get()?.unix_timestamp)
+}
+
+/// Consumes one shield charge if the player's shield is up.
+pub fn absorb_with_shield(board: &mut Board, player_index: usize) -> Result<bool> {
+    if !board.players[player_index].shield_active(current_timestamp()?) {
+        return Ok(false);
+    }
+    let charges = board.players[player_index].shield_charges;
+    board.players[player_index].shield_charges = charges.checked_sub(1).unwrap();
+    emit!(ShieldAbsorbedEvent {
+        player: board.players[player_index].player,
+        game_id: board.game_id,
+    });
+    Ok(true)
+}
+
+pub fn expire_stale_powerup(board: &mut Board, player_index: usize, now: i64) {
+    if !board.players[player_index].powerup_expired(now) {
+        return;
+    }
+    let kind = board.players[player_index].powerup.take().unwrap();
+    emit!(PowerupExpiredEvent {
+        player: board.players[player_index].player,
+        game_id: board.game_id,
+        kind,
+    });
+}
+
+/// Shared by every player action on the board: push stuns, `FreezeAll`, and the halftime
+/// freeze all work by raising `stunned_until`.
+pub fn require_not_stunned(board: &Board, player_index: usize, now: i64) -> Result<()> {
+    require!(
+        now >= board.players[player_index].stunned_until,
+        KingTilesError::PlayerStunned
+    );
+    Ok(())
+}
+
+/// Respawn immunity deflects pushes and bombs for free; otherwise a shield charge is spent.
+pub fn deflects_hit(board: &mut Board, player_index: usize) -> Result<bool> {
+    if current_timestamp()? < board.players[player_index].immune_until {
+        return Ok(true);
+    }
+    absorb_with_shield(board, player_index)
+}
 
 #[inline(always)]
 pub fn player_id_to_index(player_id: u8) -> usize {
     player_id.checked_sub(1).expect("player_id must be >= 1") as usize
 }
 
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum MoveOutcome {
+    Moved,
+    /// A wall fills the destination.
+    Blocked,
+    /// The player in the way could not be pushed: a wall, a full cell, or too long a chain.
+    PushBlocked,
+    /// Respawn immunity or a shield stopped the push; the shield charge is spent.
+    Deflected,
+    /// A player in the way has already taken `board.push_cap` pushes this window.
+    PushCapped,
+    Warped,
+}
+
+impl MoveOutcome {
+    /// Whether the mover actually left its cell.
+    pub fn moved(self) -> bool {
+        matches!(self, MoveOutcome::Moved | MoveOutcome::Warped)
+    }
+}
+
 pub fn check_board_for_new_position(
     payer_key: Pubkey,
     board: &mut Board,
     player_index: usize,
     new_position: usize,
     move_position: i16,
-) {
-    let cell = board.board[new_position];
-    if cell == EMPTY {
+) -> Result<MoveOutcome> {
+    resolve_move(
+        payer_key,
+        board,
+        player_index,
+        new_position,
+        move_position,
+        0,
+    )
+}
+
+/// `depth` counts how many players are already being pushed ahead of this one in
+/// the current chain; chains longer than `MAX_PUSH_CHAIN_DEPTH` are blocked.
+fn resolve_move(
+    payer_key: Pubkey,
+    board: &mut Board,
+    player_index: usize,
+    new_position: usize,
+    move_position: i16,
+    depth: u8,
+) -> Result<MoveOutcome> {
+    let cell = board.cell(new_position);
+    if is_obstacle(cell) {
+        return Ok(MoveOutcome::Blocked);
+    }
+    if is_open(cell) {
         new_position_is_empty(board, player_index, new_position);
     } else if cell == KING_MARK {
-        new_position_is_king(board, player_index, new_position);
+        new_position_is_king(board, player_index, new_position)?;
         emit!(PlayerScoredEvent {
             player: payer_key,
             game_id: board.game_id,
         });
     } else if cell == BOMB_MARK {
-        new_position_is_bomb(board, player_index, new_position);
+        return new_position_is_bomb(board, player_index, new_position);
     } else if cell == POWERUP_MARK {
-        new_position_is_powerup(board, player_index, new_position);
+        new_position_is_powerup(board, player_index, new_position)?;
+    } else if cell == BOMB_KIT_MARK {
+        new_position_is_bomb_kit(board, player_index, new_position);
+    } else if cell == JACKPOT_MARK {
+        new_position_is_jackpot(board, player_index, new_position);
+    } else if catches_king_player(board, player_index, cell)? {
+        return catch_king_player(board, player_index, new_position);
     } else {
-        new_position_is_occupied_by_player(board, player_index, move_position, new_position);
+        return new_position_is_occupied_by_player(
+            board,
+            player_index,
+            move_position,
+            new_position,
+            depth,
+        );
+    }
+    Ok(MoveOutcome::Moved)
+}
+
+fn catches_king_player(board: &Board, player_index: usize, collided_id: u8) -> Result<bool> {
+    Ok(board.variant == GameVariant::KingPlayer
+        && !board.in_warmup(current_timestamp()?)
+        && board.king_player != 0
+        && collided_id == board.king_player
+        && board.players[player_index].id != board.king_player)
+}
+
+/// The hunter takes the king-player's cell and `KING_CATCH_POINTS`; the king-player respawns
+/// with immunity and their evasion clock restarts.
+fn catch_king_player(
+    board: &mut Board,
+    hunter_index: usize,
+    new_position: usize,
+) -> Result<MoveOutcome> {
+    let king_index = player_id_to_index(board.king_player);
+    if deflects_hit(board, king_index)? {
+        return Ok(MoveOutcome::Deflected);
     }
+    award_evasion(board)?;
+    board.players[hunter_index].score = board.players[hunter_index]
+        .score
+        .checked_add(KING_CATCH_POINTS)
+        .unwrap();
+    emit!(KingPlayerCaughtEvent {
+        hunter: board.players[hunter_index].player,
+        king_player: board.players[king_index].player,
+        game_id: board.game_id,
+        points: KING_CATCH_POINTS,
+    });
+    warp_player_to_spawn(board, king_index)?;
+    board.last_evasion_award_timestamp = current_timestamp()?.min(board.game_end_timestamp);
+    new_position_is_empty(board, hunter_index, new_position);
+    Ok(MoveOutcome::Moved)
+}
+
+/// Credits the king-player for each whole `EVASION_INTERVAL_SECONDS` since the last award.
+pub fn award_evasion(board: &mut Board) -> Result<u64> {
+    let since = board.last_evasion_award_timestamp;
+    if board.variant != GameVariant::KingPlayer || board.king_player == 0 || since == 0 {
+        return Ok(0);
+    }
+    let now = current_timestamp()?.min(board.game_end_timestamp);
+    let intervals = (now.checked_sub(since).unwrap().max(0) / EVASION_INTERVAL_SECONDS) as u64;
+    if intervals == 0 {
+        return Ok(0);
+    }
+    let points = intervals.checked_mul(EVASION_POINTS).unwrap();
+    let king_index = player_id_to_index(board.king_player);
+    board.players[king_index].score = board.players[king_index].score.checked_add(points).unwrap();
+    board.last_evasion_award_timestamp = since
+        .checked_add(
+            EVASION_INTERVAL_SECONDS
+                .checked_mul(intervals as i64)
+                .unwrap(),
+        )
+        .unwrap();
+    emit!(KingEvadedEvent {
+        player: board.players[king_index].player,
+        game_id: board.game_id,
+        points,
+        intervals,
+    });
+    Ok(points)
 }
 
 pub fn new_position_is_empty(board: &mut Board, player_index: usize, new_position: usize) {
     let current_position = board.players[player_index].current_position;
-    board.board[new_position] = board.players[player_index].id;
-    board.board[current_position as usize] = EMPTY;
+    board.set_cell(new_position, board.players[player_index].id);
+    board.vacate(current_position as usize);
     board.players[player_index].current_position = new_position as i16;
 }
 
@@ -45,28 +237,73 @@ pub fn new_position_is_occupied_by_player(
     player_index: usize,
     move_position: i16,
     new_position: usize,
-) {
+    depth: u8,
+) -> Result<MoveOutcome> {
+    if depth >= MAX_PUSH_CHAIN_DEPTH {
+        return Ok(MoveOutcome::PushBlocked);
+    }
     let board_cells = board.active_board_cells();
     let board_side_len = board.board_side_len as i16;
-    let collision_player_id = board.board[new_position];
+    let collision_player_id = board.cell(new_position);
     let collision_player_index = player_id_to_index(collision_player_id);
     let collision_player_current_position = board.players[collision_player_index].current_position;
+    let displacing_king_holder = new_position == board.king_current_position as usize;
+
+    if push_capped(board, collision_player_index)? {
+        return Ok(MoveOutcome::PushCapped);
+    }
+    if deflects_hit(board, collision_player_index)? {
+        return Ok(MoveOutcome::Deflected);
+    }
 
     if move_position.abs() == 1 || move_position.abs() == board_side_len {
-        let collision_player_new_position = collision_player_current_position
-            .checked_add(move_position)
-            .unwrap()
+        let pass_through_position = collision_player_current_position
             .checked_add(move_position)
             .unwrap()
             .rem_euclid(board_cells as i16) as usize;
-        check_board_for_new_position(
+        if is_obstacle(board.cell(pass_through_position)) {
+            return Ok(MoveOutcome::PushBlocked);
+        }
+        // A player directly behind the victim stops the two-step bump short: the
+        // victim takes that cell instead and the push cascades down the line.
+        let collision_player_new_position =
+            if check_if_player_exists(pass_through_position as i16, board) {
+                pass_through_position
+            } else {
+                collision_player_current_position
+                    .checked_add(move_position)
+                    .unwrap()
+                    .checked_add(move_position)
+                    .unwrap()
+                    .rem_euclid(board_cells as i16) as usize
+            };
+        if is_obstacle(board.cell(collision_player_new_position)) {
+            return Ok(MoveOutcome::PushBlocked);
+        }
+        let victim_outcome = resolve_move(
             board.players[collision_player_index].player,
             board,
             collision_player_index,
             collision_player_new_position,
             move_position,
-        );
+            depth.checked_add(1).unwrap(),
+        )?;
+        match victim_outcome {
+            MoveOutcome::Blocked | MoveOutcome::PushBlocked => return Ok(MoveOutcome::PushBlocked),
+            MoveOutcome::Deflected => return Ok(MoveOutcome::Deflected),
+            MoveOutcome::PushCapped => return Ok(MoveOutcome::PushCapped),
+            MoveOutcome::Moved | MoveOutcome::Warped => {}
+        }
+        if victim_outcome == MoveOutcome::Warped {
+            steal_score(board, player_index, collision_player_index);
+        }
+        stun_player(board, collision_player_index)?;
+        record_push_received(board, collision_player_index);
         new_position_is_empty(board, player_index, new_position);
+        if displacing_king_holder {
+            award_king_steal(board, player_index, collision_player_index)?;
+        }
+        Ok(MoveOutcome::Moved)
     } else {
         let single_step: i16 = if move_position.abs() >= board_side_len {
             if move_position > 0 {
@@ -74,12 +311,10 @@ pub fn new_position_is_occupied_by_player(
             } else {
                 -board_side_len
             }
+        } else if move_position > 0 {
+            1
         } else {
-            if move_position > 0 {
-                1
-            } else {
-                -1
-            }
+            -1
         };
 
         let new_pos = (collision_player_current_position
@@ -87,66 +322,634 @@ pub fn new_position_is_occupied_by_player(
             .unwrap())
         .rem_euclid(board_cells as i16) as usize;
 
-        if board.board[new_pos] == EMPTY {
-            new_position_is_empty(board, collision_player_index, new_pos);
-            new_position_is_empty(board, player_index, new_position);
+        if !is_open(board.cell(new_pos)) {
+            return Ok(MoveOutcome::PushBlocked);
         }
+        new_position_is_empty(board, collision_player_index, new_pos);
+        stun_player(board, collision_player_index)?;
+        record_push_received(board, collision_player_index);
+        new_position_is_empty(board, player_index, new_position);
+        if displacing_king_holder {
+            award_king_steal(board, player_index, collision_player_index)?;
+        }
+        Ok(MoveOutcome::Moved)
+    }
+}
+
+/// Whether the player has used up `board.push_cap` for the current window, opening a fresh
+/// window once the last one has lapsed.
+fn push_capped(board: &mut Board, player_index: usize) -> Result<bool> {
+    if board.push_cap == 0 {
+        return Ok(false);
+    }
+    let now = current_timestamp()?;
+    let window_end = board.push_window_start[player_index]
+        .checked_add(PUSH_CAP_WINDOW_SECONDS)
+        .unwrap();
+    if now >= window_end {
+        board.push_window_start[player_index] = now;
+        board.pushes_received[player_index] = 0;
     }
+    Ok(board.pushes_received[player_index] >= board.push_cap)
 }
 
-pub fn new_position_is_king(board: &mut Board, player_index: usize, new_position: usize) {
-    board.board[new_position] = board.players[player_index].id;
+fn record_push_received(board: &mut Board, player_index: usize) {
+    board.pushes_received[player_index] = board.pushes_received[player_index].saturating_add(1);
+}
+
+pub fn stun_player(board: &mut Board, player_index: usize) -> Result<()> {
+    let stunned_until = current_timestamp()?
+        .checked_add(STUN_DURATION_SECONDS)
+        .unwrap();
+    board.players[player_index].stunned_until = stunned_until;
+    emit!(PlayerStunnedEvent {
+        player: board.players[player_index].player,
+        game_id: board.game_id,
+        stunned_until,
+    });
+    Ok(())
+}
+
+pub fn award_king_steal(
+    board: &mut Board,
+    attacker_index: usize,
+    victim_index: usize,
+) -> Result<()> {
+    if board.in_warmup(current_timestamp()?) {
+        return Ok(());
+    }
+    board.players[attacker_index].score = board.players[attacker_index]
+        .score
+        .checked_add(KING_STEAL_BONUS_POINTS)
+        .unwrap();
+    emit!(KingStolenEvent {
+        attacker: board.players[attacker_index].player,
+        victim: board.players[victim_index].player,
+        game_id: board.game_id,
+        points: KING_STEAL_BONUS_POINTS,
+    });
+    Ok(())
+}
+
+pub fn new_position_is_king(
+    board: &mut Board,
+    player_index: usize,
+    new_position: usize,
+) -> Result<()> {
+    board.set_cell(new_position, board.players[player_index].id);
     let current_position = board.players[player_index].current_position;
-    board.board[current_position as usize] = EMPTY;
+    board.vacate(current_position as usize);
     board.players[player_index].current_position = new_position as i16;
+    if !board.in_warmup(current_timestamp()?) {
+        let kings_captured = board.players[player_index].kings_captured;
+        board.players[player_index].kings_captured = kings_captured.checked_add(1).unwrap();
+    }
+    if board.variant == GameVariant::CarryKing {
+        pick_up_king(board, player_index);
+    }
+    if board.options.relocate_king_on_score {
+        board.king_needs_relocation = true;
+    }
+    Ok(())
+}
+
+fn pick_up_king(board: &mut Board, player_index: usize) {
+    board.king_carrier = board.players[player_index].id;
+    emit!(KingPickedUpEvent {
+        player: board.players[player_index].player,
+        game_id: board.game_id,
+        cell: board.king_current_position,
+    });
+}
+
+/// Keeps a carried king with its carrier. `own_move` is `(player_index, cell)` for a move the
+/// player chose; if the carrier left the king's cell any other way, the king drops there and
+/// whoever now stands on it picks it up.
+pub fn follow_carrier(board: &mut Board, own_move: Option<(usize, usize)>) {
+    if board.king_carrier == 0 {
+        return;
+    }
+    let carrier_index = player_id_to_index(board.king_carrier);
+    let at = board.players[carrier_index].current_position as usize;
+    let king = board.king_current_position as usize;
+    if at == king {
+        return;
+    }
+    if own_move == Some((carrier_index, at)) {
+        board.king_current_position = at as u8;
+        return;
+    }
+    board.king_carrier = 0;
+    emit!(KingDroppedEvent {
+        player: board.players[carrier_index].player,
+        game_id: board.game_id,
+        cell: king as u8,
+    });
+    let cell = board.cell(king);
+    if is_open(cell) {
+        board.set_cell(king, KING_MARK);
+    } else if check_if_player_exists(king as i16, board) {
+        pick_up_king(board, player_id_to_index(cell));
+    }
 }
 
-pub fn new_position_is_powerup(board: &mut Board, player_index: usize, new_position: usize) {
+pub fn new_position_is_powerup(
+    board: &mut Board,
+    player_index: usize,
+    new_position: usize,
+) -> Result<()> {
     let current_position = board.players[player_index].current_position;
-    board.board[new_position] = board.players[player_index].id;
+    board.set_cell(new_position, board.players[player_index].id);
     emit!(PlayerScoredPowerupEvent {
         player: board.players[player_index].player,
         game_id: board.game_id,
     });
-    board.board[current_position as usize] = EMPTY;
+    board.vacate(current_position as usize);
     board.players[player_index].current_position = new_position as i16;
-    board.players[player_index].powerup_score = POWERUP_SCORE;
+    board.players[player_index].powerup = Some(board.powerup_kind);
+    let ttl = board.options.powerup_ttl_seconds;
+    board.players[player_index].powerup_expires_at = if ttl > 0 {
+        current_timestamp()?.checked_add(ttl).unwrap()
+    } else {
+        0
+    };
+    Ok(())
 }
 
-pub fn check_if_player_exists(i: i16, board: &mut Board) -> bool {
-    if board.board[i as usize] != EMPTY
-        && board.board[i as usize] != KING_MARK
-        && board.board[i as usize] != BOMB_MARK
-        && board.board[i as usize] != POWERUP_MARK
-    {
-        return true;
-    }
-    return false;
+pub fn new_position_is_bomb_kit(board: &mut Board, player_index: usize, new_position: usize) {
+    new_position_is_empty(board, player_index, new_position);
+    let inventory = board.players[player_index].bomb_inventory;
+    board.players[player_index].bomb_inventory =
+        inventory.checked_add(1).unwrap().min(MAX_BOMB_INVENTORY);
+    emit!(BombKitCollectedEvent {
+        player: board.players[player_index].player,
+        game_id: board.game_id,
+    });
 }
 
-pub fn new_position_is_bomb(board: &mut Board, player_index: usize, new_position: usize) {
-    let board_cells = board.active_board_cells();
+pub fn new_position_is_jackpot(board: &mut Board, player_index: usize, new_position: usize) {
+    new_position_is_empty(board, player_index, new_position);
+    board.jackpot_position = NO_POSITION;
+    board.jackpot_winner = board.players[player_index].id;
+    emit!(JackpotHitEvent {
+        player: board.players[player_index].player,
+        game_id: board.game_id,
+    });
+}
+
+/// Empty or poisoned: free to walk onto.
+pub fn is_open(mark: u8) -> bool {
+    mark == EMPTY || mark == POISON_MARK
+}
+
+/// Walls and king guards: nothing moves onto them and pushes and the power beam stop there.
+pub fn is_obstacle(mark: u8) -> bool {
+    mark == WALL_MARK || mark == GUARD_MARK
+}
+
+pub fn check_if_player_exists(i: i16, board: &Board) -> bool {
+    (1..=MAX_PLAYERS as u8).contains(&board.cell(i as usize))
+}
+
+pub fn new_position_is_bomb(
+    board: &mut Board,
+    player_index: usize,
+    new_position: usize,
+) -> Result<MoveOutcome> {
+    board.set_cell(new_position, EMPTY);
+    board.clear_bomb_at(new_position);
+    if deflects_hit(board, player_index)? {
+        new_position_is_empty(board, player_index, new_position);
+        return Ok(MoveOutcome::Moved);
+    }
+    let bombs_hit = board.players[player_index].bombs_hit;
+    board.players[player_index].bombs_hit = bombs_hit.checked_add(1).unwrap();
     emit!(PlayerScoredBombEvent {
         player: board.players[player_index].player,
         game_id: board.game_id,
     });
+    let score = board.players[player_index].score;
+    let penalty = score.min(board.bomb_penalty_points);
+    if penalty > 0 {
+        board.players[player_index].score = score.checked_sub(penalty).unwrap();
+        emit!(BombPenaltyEvent {
+            player: board.players[player_index].player,
+            game_id: board.game_id,
+            points: penalty,
+        });
+    }
+    warp_player_to_spawn(board, player_index)?;
+    Ok(MoveOutcome::Warped)
+}
+
+/// Moves up to `bomb_push_steal_points` from a victim pushed onto a bomb to the pusher.
+pub fn steal_score(board: &mut Board, attacker_index: usize, victim_index: usize) {
+    let points = board.players[victim_index]
+        .score
+        .min(board.options.bomb_push_steal_points);
+    if points == 0 {
+        return;
+    }
+    board.players[victim_index].score = board.players[victim_index]
+        .score
+        .checked_sub(points)
+        .unwrap();
+    board.players[attacker_index].score = board.players[attacker_index]
+        .score
+        .checked_add(points)
+        .unwrap();
+    emit!(ScoreStolenEvent {
+        attacker: board.players[attacker_index].player,
+        victim: board.players[victim_index].player,
+        game_id: board.game_id,
+        points,
+    });
+}
+
+pub fn warp_player_to_spawn(board: &mut Board, player_index: usize) -> Result<()> {
+    let board_cells = board.active_board_cells();
     let player_id = board.players[player_index].id;
     let current_position = board.players[player_index].current_position as usize;
 
-    board.board[current_position] = EMPTY;
-    board.board[new_position] = EMPTY;
+    board.vacate(current_position);
 
-    let mut landing = player_index;
+    let mut landing = board.spawn_positions[player_index] as usize;
     for _ in 0..board_cells {
-        if board.board[landing] == EMPTY {
+        if board.cell(landing) == EMPTY {
             break;
         }
         landing = landing.checked_add(1).unwrap_or(0) % board_cells;
     }
-    board.board[landing] = player_id;
+    board.set_cell(landing, player_id);
     board.players[player_index].current_position = landing as i16;
+
+    let immune_until = current_timestamp()?
+        .checked_add(RESPAWN_IMMUNITY_SECONDS)
+        .unwrap();
+    board.players[player_index].immune_until = immune_until;
+    emit!(PlayerImmuneEvent {
+        player: board.players[player_index].player,
+        game_id: board.game_id,
+        immune_until,
+    });
+    Ok(())
+}
+
+/// Orthogonal neighbours of `cell` without wrapping across row or board edges.
+pub fn orthogonal_neighbors(board: &Board, cell: usize) -> [Option<usize>; 4] {
+    let side = board.board_side_len as usize;
+    let board_cells = board.active_board_cells();
+    let col = cell % side;
+    [
+        cell.checked_sub(side),
+        Some(cell.checked_add(side).unwrap()).filter(|&c| c < board_cells),
+        cell.checked_sub(1).filter(|_| col > 0),
+        Some(cell.checked_add(1).unwrap()).filter(|_| col < side.checked_sub(1).unwrap()),
+    ]
+}
+
+pub fn adjacent_player_count(board: &Board, cell: usize) -> usize {
+    orthogonal_neighbors(board, cell)
+        .into_iter()
+        .flatten()
+        .filter(|&neighbor| check_if_player_exists(neighbor as i16, board))
+        .count()
+}
+
+/// The king tile is contested while two or more players crowd its occupant.
+pub fn update_king_contested(board: &mut Board, occupied: bool) {
+    let contested = occupied
+        && adjacent_player_count(board, board.king_current_position as usize)
+            >= KING_CONTEST_THRESHOLD;
+    if contested == board.king_contested {
+        return;
+    }
+    board.king_contested = contested;
+    emit!(KingContestedEvent {
+        game_id: board.game_id,
+        contested,
+    });
 }
-pub fn use_power_with_direction(board: &mut Board, player_index: usize, power_use_direction: i16) {
+
+/// Index of the player standing on the king tile, if any.
+pub fn king_occupant(board: &Board) -> Option<usize> {
+    if board.king_current_position == NO_POSITION {
+        return None;
+    }
+    let cell = board.cell(board.king_current_position as usize);
+    (1..=board.players_count)
+        .contains(&cell)
+        .then(|| player_id_to_index(cell))
+}
+
+/// Credits the king's occupant for the seconds held since the last settlement and restarts
+/// the clock. Call before and after anything that can change who holds or crowds the king.
+pub fn settle_king_occupancy(board: &mut Board) -> Result<u64> {
+    follow_carrier(board, None);
+    let now = current_timestamp()?.min(board.game_end_timestamp);
+    let occupant = king_occupant(board);
+    update_king_contested(board, occupant.is_some());
+    let occupant_id = occupant.map_or(0, |player_index| board.players[player_index].id);
+    if occupant_id != board.king_streak_holder {
+        board.king_streak_holder = occupant_id;
+        board.king_streak = 0;
+    }
+    // Holding the king through warmup only counts from the moment it ends.
+    let since = match board.king_occupied_since {
+        0 => 0,
+        since => since.max(board.warmup_ends_at),
+    };
+    let mut points = 0;
+    if let Some(player_index) = occupant {
+        if since > 0 && since < now && !board.king_contested {
+            let elapsed_seconds = now.checked_sub(since).unwrap().max(0) as u64;
+            let doubled_seconds = board.players[player_index]
+                .score_multiplier_expiry
+                .min(now)
+                .checked_sub(since)
+                .unwrap()
+                .clamp(0, elapsed_seconds as i64) as u64;
+            let golden_seconds = golden_king_overlap(board, since, now, elapsed_seconds);
+            points = elapsed_seconds
+                .checked_add(doubled_seconds)
+                .unwrap()
+                .checked_add(golden_seconds)
+                .unwrap();
+            if points > 0 {
+                board.players[player_index].score = board.players[player_index]
+                    .score
+                    .checked_add(points)
+                    .unwrap();
+                emit!(ScoreTickEvent {
+                    player: board.players[player_index].player,
+                    game_id: board.game_id,
+                    points,
+                    multiplier: board.players[player_index].score_multiplier(since),
+                    elapsed_seconds,
+                    golden_seconds,
+                });
+            }
+        }
+    }
+    if board.golden_king_until != 0 && now >= board.golden_king_until {
+        board.golden_king_until = 0;
+    }
+    board.king_occupied_since = if occupant.is_some() && board.is_active {
+        now
+    } else {
+        0
+    };
+    Ok(points)
+}
+
+/// Seconds of `[since, now]` that fall inside the golden-king window.
+fn golden_king_overlap(board: &Board, since: i64, now: i64, elapsed_seconds: u64) -> u64 {
+    if board.golden_king_until == 0 {
+        return 0;
+    }
+    let window_start = board
+        .golden_king_until
+        .checked_sub(GOLDEN_KING_DURATION_SECONDS)
+        .unwrap();
+    board
+        .golden_king_until
+        .min(now)
+        .checked_sub(since.max(window_start))
+        .unwrap()
+        .clamp(0, elapsed_seconds as i64) as u64
+}
+
+/// Turns the king golden with 1-in-`GOLDEN_KING_ODDS` odds unless a window is already running.
+pub fn roll_golden_king(board: &mut Board, randomness: &[u8; 32]) -> Result<()> {
+    if board.golden_king_until != 0 || randomness[0] % GOLDEN_KING_ODDS != 0 {
+        return Ok(());
+    }
+    let until = current_timestamp()?
+        .checked_add(GOLDEN_KING_DURATION_SECONDS)
+        .unwrap()
+        .min(board.game_end_timestamp);
+    board.golden_king_until = until;
+    emit!(GoldenKingEvent {
+        game_id: board.game_id,
+        until,
+    });
+    Ok(())
+}
+
+/// Extends the king holder's streak by one scoring tick and pays the escalating bonus
+/// (1, 1, 2, 2, 3, ... up to `KING_STREAK_MAX_BONUS`). Contested ticks neither extend nor
+/// break the streak; leaving the tile resets it in `settle_king_occupancy`.
+pub fn award_king_streak(board: &mut Board) -> u64 {
+    if board.king_streak_holder == 0 || board.king_contested {
+        return 0;
+    }
+    board.king_streak = board.king_streak.saturating_add(1);
+    let bonus = (board.king_streak as u64)
+        .checked_add(1)
+        .unwrap()
+        .checked_div(2)
+        .unwrap()
+        .min(KING_STREAK_MAX_BONUS);
+    let player_index = player_id_to_index(board.king_streak_holder);
+    board.players[player_index].score = board.players[player_index]
+        .score
+        .checked_add(bonus)
+        .unwrap();
+    emit!(KingStreakEvent {
+        player: board.players[player_index].player,
+        game_id: board.game_id,
+        streak: board.king_streak,
+        bonus_points: bonus,
+    });
+    bonus
+}
+
+pub fn is_king_or_adjacent(board: &Board, cell: usize) -> bool {
+    if board.king_current_position == NO_POSITION {
+        return false;
+    }
+    let king = board.king_current_position as usize;
+    cell == king || orthogonal_neighbors(board, king).contains(&Some(cell))
+}
+
+/// Linear probe from `start` for an empty cell that is neither the king tile nor
+/// next to it, falling back to any empty cell when the board is too crowded; `None` only
+/// when no cell is empty.
+pub fn probe_item_cell(board: &Board, start: usize) -> Option<usize> {
+    let board_cells = board.active_board_cells();
+    let probe = (0..board_cells).map(|step| start.checked_add(step).unwrap() % board_cells);
+    probe
+        .clone()
+        .find(|&cell| board.cell(cell) == EMPTY && !is_king_or_adjacent(board, cell))
+        .or_else(|| probe.clone().find(|&cell| board.cell(cell) == EMPTY))
+}
+
+/// Linear probe from `start` for any empty cell.
+pub fn probe_empty_cell(board: &Board, start: usize) -> Option<usize> {
+    let board_cells = board.active_board_cells();
+    (0..board_cells)
+        .map(|step| start.checked_add(step).unwrap() % board_cells)
+        .find(|&cell| board.cell(cell) == EMPTY)
+}
+
+pub fn empty_cell_count(board: &Board) -> usize {
+    (0..board.active_board_cells())
+        .filter(|&cell| board.cell(cell) == EMPTY)
+        .count()
+}
+
+pub fn manhattan_distance(board: &Board, a: usize, b: usize) -> u32 {
+    let side = board.board_side_len as usize;
+    let rows = (a / side).abs_diff(b / side);
+    let cols = (a % side).abs_diff(b % side);
+    rows.checked_add(cols).unwrap() as u32
+}
+
+/// Picks an empty cell with probability proportional to its distance from the
+/// nearest player, so the king tends to land away from the pack.
+pub fn pick_flee_cell(board: &Board, randomness: &[u8; 32]) -> Option<usize> {
+    let board_cells = board.active_board_cells();
+    let weight = |cell: usize| -> u32 {
+        if board.cell(cell) != EMPTY {
+            return 0;
+        }
+        board.players[..board.players_count as usize]
+            .iter()
+            .map(|p| manhattan_distance(board, cell, p.current_position as usize))
+            .min()
+            .unwrap_or(1)
+    };
+    let total: u32 = (0..board_cells).map(weight).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut roll = ephemeral_vrf_sdk::rnd::random_u32(randomness) % total;
+    for cell in 0..board_cells {
+        let w = weight(cell);
+        if roll < w {
+            return Some(cell);
+        }
+        roll = roll.checked_sub(w).unwrap();
+    }
+    None
+}
+
+pub fn neighbor_in_direction(board: &Board, cell: usize, direction: Direction) -> Option<usize> {
+    let [up, down, left, right] = orthogonal_neighbors(board, cell);
+    match direction {
+        Direction::Up => up,
+        Direction::Down => down,
+        Direction::Left => left,
+        Direction::Right => right,
+    }
+}
+
+/// Walks from the king toward `target` one ring at a time and returns the first
+/// empty cell, or `None` if the walk reaches `target` first.
+pub fn next_empty_cell_toward(board: &Board, from: usize, target: usize) -> Option<usize> {
+    let side = board.board_side_len as i16;
+    let (target_row, target_col) = ((target as i16) / side, (target as i16) % side);
+    let (mut row, mut col) = ((from as i16) / side, (from as i16) % side);
+    loop {
+        row = row.checked_add((target_row - row).signum()).unwrap();
+        col = col.checked_add((target_col - col).signum()).unwrap();
+        if row == target_row && col == target_col {
+            return None;
+        }
+        let cell = row.checked_mul(side).unwrap().checked_add(col).unwrap() as usize;
+        if board.cell(cell) == EMPTY {
+            return Some(cell);
+        }
+    }
+}
+
+pub fn pull_king_toward_player(board: &mut Board, player_index: usize) -> bool {
+    if board.king_current_position == NO_POSITION || board.king_carrier != 0 {
+        return false;
+    }
+    let from = board.king_current_position as usize;
+    let player_position = board.players[player_index].current_position as usize;
+    let Some(to) = next_empty_cell_toward(board, from, player_position) else {
+        return false;
+    };
+    if board.cell(from) == KING_MARK {
+        board.vacate(from);
+    }
+    board.set_cell(to, KING_MARK);
+    board.king_current_position = to as u8;
+    emit!(KingPulledEvent {
+        player: board.players[player_index].player,
+        game_id: board.game_id,
+        from: from as u8,
+        to: to as u8,
+    });
+    true
+}
+
+pub fn tick_bomb_fuses(board: &mut Board) -> Result<()> {
+    for slot in 0..MAX_BOMBS {
+        if board.bomb_positions[slot] == NO_POSITION {
+            continue;
+        }
+        board.bomb_fuses[slot] = board.bomb_fuses[slot].saturating_sub(1);
+        if board.bomb_fuses[slot] == 0 {
+            detonate_bomb(board, slot)?;
+        }
+    }
+    Ok(())
+}
+
+/// Clears the bomb and warps every orthogonally adjacent player back to spawn.
+/// The freed slot is refilled by the next bomb drop.
+pub fn detonate_bomb(board: &mut Board, slot: usize) -> Result<()> {
+    let cell = board.bomb_positions[slot] as usize;
+    if board.cell(cell) == BOMB_MARK {
+        board.set_cell(cell, EMPTY);
+    }
+    board.clear_bomb_at(cell);
+    emit!(BombDetonatedEvent {
+        game_id: board.game_id,
+        cell: cell as u8,
+    });
+
+    for neighbor in orthogonal_neighbors(board, cell).into_iter().flatten() {
+        if !check_if_player_exists(neighbor as i16, board) {
+            continue;
+        }
+        let victim_index = player_id_to_index(board.cell(neighbor));
+        if deflects_hit(board, victim_index)? {
+            continue;
+        }
+        let bombs_hit = board.players[victim_index].bombs_hit;
+        board.players[victim_index].bombs_hit = bombs_hit.checked_add(1).unwrap();
+        emit!(PlayerScoredBombEvent {
+            player: board.players[victim_index].player,
+            game_id: board.game_id,
+        });
+        warp_player_to_spawn(board, victim_index)?;
+    }
+    Ok(())
+}
+
+/// What a `Push` beam did, for `PowerUsedEvent`.
+#[derive(Default)]
+pub struct BeamTrace {
+    /// Cells the beam crossed in order, ending at the victim or whatever stopped it.
+    pub scanned_cells: Vec<u8>,
+    /// Id of the player hit, or 0 when the beam found nobody.
+    pub victim: u8,
+    pub victim_from: u8,
+    pub victim_to: u8,
+    /// The victim had already taken `board.push_cap` pushes this window.
+    pub capped: bool,
+}
+
+pub fn use_power_with_direction(
+    board: &mut Board,
+    player_index: usize,
+    power_use_direction: i16,
+) -> Result<BeamTrace> {
+    let mut trace = BeamTrace::default();
     let board_cells = board.active_board_cells();
     let board_side_len = board.board_side_len as i16;
     let current_position = board.players[player_index].current_position;
@@ -174,33 +977,571 @@ pub fn use_power_with_direction(board: &mut Board, player_index: usize, power_us
             }
         }
 
+        trace.scanned_cells.push(i as u8);
+        if is_obstacle(board.cell(i as usize)) {
+            break;
+        }
+
         if check_if_player_exists(i, board) {
-            let attacked_player_id = board.board[i as usize];
+            let attacked_player_id = board.cell(i as usize);
             let attacked_player_index = player_id_to_index(attacked_player_id);
-            let attacked_player_current_position =
-                board.players[attacked_player_index].current_position;
+            trace.victim = attacked_player_id;
+            trace.victim_from = i as u8;
+            trace.victim_to = i as u8;
 
-            let new_position_offset = (POWERUP_SCORE as i16)
-                .checked_mul(power_use_direction)
-                .unwrap();
+            if push_capped(board, attacked_player_index)? {
+                trace.capped = true;
+                break;
+            }
+            if deflects_hit(board, attacked_player_index)? {
+                board.players[player_index].powerup = None;
+                break;
+            }
 
-            let attacked_player_new_position = attacked_player_current_position
-                .checked_add(new_position_offset)
-                .unwrap()
-                .rem_euclid(board_cells as i16)
-                as usize;
-
-            check_board_for_new_position(
-                board.players[attacked_player_index].player,
-                board,
-                attacked_player_index,
-                attacked_player_new_position,
-                new_position_offset,
-            );
-            board.players[player_index].powerup_score = 0;
+            let outcome = push_along_beam(board, attacked_player_index, power_use_direction)?;
+            if outcome == MoveOutcome::Warped {
+                steal_score(board, player_index, attacked_player_index);
+            }
+            if outcome.moved() {
+                stun_player(board, attacked_player_index)?;
+                record_push_received(board, attacked_player_index);
+            }
+            trace.victim_to = board.players[attacked_player_index].current_position as u8;
+            board.players[player_index].powerup = None;
             break;
         }
 
         i = i.checked_add(power_use_direction).unwrap();
     }
+    Ok(trace)
+}
+
+/// Shoves `player_index` up to `powerup_push_distance` cells one at a time, so each step
+/// resolves like a normal move: players in the way are bumped down the line, and a wall,
+/// guard, board edge, or blocked chain ends the push early. Returns `Moved` if it got at
+/// least one cell, `Warped` if a bomb ended it, or the first step's failure.
+fn push_along_beam(board: &mut Board, player_index: usize, step: i16) -> Result<MoveOutcome> {
+    let side = board.board_side_len as i16;
+    let mut outcome = MoveOutcome::Blocked;
+    for _ in 0..board.powerup_push_distance {
+        let from = board.players[player_index].current_position as usize;
+        let [up, down, left, right] = orthogonal_neighbors(board, from);
+        let next = match step {
+            s if s == -side => up,
+            s if s == side => down,
+            -1 => left,
+            _ => right,
+        };
+        let Some(next) = next else {
+            break;
+        };
+        let step_outcome = check_board_for_new_position(
+            board.players[player_index].player,
+            board,
+            player_index,
+            next,
+            step,
+        )?;
+        if !step_outcome.moved() {
+            if outcome == MoveOutcome::Blocked {
+                outcome = step_outcome;
+            }
+            break;
+        }
+        outcome = step_outcome;
+        if step_outcome == MoveOutcome::Warped {
+            break;
+        }
+    }
+    Ok(outcome)
+}
+
+/// Derives an independent 32-byte stream from one VRF output for a named consumer.
+pub fn expand_randomness(randomness: &[u8; 32], domain: &[u8]) -> [u8; 32] {
+    hashv(&[domain, randomness]).to_bytes()
+}
+
+/// VRF `caller_seed` for a request: the treasury's byte alone until a player reveals a seed,
+/// then folded into the revealed-seed mix so the operator cannot pick it unilaterally.
+pub fn mixed_caller_seed(board: &Board, client_seed: u8) -> [u8; 32] {
+    if board.seed_mix == [0; 32] {
+        return [client_seed; 32];
+    }
+    expand_randomness(&board.seed_mix, &[client_seed])
+}
+
+pub fn relocate_king(board: &mut Board, randomness: &[u8; 32]) -> Result<()> {
+    if board.variant == GameVariant::KingPlayer || board.king_carrier != 0 {
+        // No loose king tile to move; bombs still burn down on the king-move clock.
+        tick_bomb_fuses(board)?;
+        return Ok(());
+    }
+    settle_king_occupancy(board)?;
+    let active_cells = board.active_board_cells();
+    let king_current_position = board.king_current_position;
+    let start = ephemeral_vrf_sdk::rnd::random_u8_with_range(
+        randomness,
+        0,
+        (active_cells.checked_sub(1).unwrap()) as u8,
+    ) as usize;
+    if king_current_position != NO_POSITION
+        && board.cell(king_current_position as usize) == KING_MARK
+    {
+        board.vacate(king_current_position as usize);
+    }
+    lift_guards(board);
+    // Vacating the tile leaves at least one empty cell, so the king only stays put when a
+    // player covers it on a board with nothing else free.
+    if let Some(mut cell_index) = probe_empty_cell(board, start) {
+        if board.options.king_relocation == KingRelocation::Flee {
+            cell_index = pick_flee_cell(board, randomness).unwrap_or(cell_index);
+        }
+        board.set_cell(cell_index, KING_MARK);
+        board.king_current_position = cell_index as u8;
+        board.king_needs_relocation = false;
+        board.last_king_move_timestamp = current_timestamp()?;
+        emit!(KingMoveEvent {
+            game_id: board.game_id,
+            king_move: board.king_current_position,
+        });
+    }
+    let guard_start = expand_randomness(randomness, GUARD_RANDOMNESS_DOMAIN)[0] as usize;
+    post_guards(board, guard_start);
+    tick_bomb_fuses(board)?;
+    settle_king_occupancy(board)?;
+    roll_golden_king(
+        board,
+        &expand_randomness(randomness, GOLDEN_KING_RANDOMNESS_DOMAIN),
+    )?;
+    Ok(())
+}
+
+/// Clockwise `(row, col)` offsets of the eight cells ringing the king.
+const GUARD_RING: [(i16, i16); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+];
+
+/// Cell at `slot` (taken modulo the ring) around `king`, or `None` past a board edge.
+fn guard_ring_cell(board: &Board, king: usize, slot: usize) -> Option<usize> {
+    let side = board.board_side_len as i16;
+    let (row_step, col_step) = GUARD_RING[slot % GUARD_RING.len()];
+    let row = (king as i16 / side).checked_add(row_step).unwrap();
+    let col = (king as i16 % side).checked_add(col_step).unwrap();
+    if row < 0 || row >= side || col < 0 || col >= side {
+        return None;
+    }
+    Some(row.checked_mul(side).unwrap().checked_add(col).unwrap() as usize)
+}
+
+fn guard_ring_slot(board: &Board, king: usize, cell: usize) -> Option<usize> {
+    (0..GUARD_RING.len()).find(|&slot| guard_ring_cell(board, king, slot) == Some(cell))
+}
+
+fn lift_guards(board: &mut Board) {
+    for guard in 0..board.guards_placed as usize {
+        let cell = board.guard_positions[guard] as usize;
+        if board.cell(cell) == GUARD_MARK {
+            board.set_cell(cell, EMPTY);
+        }
+    }
+    board.guards_placed = 0;
+}
+
+fn emit_guards_moved(board: &Board) {
+    emit!(GuardsMovedEvent {
+        game_id: board.game_id,
+        guard_positions: board.guard_positions[..board.guards_placed as usize].to_vec(),
+    });
+}
+
+/// Re-posts the guards on empty cells around the king, spread evenly around the ring from
+/// `start`. Guards that find no empty cell sit the king move out.
+pub fn post_guards(board: &mut Board, start: usize) {
+    lift_guards(board);
+    if board.king_current_position == NO_POSITION {
+        return;
+    }
+    let king = board.king_current_position as usize;
+    let wanted = if board.max_players <= 2 {
+        1
+    } else {
+        MAX_GUARDS
+    };
+    let spacing = GUARD_RING.len() / wanted;
+    for guard in 0..wanted {
+        let first = start
+            .checked_add(guard.checked_mul(spacing).unwrap())
+            .unwrap();
+        let Some(cell) = (0..GUARD_RING.len())
+            .filter_map(|step| guard_ring_cell(board, king, first.checked_add(step).unwrap()))
+            .find(|&cell| board.cell(cell) == EMPTY)
+        else {
+            break;
+        };
+        board.set_cell(cell, GUARD_MARK);
+        board.guard_positions[board.guards_placed as usize] = cell as u8;
+        board.guards_placed = board.guards_placed.checked_add(1).unwrap();
+    }
+    emit_guards_moved(board);
+}
+
+/// Walks each guard one cell clockwise around the king. A player in the way is shoved one
+/// cell further along and stunned; if that cell is taken, or anything else is in the way,
+/// the guard holds. Guards left behind by a king move that skipped them regroup instead.
+pub fn step_guards(board: &mut Board) -> Result<()> {
+    if board.king_current_position == NO_POSITION {
+        return Ok(());
+    }
+    let king = board.king_current_position as usize;
+    let side = board.board_side_len as i16;
+    let placed = board.guards_placed as usize;
+    if placed == 0
+        || !board.guard_positions[..placed]
+            .iter()
+            .all(|&cell| guard_ring_slot(board, king, cell as usize).is_some())
+    {
+        post_guards(board, 0);
+        return Ok(());
+    }
+    for guard in 0..placed {
+        let from = board.guard_positions[guard] as usize;
+        let slot = guard_ring_slot(board, king, from).unwrap();
+        let Some(to) = (1..GUARD_RING.len())
+            .find_map(|step| guard_ring_cell(board, king, slot.checked_add(step).unwrap()))
+        else {
+            continue;
+        };
+        if board.cell(to) == board.king_carrier {
+            // Guards never knock the king out of its carrier's hands.
+            continue;
+        } else if check_if_player_exists(to as i16, board) {
+            // Ring neighbours can sit two cells apart where the ring is cut by an edge.
+            let row_step = (to as i16 / side - from as i16 / side).signum();
+            let col_step = (to as i16 % side - from as i16 % side).signum();
+            let (row, col) = (
+                (to as i16 / side).checked_add(row_step).unwrap(),
+                (to as i16 % side).checked_add(col_step).unwrap(),
+            );
+            if row < 0 || row >= side || col < 0 || col >= side {
+                continue;
+            }
+            let shoved_to = row.checked_mul(side).unwrap().checked_add(col).unwrap() as usize;
+            if !is_open(board.cell(shoved_to)) {
+                continue;
+            }
+            let player_index = player_id_to_index(board.cell(to));
+            new_position_is_empty(board, player_index, shoved_to);
+            stun_player(board, player_index)?;
+        } else if board.cell(to) != EMPTY {
+            continue;
+        }
+        board.set_cell(from, EMPTY);
+        board.set_cell(to, GUARD_MARK);
+        board.guard_positions[guard] = to as u8;
+    }
+    emit_guards_moved(board);
+    Ok(())
+}
+
+pub fn spawn_powerup(board: &mut Board, randomness: &[u8; 32]) -> Result<()> {
+    let active_cells = board.active_board_cells();
+    let powerup_current_position = board.powerup_current_position;
+    let start = ephemeral_vrf_sdk::rnd::random_u8_with_range(
+        randomness,
+        0,
+        (active_cells.checked_sub(1).unwrap()) as u8,
+    ) as usize;
+    let previous_mark = board.cell(powerup_current_position as usize);
+    if previous_mark == POWERUP_MARK || previous_mark == BOMB_KIT_MARK {
+        board.set_cell(powerup_current_position as usize, EMPTY);
+    }
+    let Some(cell_index) = probe_item_cell(board, start) else {
+        return Ok(());
+    };
+    let mark = if randomness[0] % BOMB_KIT_SPAWN_ODDS == 0 {
+        BOMB_KIT_MARK
+    } else {
+        POWERUP_MARK
+    };
+    board.set_cell(cell_index, mark);
+    board.powerup_kind = PowerupKind::from_randomness(randomness);
+    board.powerup_current_position = cell_index as u8;
+    emit!(PowerupMoveEvent {
+        game_id: board.game_id,
+        powerup_move: board.powerup_current_position,
+    });
+    if current_timestamp()? < board.double_powerups_until {
+        // The bonus item is not tracked in `powerup_current_position`, so later spawns leave
+        // it on the board until someone picks it up.
+        let bonus = expand_randomness(randomness, BONUS_POWERUP_RANDOMNESS_DOMAIN);
+        let start = ephemeral_vrf_sdk::rnd::random_u8_with_range(
+            &bonus,
+            0,
+            (active_cells.checked_sub(1).unwrap()) as u8,
+        ) as usize;
+        if let Some(cell) = probe_item_cell(board, start) {
+            board.set_cell(cell, POWERUP_MARK);
+        }
+    }
+    Ok(())
+}
+
+/// Applies one VRF-chosen board-wide effect and announces it.
+pub fn apply_global_event(board: &mut Board, randomness: &[u8; 32]) -> Result<()> {
+    let event = GlobalEvent::from_randomness(randomness);
+    let now = current_timestamp()?;
+    settle_king_occupancy(board)?;
+    match event {
+        GlobalEvent::SwapPlayers => swap_all_players(board, randomness),
+        GlobalEvent::ShuffleBombs => shuffle_bombs(board, randomness),
+        GlobalEvent::FreezeAll => {
+            let frozen_until = now.checked_add(FREEZE_ALL_SECONDS).unwrap();
+            for player in board.players.iter_mut() {
+                player.stunned_until = player.stunned_until.max(frozen_until);
+            }
+        }
+        GlobalEvent::DoublePowerups => {
+            board.double_powerups_until = now
+                .checked_add(DOUBLE_POWERUPS_DURATION_SECONDS)
+                .unwrap()
+                .min(board.game_end_timestamp);
+        }
+    }
+    settle_king_occupancy(board)?;
+    emit!(GlobalEventTriggeredEvent {
+        game_id: board.game_id,
+        event,
+    });
+    Ok(())
+}
+
+/// Halftime intermission: freezes everyone for `board.halftime_freeze_seconds`, then redraws
+/// the king, the powerup, and every live bomb. Runs once per round.
+pub fn run_halftime(board: &mut Board, randomness: &[u8; 32]) -> Result<()> {
+    settle_king_occupancy(board)?;
+    award_evasion(board)?;
+    let resumes_at = current_timestamp()?
+        .checked_add(board.halftime_freeze_seconds as i64)
+        .unwrap();
+    for player in board.players.iter_mut() {
+        player.stunned_until = player.stunned_until.max(resumes_at);
+    }
+    relocate_king(
+        board,
+        &expand_randomness(randomness, KING_RANDOMNESS_DOMAIN),
+    )?;
+    spawn_powerup(
+        board,
+        &expand_randomness(randomness, POWERUP_RANDOMNESS_DOMAIN),
+    )?;
+    shuffle_bombs(
+        board,
+        &expand_randomness(randomness, BOMB_RANDOMNESS_DOMAIN),
+    );
+    board.halftime_at = 0;
+    settle_king_occupancy(board)?;
+    emit!(HalftimeEvent {
+        game_id: board.game_id,
+        king_position: board.king_current_position,
+        powerup_position: board.powerup_current_position,
+        bomb_positions: board.bomb_positions.to_vec(),
+        resumes_at,
+    });
+    Ok(())
+}
+
+/// Rotates every player onto the cell of the player a VRF-chosen number of seats ahead.
+fn swap_all_players(board: &mut Board, randomness: &[u8; 32]) {
+    let count = board.players.len();
+    if count < 2 {
+        return;
+    }
+    let shift = (randomness[1] as usize % (count - 1))
+        .checked_add(1)
+        .unwrap();
+    let mut cells = [0i16; MAX_PLAYERS];
+    for (slot, player) in board.players.iter().enumerate() {
+        cells[slot] = player.current_position;
+    }
+    for slot in 0..count {
+        let cell = cells[slot.checked_add(shift).unwrap() % count];
+        board.players[slot].current_position = cell;
+        board.set_cell(cell as usize, board.players[slot].id);
+    }
+}
+
+/// Lifts every live bomb first so bombs may land on each other's old cells.
+fn shuffle_bombs(board: &mut Board, randomness: &[u8; 32]) {
+    let active_cells = board.active_board_cells();
+    let mut live = [false; MAX_BOMBS];
+    for (slot, is_live) in live.iter_mut().enumerate() {
+        let cell = board.bomb_positions[slot];
+        if cell != NO_POSITION && board.cell(cell as usize) == BOMB_MARK {
+            board.set_cell(cell as usize, EMPTY);
+            *is_live = true;
+        }
+    }
+    for (slot, &is_live) in live.iter().enumerate() {
+        if !is_live {
+            continue;
+        }
+        let start = randomness[slot.checked_add(2).unwrap()] as usize % active_cells;
+        // Lifting the bombs freed their cells, so there is always room to put them back.
+        let cell = probe_item_cell(board, start).unwrap();
+        board.set_cell(cell, BOMB_MARK);
+        board.bomb_positions[slot] = cell as u8;
+    }
+}
+
+pub fn drop_bomb(board: &mut Board, randomness: &[u8; 32]) {
+    let active_cells = board.active_board_cells();
+    let start = ephemeral_vrf_sdk::rnd::random_u8_with_range(
+        randomness,
+        0,
+        (active_cells.checked_sub(1).unwrap()) as u8,
+    ) as usize;
+    // Evict before probing so a full set of slots can reuse the oldest bomb's cell.
+    let slot = board.take_bomb_slot();
+    let oldest = board.bomb_positions[slot];
+    if oldest != NO_POSITION && board.cell(oldest as usize) == BOMB_MARK {
+        board.set_cell(oldest as usize, EMPTY);
+        board.clear_bomb_at(oldest as usize);
+    }
+    let Some(cell_index) = probe_item_cell(board, start) else {
+        return;
+    };
+    board.arm_bomb(cell_index);
+    emit!(BombDropEvent {
+        game_id: board.game_id,
+        bomb_drop: cell_index as u8,
+    });
+}
+
+/// Rarely drops the jackpot tile on jackpot-enabled boards; one per round at most.
+pub fn spawn_jackpot(board: &mut Board, randomness: &[u8; 32]) {
+    if board.options.jackpot_fee_bps == 0
+        || board.jackpot_winner != 0
+        || board.jackpot_position != NO_POSITION
+        || randomness[0] % JACKPOT_SPAWN_ODDS != 0
+    {
+        return;
+    }
+    let active_cells = board.active_board_cells();
+    let cell_index = ephemeral_vrf_sdk::rnd::random_u8_with_range(
+        randomness,
+        0,
+        (active_cells.checked_sub(1).unwrap()) as u8,
+    ) as usize;
+    let Some(cell_index) = probe_item_cell(board, cell_index) else {
+        return;
+    };
+    board.set_cell(cell_index, JACKPOT_MARK);
+    board.jackpot_position = cell_index as u8;
+    emit!(JackpotSpawnedEvent {
+        game_id: board.game_id,
+        cell: cell_index as u8,
+    });
+}
+
+/// Poisons an empty `cell`; `spread_poison` grows the patch from there.
+pub fn poison_cell(board: &mut Board, cell: usize) {
+    board.poison.set(cell, true);
+    board.set_cell(cell, POISON_MARK);
+    emit!(PoisonSpreadEvent {
+        game_id: board.game_id,
+        cell: cell as u8,
+    });
+}
+
+/// Drains `POISON_DRAIN_POINTS` from everyone standing on poison, floored at zero.
+pub fn drain_poisoned_players(board: &mut Board) {
+    for player_index in 0..board.players_count as usize {
+        let position = board.players[player_index].current_position as usize;
+        if !board.poison.get(position) {
+            continue;
+        }
+        let score = board.players[player_index].score;
+        let drained = score.min(POISON_DRAIN_POINTS);
+        if drained == 0 {
+            continue;
+        }
+        board.players[player_index].score = score.checked_sub(drained).unwrap();
+        emit!(PoisonDrainEvent {
+            player: board.players[player_index].player,
+            game_id: board.game_id,
+            points: drained,
+        });
+    }
+}
+
+/// Poisons one empty cell next to the patch, chosen by `randomness`; returns whether it grew.
+/// Only `EMPTY` cells qualify, so poison never creeps under the king, items, or players, and
+/// the patch stops growing once only `POISON_MIN_EMPTY_CELLS` are left.
+pub fn spread_poison_once(board: &mut Board, randomness: &[u8; 32]) -> bool {
+    if empty_cell_count(board) <= POISON_MIN_EMPTY_CELLS {
+        return false;
+    }
+    let board_cells = board.active_board_cells();
+    let borders_poison = |cell: usize| -> bool {
+        board.cell(cell) == EMPTY
+            && orthogonal_neighbors(board, cell)
+                .into_iter()
+                .flatten()
+                .any(|neighbor| board.poison.get(neighbor))
+    };
+    let candidates = (0..board_cells)
+        .filter(|&cell| borders_poison(cell))
+        .count();
+    if candidates == 0 {
+        return false;
+    }
+    let pick = ephemeral_vrf_sdk::rnd::random_u32(randomness) as usize % candidates;
+    let cell = (0..board_cells)
+        .filter(|&cell| borders_poison(cell))
+        .nth(pick)
+        .unwrap();
+    poison_cell(board, cell);
+    true
+}
+
+/// One camping tick: counts how long each player has held their cell and, past
+/// `camping_decay_ticks`, drains `camping_decay_points` from them, floored at zero. The king's
+/// occupant is where the action is, so they never decay.
+pub fn tick_camping_decay(board: &mut Board) {
+    if board.camping_decay_ticks == 0 {
+        return;
+    }
+    let king_holder = king_occupant(board);
+    for player_index in 0..board.players_count as usize {
+        let position = board.players[player_index].current_position;
+        if position != board.camp_positions[player_index] || king_holder == Some(player_index) {
+            board.camp_positions[player_index] = position;
+            board.camp_ticks[player_index] = 0;
+            continue;
+        }
+        let idle_ticks = board.camp_ticks[player_index].saturating_add(1);
+        board.camp_ticks[player_index] = idle_ticks;
+        if idle_ticks <= board.camping_decay_ticks {
+            continue;
+        }
+        let score = board.players[player_index].score;
+        let points = score.min(board.camping_decay_points);
+        if points == 0 {
+            continue;
+        }
+        board.players[player_index].score = score.checked_sub(points).unwrap();
+        emit!(CampingDecayEvent {
+            player: board.players[player_index].player,
+            game_id: board.game_id,
+            points,
+            idle_ticks,
+        });
+    }
 }
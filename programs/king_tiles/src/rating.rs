@@ -0,0 +1,82 @@
+use crate::constants::{ELO_K_FACTOR, MAX_PLAYERS};
+
+/// Elo expected score in per-mille for rating gaps of 0, 25, ..., 400 in the player's favour.
+const EXPECTED_SCORE_PER_MILLE: [i64; 17] = [
+    500, 536, 571, 606, 640, 673, 703, 733, 760, 785, 808, 830, 849, 867, 882, 896, 909,
+];
+
+/// Expected score of a player rated `rating` against `opponent`, in per-mille.
+/// Gaps are bucketed to 25 points and clamped at 400 to keep the maths integer-only.
+pub fn expected_score_per_mille(rating: u32, opponent: u32) -> i64 {
+    let gap = (rating as i64).checked_sub(opponent as i64).unwrap();
+    let bucket = (gap.unsigned_abs() / 25).min(16) as usize;
+    if gap >= 0 {
+        EXPECTED_SCORE_PER_MILLE[bucket]
+    } else {
+        1000 - EXPECTED_SCORE_PER_MILLE[bucket]
+    }
+}
+
+/// Rating change for each entry when every pair is scored as a head-to-head result, with
+/// the K factor spread across the field so a six-player game moves ratings like a 1v1.
+pub fn rating_deltas(ratings: &[u32], scores: &[u64]) -> [i64; MAX_PLAYERS] {
+    let mut deltas = [0i64; MAX_PLAYERS];
+    let count = ratings.len();
+    if count < 2 {
+        return deltas;
+    }
+    for i in 0..count {
+        let mut total = 0i64;
+        for j in 0..count {
+            if i == j {
+                continue;
+            }
+            let actual = match scores[i].cmp(&scores[j]) {
+                core::cmp::Ordering::Greater => 1000,
+                core::cmp::Ordering::Equal => 500,
+                core::cmp::Ordering::Less => 0,
+            };
+            let expected = expected_score_per_mille(ratings[i], ratings[j]);
+            total = total.checked_add(actual - expected).unwrap();
+        }
+        let field = ((count - 1) as i64).checked_mul(1000).unwrap();
+        deltas[i] = total.checked_mul(ELO_K_FACTOR).unwrap() / field;
+    }
+    deltas
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expected_scores_mirror_and_clamp() {
+        assert_eq!(expected_score_per_mille(1200, 1200), 500);
+        assert_eq!(expected_score_per_mille(1400, 1200), 760);
+        assert_eq!(expected_score_per_mille(1200, 1400), 240);
+        assert_eq!(expected_score_per_mille(2400, 1200), 909);
+        assert_eq!(expected_score_per_mille(1200, 2400), 91);
+    }
+
+    #[test]
+    fn even_head_to_head_moves_half_of_k() {
+        let deltas = rating_deltas(&[1200, 1200], &[10, 3]);
+        assert_eq!(&deltas[..2], &[ELO_K_FACTOR / 2, -ELO_K_FACTOR / 2]);
+        assert_eq!(&rating_deltas(&[1200, 1200], &[5, 5])[..2], &[0, 0]);
+    }
+
+    #[test]
+    fn upsets_pay_more_than_expected_wins() {
+        let favourite_wins = rating_deltas(&[1400, 1200], &[10, 3]);
+        let underdog_wins = rating_deltas(&[1400, 1200], &[3, 10]);
+        assert!(underdog_wins[1] > favourite_wins[0]);
+        assert_eq!(favourite_wins[0], -favourite_wins[1]);
+    }
+
+    #[test]
+    fn the_field_shares_one_k_factor() {
+        let deltas = rating_deltas(&[1200; 4], &[40, 30, 20, 10]);
+        assert_eq!(&deltas[..4], &[16, 5, -5, -16]);
+        assert_eq!(rating_deltas(&[1200], &[40]), [0; MAX_PLAYERS]);
+    }
+}
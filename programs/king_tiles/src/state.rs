@@ -1,7 +1,10 @@
-
 use anchor_lang::prelude::*;
 
-use crate::constants::BOARD_SIZE;
+use crate::constants::{
+    BOARD_SIZE, BOARD_WORDS, BOMB_FUSE_KING_MOVES, BOMB_MARK, EMPTY, MAX_BOMBS, MAX_BPS,
+    MAX_GUARDS, MAX_LISTINGS, MAX_PLAYERS, NO_POSITION, POISON_MARK, POWERUP_KIND_COUNT,
+    VRF_PENDING_TIMEOUT_SECONDS, WALL_MARK,
+};
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
 pub struct Player {
@@ -10,7 +13,120 @@ pub struct Player {
     pub current_position: i16,
     pub id: u8,
 
-    pub powerup_score: u64,
+    pub powerup: Option<PowerupKind>,
+    pub bomb_inventory: u8,
+    pub shield_expiry: i64,
+    pub shield_charges: u8,
+    pub score_multiplier_expiry: i64,
+    pub powerup_expires_at: i64,
+    pub stunned_until: i64,
+    pub immune_until: i64,
+    /// `sha256(seed || player)` committed at registration; all zeroes opts out.
+    pub seed_commitment: [u8; 32],
+    pub seed_revealed: bool,
+
+    pub moves_made: u32,
+    pub kings_captured: u32,
+    pub bombs_hit: u32,
+    pub powerups_used: u32,
+}
+
+impl Player {
+    pub fn new(player: Pubkey, id: u8, position: u8, seed_commitment: [u8; 32]) -> Self {
+        Player {
+            player,
+            score: 0,
+            current_position: position as i16,
+            id,
+            powerup: None,
+            bomb_inventory: 0,
+            shield_expiry: 0,
+            shield_charges: 0,
+            score_multiplier_expiry: 0,
+            powerup_expires_at: 0,
+            stunned_until: 0,
+            immune_until: 0,
+            seed_commitment,
+            seed_revealed: false,
+            moves_made: 0,
+            kings_captured: 0,
+            bombs_hit: 0,
+            powerups_used: 0,
+        }
+    }
+
+    pub fn shield_active(&self, now: i64) -> bool {
+        self.shield_charges > 0 && now < self.shield_expiry
+    }
+
+    pub fn powerup_expired(&self, now: i64) -> bool {
+        self.powerup.is_some() && self.powerup_expires_at != 0 && now >= self.powerup_expires_at
+    }
+
+    pub fn score_multiplier(&self, now: i64) -> u64 {
+        if now < self.score_multiplier_expiry {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+/// Where the board's live copy is: the base layer, the ER, or in flight back from the ER.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum DelegationState {
+    Undelegated,
+    Delegated,
+    CommitPending,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum PowerupKind {
+    Push,
+    Shield,
+    Teleport,
+    DoubleScore,
+    Magnet,
+}
+
+impl PowerupKind {
+    pub fn from_randomness(randomness: &[u8; 32]) -> Self {
+        match randomness[1] % 5 {
+            0 => PowerupKind::Push,
+            1 => PowerupKind::Shield,
+            2 => PowerupKind::Teleport,
+            3 => PowerupKind::DoubleScore,
+            _ => PowerupKind::Magnet,
+        }
+    }
+
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Board-wide effect rolled by `callback_random_event`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum GlobalEvent {
+    /// Every player takes another player's cell.
+    SwapPlayers,
+    /// Every live bomb jumps to a new cell, keeping its fuse.
+    ShuffleBombs,
+    /// Stuns every player for `FREEZE_ALL_SECONDS`.
+    FreezeAll,
+    /// Powerup spawns drop a second item for `DOUBLE_POWERUPS_DURATION_SECONDS`.
+    DoublePowerups,
+}
+
+impl GlobalEvent {
+    pub fn from_randomness(randomness: &[u8; 32]) -> Self {
+        match randomness[0] % 4 {
+            0 => GlobalEvent::SwapPlayers,
+            1 => GlobalEvent::ShuffleBombs,
+            2 => GlobalEvent::FreezeAll,
+            _ => GlobalEvent::DoublePowerups,
+        }
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
@@ -33,6 +149,123 @@ impl Direction {
     }
 }
 
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, InitSpace)]
+pub enum KingRelocation {
+    /// Uniform random cell with linear probing past occupied cells.
+    #[default]
+    Uniform,
+    /// Random cell weighted by distance from the nearest player.
+    Flee,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, InitSpace)]
+pub enum GameVariant {
+    /// Everyone races for the king tile.
+    #[default]
+    Classic,
+    /// The first registrant is the king: hunters score by catching them, and they score for
+    /// every interval they stay uncaught. There is no king tile.
+    KingPlayer,
+    /// Landing on the king picks it up and it moves with its carrier, who scores while holding
+    /// it; a push or a bomb drops it on the cell the carrier was knocked off.
+    CarryKing,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, InitSpace)]
+pub enum SpawnLayout {
+    /// Players spawn at cells `0..max_players` along the top row.
+    #[default]
+    TopRow,
+    /// Players spawn in the board corners, then the middle of the top and bottom edges.
+    Corners,
+    /// Players are re-spawned on VRF-chosen cells once the lobby fills.
+    Random,
+}
+
+/// Settings for a new game passed to `start_game_session`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct GameSessionArgs {
+    pub board_side_len: u8,
+    pub max_players: u8,
+    pub registration_fee_lamports: u64,
+    pub lamports_per_score: u64,
+    /// Inline wall cells; must be empty when a `MapTemplate` is passed.
+    pub walls: Vec<u8>,
+    pub options: GameOptions,
+}
+
+/// Per-game tuning knobs fixed at `start_game_session`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct GameOptions {
+    /// Seconds a picked-up powerup stays usable; 0 disables expiry.
+    pub powerup_ttl_seconds: i64,
+    /// Points moved from a victim to their pusher when the push lands them on a bomb.
+    pub bomb_push_steal_points: u64,
+    pub king_relocation: KingRelocation,
+    /// Leave the king unplaced until `callback_king_start` drops it on a VRF-chosen cell.
+    pub random_king_start: bool,
+    pub spawn_layout: SpawnLayout,
+    /// Flag the king for relocation as soon as a player lands on it.
+    pub relocate_king_on_score: bool,
+    /// Minimum seconds between king relocations enforced at request time; 0 disables.
+    pub king_move_interval_seconds: i64,
+    /// Lamports paid from the board's balance to whoever cranks a king move or score tick.
+    pub crank_tip_lamports: u64,
+    /// Lamports the treasury deposits into the board at session start to fund crank tips.
+    pub crank_tip_budget_lamports: u64,
+    /// Merkle root over `sha256(player)` leaves; registrants must prove membership. All zeroes
+    /// leaves the lobby open.
+    pub allowlist_root: [u8; 32],
+    /// Mint registrants must hold at least `token_gate_min_amount` of; ignored while that is 0.
+    pub token_gate_mint: Pubkey,
+    pub token_gate_min_amount: u64,
+    /// Share of each referred registration fee credited to the referrer, in basis points.
+    pub referral_fee_bps: u16,
+    /// Free-entry promotional game: registration is free and rewards are paid from the
+    /// game's `SponsorVault` instead of the treasury.
+    pub free_play: bool,
+    /// Entry fee pegged to USD cents, converted with the SOL/USD Pyth feed at registration; 0
+    /// charges the fixed `registration_fee_lamports`.
+    pub entry_fee_usd_cents: u64,
+    pub pyth_feed_id: [u8; 32],
+    /// Cut of every registration fee paid into the progressive `JackpotVault`, in basis points.
+    /// Non-zero also lets the jackpot tile spawn on this board.
+    pub jackpot_fee_bps: u16,
+    /// Head-to-head stake each of the two players escrows on registration; 0 disables.
+    pub wager_lamports: u64,
+    /// Treasury's cut of a won escrow, in basis points; ties and cancellations are not raked.
+    pub wager_rake_bps: u16,
+    /// Players scoring below this get no score reward, only `participation_refund_lamports`.
+    pub min_payout_score: u64,
+    pub participation_refund_lamports: u64,
+    /// Filling the lobby leaves the round pending until `activate_and_delegate` starts it and
+    /// delegates the board in one transaction.
+    pub delegate_on_activation: bool,
+}
+
+/// One bit per cell; cell `i` is bit `i % 64` of word `i / 64`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace, PartialEq, Eq)]
+pub struct Bitboard {
+    pub words: [u64; BOARD_WORDS],
+}
+
+impl Bitboard {
+    #[inline(always)]
+    pub fn get(&self, cell: usize) -> bool {
+        (self.words[cell / 64] >> (cell % 64)) & 1 == 1
+    }
+
+    #[inline(always)]
+    pub fn set(&mut self, cell: usize, on: bool) {
+        let bit = 1u64 << (cell % 64);
+        if on {
+            self.words[cell / 64] |= bit;
+        } else {
+            self.words[cell / 64] &= !bit;
+        }
+    }
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct Board {
@@ -47,17 +280,824 @@ pub struct Board {
     pub lamports_per_score: u64,
     pub players_count: u8,
     pub king_current_position: u8,
+    pub king_contested: bool,
+    /// Set when a player lands on the king in `relocate_king_on_score` mode; cleared by the next king move.
+    pub king_needs_relocation: bool,
     pub last_move_timestamp: i64,
+    pub last_king_move_timestamp: i64,
+    /// When the current king occupant's unsettled time started; 0 while nobody holds the king.
+    pub king_occupied_since: i64,
     pub game_end_timestamp: i64,
 
     pub powerup_current_position: u8,
-    pub bomb_current_position: u8,
+    pub powerup_kind: PowerupKind,
+    pub bomb_positions: [u8; MAX_BOMBS],
+    pub bomb_fuses: [u8; MAX_BOMBS],
+    pub spawn_positions: [u8; MAX_PLAYERS],
+    pub king_start_position: u8,
+    pub spawns_pending: bool,
+    pub options: GameOptions,
+    /// Running hash of every revealed player seed, mixed into each VRF `caller_seed`.
+    pub seed_mix: [u8; 32],
+    /// Bit `i` is set once player `i`'s profile has been updated for this round.
+    pub profiles_recorded_mask: u8,
+    /// Bit `i` is set once player `i`'s rating has been settled for this round.
+    pub ratings_settled_mask: u8,
+    /// Bit `i` is set once player `i`'s score has been recorded into a season.
+    pub season_recorded_mask: u8,
+    pub vrf_pending: u8,
+    pub last_vrf_request_timestamp: i64,
+    pub jackpot_position: u8,
+    /// Id of the player who landed on the jackpot this round; 0 until someone does.
+    pub jackpot_winner: u8,
+    pub trophy_minted: bool,
+    pub game_duration_seconds: i64,
+    /// When `pause_game` froze the round; 0 while running.
+    pub paused_at: i64,
+    /// Seconds added to the current round by `extend_game`.
+    pub extended_seconds: i64,
+    /// Join-order index of the next player `distribute_rewards` pays.
+    pub next_payout_index: u8,
+    /// Score rewards paid so far this round.
+    pub rewards_paid_lamports: u64,
+    /// Registration fees the treasury has kept since the lobby opened (or the last paid rematch).
+    pub fees_collected_lamports: u64,
+    /// Prize pool fixed at activation; score rewards are scaled down pro rata above it.
+    pub max_total_payout: u64,
+    /// Set once `distribute_rewards` has paid the last page of players this round.
+    pub rewards_distributed: bool,
+    pub last_checkpoint_timestamp: i64,
+    /// Backup validator `complete_redelegation` hands the board to; default when no failover runs.
+    pub pending_validator: Pubkey,
+    pub delegation_state: DelegationState,
+    pub delegated_at: i64,
+    pub version: u8,
+    /// Id of the player whose king streak is running; 0 while the king is unheld.
+    pub king_streak_holder: u8,
+    /// Consecutive `update_player_score` ticks `king_streak_holder` has held the king.
+    pub king_streak: u16,
+    /// End of the current golden-king window, which began `GOLDEN_KING_DURATION_SECONDS`
+    /// earlier; 0 when none is running.
+    pub golden_king_until: i64,
+    /// End of a `DoublePowerups` global event; 0 when none is running.
+    pub double_powerups_until: i64,
+    /// Cells of the king's guards; only the first `guards_placed` are on the board.
+    pub guard_positions: [u8; MAX_GUARDS],
+    pub guards_placed: u8,
+    pub last_guard_step_timestamp: i64,
+    pub variant: GameVariant,
+    /// Id of the king-player in the `KingPlayer` variant; 0 until someone registers.
+    pub king_player: u8,
+    pub last_evasion_award_timestamp: i64,
+    /// Id of the player carrying the king in the `CarryKing` variant; 0 while it lies loose.
+    pub king_carrier: u8,
+    /// Poisoned cells, including those a player, the king, or an item currently covers.
+    pub poison: Bitboard,
+    pub last_poison_spread_timestamp: i64,
+    /// Score ticks a player may hold one cell before `camping_decay_points` per tick start
+    /// draining their score; 0 disables camping decay.
+    pub camping_decay_ticks: u16,
+    pub camping_decay_points: u64,
+    /// Per seat: the cell at the last camping tick and how many ticks they have held it.
+    pub camp_positions: [i16; MAX_PLAYERS],
+    pub camp_ticks: [u16; MAX_PLAYERS],
+    pub last_camping_tick_timestamp: i64,
+    /// Points a player loses on top of the warp when they step on a bomb; 0 disables.
+    pub bomb_penalty_points: u64,
+    /// Cells the `Push` powerup shoves its target.
+    pub powerup_push_distance: u8,
+    /// Pushes a player may take per `PUSH_CAP_WINDOW_SECONDS` before further pushes against
+    /// them fail; 0 disables the cap.
+    pub push_cap: u8,
+    /// Per seat: pushes taken in the current window and when that window opened.
+    pub pushes_received: [u8; MAX_PLAYERS],
+    pub push_window_start: [i64; MAX_PLAYERS],
+    /// Free-movement seconds between activation and the round clock starting; 0 disables.
+    pub warmup_seconds: u16,
+    /// When the current round's warmup ends; scoring, bombs, and powerups wait for it.
+    pub warmup_ends_at: i64,
+    /// Seconds every player is frozen for at halftime; 0 disables halftime.
+    pub halftime_freeze_seconds: u8,
+    /// When halftime falls due this round; 0 once it has run or when disabled.
+    pub halftime_at: i64,
+    /// Basis points of `registration_fee_lamports` added to the fee for each earlier
+    /// registrant; 0 keeps the fee flat.
+    pub fee_escalation_bps: u16,
+    pub xp_minted: bool,
+    /// Per seat: the profile credit redeemed this round.
+    pub redeemed_credits: [Option<PowerupKind>; MAX_PLAYERS],
+    /// Lamports a spectator pays the treasury to join; 0 makes spectating free.
+    pub spectator_fee_lamports: u64,
+    /// Open `Spectator` accounts for this game.
+    pub viewer_count: u32,
+    /// Per seat: when its last emote was sent, for `EMOTE_COOLDOWN_SECONDS`.
+    pub last_emote_at: [i64; MAX_PLAYERS],
+    /// Per bomb slot: `bombs_armed` when its bomb was armed, so eviction takes the oldest.
+    pub bomb_armed_seq: [u32; MAX_BOMBS],
+    /// Bombs armed this round.
+    pub bombs_armed: u32,
+    /// Rounds played on this board before the current one; `reset_board` and `rematch` bump
+    /// it, and betting accounts are keyed by it so each round gets a fresh pool.
+    pub round: u32,
 }
 
 impl Board {
+    /// Mark at `cell`: `EMPTY`, a player id, or one of the `*_MARK` constants.
+    #[inline(always)]
+    pub fn cell(&self, cell: usize) -> u8 {
+        self.board[cell]
+    }
+
+    #[inline(always)]
+    pub fn set_cell(&mut self, cell: usize, mark: u8) {
+        self.board[cell] = mark;
+    }
+
+    /// Clears `cell` as its occupant leaves, uncovering any poison beneath.
+    pub fn vacate(&mut self, cell: usize) {
+        let mark = if self.poison.get(cell) {
+            POISON_MARK
+        } else {
+            EMPTY
+        };
+        self.set_cell(cell, mark);
+    }
+
+    /// Account size for a board seating `max_players`; `INIT_SPACE` reserves all `MAX_PLAYERS`
+    /// seats, and out-of-range counts fall back to it.
+    pub fn space(max_players: u8) -> usize {
+        let unused_seats = MAX_PLAYERS.saturating_sub(max_players as usize);
+        8usize
+            .checked_add(Board::INIT_SPACE)
+            .unwrap()
+            .checked_sub(unused_seats.checked_mul(Player::INIT_SPACE).unwrap())
+            .unwrap()
+    }
+
     #[inline(always)]
     pub fn active_board_cells(&self) -> usize {
         let side = self.board_side_len as usize;
         side.checked_mul(side).unwrap()
     }
+
+    /// First free bomb slot, or the oldest live one when all slots are taken.
+    pub fn take_bomb_slot(&mut self) -> usize {
+        if let Some(slot) = self.bomb_positions.iter().position(|&p| p == NO_POSITION) {
+            return slot;
+        }
+        (0..MAX_BOMBS)
+            .min_by_key(|&slot| self.bomb_armed_seq[slot])
+            .unwrap()
+    }
+
+    /// Places a live bomb at `cell`, evicting the oldest bomb if every slot is taken.
+    pub fn arm_bomb(&mut self, cell: usize) {
+        let slot = self.take_bomb_slot();
+        let previous = self.bomb_positions[slot];
+        if previous != NO_POSITION && self.cell(previous as usize) == BOMB_MARK {
+            self.set_cell(previous as usize, EMPTY);
+        }
+        self.set_cell(cell, BOMB_MARK);
+        self.bomb_positions[slot] = cell as u8;
+        self.bomb_fuses[slot] = BOMB_FUSE_KING_MOVES;
+        self.bomb_armed_seq[slot] = self.bombs_armed;
+        self.bombs_armed = self.bombs_armed.checked_add(1).unwrap();
+    }
+
+    pub fn clear_bomb_at(&mut self, cell: usize) {
+        if let Some(slot) = self.bomb_positions.iter().position(|&p| p as usize == cell) {
+            self.bomb_positions[slot] = NO_POSITION;
+            self.bomb_fuses[slot] = 0;
+        }
+    }
+
+    /// Clears everything a round leaves behind (pieces, timers, VRF and settlement flags)
+    /// while keeping the layout, options, and registered players.
+    pub fn reset_round(&mut self) {
+        for cell in 0..BOARD_SIZE {
+            if self.cell(cell) != WALL_MARK {
+                self.set_cell(cell, EMPTY);
+            }
+        }
+        self.is_active = false;
+        self.king_current_position = NO_POSITION;
+        self.king_contested = false;
+        self.king_needs_relocation = false;
+        self.last_move_timestamp = 0;
+        self.last_king_move_timestamp = 0;
+        self.king_occupied_since = 0;
+        self.game_end_timestamp = 0;
+        self.powerup_current_position = 0;
+        self.powerup_kind = PowerupKind::Push;
+        self.bomb_positions = [NO_POSITION; MAX_BOMBS];
+        self.bomb_fuses = [0; MAX_BOMBS];
+        self.bomb_armed_seq = [0; MAX_BOMBS];
+        self.bombs_armed = 0;
+        self.spawns_pending = false;
+        self.seed_mix = [0; 32];
+        self.profiles_recorded_mask = 0;
+        self.ratings_settled_mask = 0;
+        self.season_recorded_mask = 0;
+        self.vrf_pending = 0;
+        self.last_vrf_request_timestamp = 0;
+        self.jackpot_position = NO_POSITION;
+        self.jackpot_winner = 0;
+        self.trophy_minted = false;
+        self.paused_at = 0;
+        self.extended_seconds = 0;
+        self.next_payout_index = 0;
+        self.rewards_paid_lamports = 0;
+        self.rewards_distributed = false;
+        self.last_checkpoint_timestamp = 0;
+        self.king_streak_holder = 0;
+        self.king_streak = 0;
+        self.golden_king_until = 0;
+        self.double_powerups_until = 0;
+        self.guard_positions = [0; MAX_GUARDS];
+        self.guards_placed = 0;
+        self.last_guard_step_timestamp = 0;
+        self.last_evasion_award_timestamp = 0;
+        self.king_carrier = 0;
+        self.poison = Bitboard::default();
+        self.last_poison_spread_timestamp = 0;
+        self.camp_positions = [0; MAX_PLAYERS];
+        self.camp_ticks = [0; MAX_PLAYERS];
+        self.last_camping_tick_timestamp = 0;
+        self.pushes_received = [0; MAX_PLAYERS];
+        self.push_window_start = [0; MAX_PLAYERS];
+        self.warmup_ends_at = 0;
+        self.halftime_at = 0;
+        self.xp_minted = false;
+        self.redeemed_credits = [None; MAX_PLAYERS];
+    }
+
+    /// Worst-case reward bill: every player holding a doubled king for the whole round.
+    pub fn max_prize_payout(&self) -> u64 {
+        let max_plausible_score = (self.game_duration_seconds as u64).checked_mul(2).unwrap();
+        (self.max_players as u64)
+            .checked_mul(max_plausible_score)
+            .unwrap()
+            .checked_mul(self.lamports_per_score)
+            .unwrap()
+    }
+
+    /// `score * lamports_per_score`, scaled down pro rata when the rewards of everyone at or
+    /// above `min_payout_score` would exceed `max_total_payout`; the flat participation refund
+    /// below the threshold.
+    pub fn reward_for(&self, score: u64) -> u64 {
+        if score < self.options.min_payout_score {
+            return self.options.participation_refund_lamports;
+        }
+        let reward = score.checked_mul(self.lamports_per_score).unwrap();
+        let total = self.players[..self.players_count as usize]
+            .iter()
+            .filter(|player| player.score >= self.options.min_payout_score)
+            .map(|player| player.score.checked_mul(self.lamports_per_score).unwrap())
+            .fold(0u64, |acc, reward| acc.checked_add(reward).unwrap());
+        if total <= self.max_total_payout {
+            return reward;
+        }
+        (reward as u128)
+            .checked_mul(self.max_total_payout as u128)
+            .unwrap()
+            .checked_div(total as u128)
+            .unwrap() as u64
+    }
+
+    /// Whether the round is still running; late VRF callbacks must leave the board alone otherwise.
+    pub fn is_live(&self, now: i64) -> bool {
+        self.is_active && self.paused_at == 0 && now < self.game_end_timestamp
+    }
+
+    pub fn in_warmup(&self, now: i64) -> bool {
+        now < self.warmup_ends_at
+    }
+
+    /// Fee for whoever takes seat `seat` (0-based):
+    /// `registration_fee_lamports * (1 + seat * fee_escalation_bps / 10_000)`.
+    pub fn registration_fee_for_seat(&self, seat: u8) -> u64 {
+        let bps = (MAX_BPS as u64)
+            .checked_add(
+                (seat as u64)
+                    .checked_mul(self.fee_escalation_bps as u64)
+                    .unwrap(),
+            )
+            .unwrap();
+        self.registration_fee_lamports
+            .checked_mul(bps)
+            .unwrap()
+            .checked_div(MAX_BPS as u64)
+            .unwrap()
+    }
+
+    pub fn halftime_due(&self, now: i64) -> bool {
+        self.halftime_at != 0 && now >= self.halftime_at
+    }
+
+    pub fn mark_delegated(&mut self, now: i64) {
+        self.delegation_state = DelegationState::Delegated;
+        self.delegated_at = now;
+    }
+
+    /// Pushes every running clock forward by `paused_seconds` so a pause costs no game time.
+    pub fn shift_clocks(&mut self, paused_seconds: i64) {
+        let paused_at = self.paused_at;
+        let shift = |t: &mut i64| {
+            if *t > 0 {
+                *t = t.checked_add(paused_seconds).unwrap();
+            }
+        };
+        shift(&mut self.game_end_timestamp);
+        shift(&mut self.last_king_move_timestamp);
+        shift(&mut self.king_occupied_since);
+        shift(&mut self.golden_king_until);
+        shift(&mut self.double_powerups_until);
+        shift(&mut self.last_evasion_award_timestamp);
+        shift(&mut self.last_poison_spread_timestamp);
+        shift(&mut self.last_camping_tick_timestamp);
+        shift(&mut self.warmup_ends_at);
+        shift(&mut self.halftime_at);
+        for player in self.players.iter_mut() {
+            for expiry in [
+                &mut player.shield_expiry,
+                &mut player.score_multiplier_expiry,
+                &mut player.powerup_expires_at,
+                &mut player.stunned_until,
+                &mut player.immune_until,
+            ] {
+                if *expiry > paused_at {
+                    shift(expiry);
+                }
+            }
+        }
+    }
+
+    /// Whether `king_move_interval_seconds` has passed since the king last moved.
+    pub fn king_move_due(&self, now: i64) -> bool {
+        now.checked_sub(self.last_king_move_timestamp).unwrap()
+            >= self.options.king_move_interval_seconds
+    }
+
+    /// True while any of `mask` has a VRF request in flight that has not timed out.
+    pub fn vrf_request_pending(&self, mask: u8, now: i64) -> bool {
+        self.vrf_pending & mask != 0
+            && now.checked_sub(self.last_vrf_request_timestamp).unwrap()
+                < VRF_PENDING_TIMEOUT_SECONDS
+    }
+
+    pub fn mark_vrf_requested(&mut self, mask: u8, now: i64) {
+        self.vrf_pending |= mask;
+        self.last_vrf_request_timestamp = now;
+    }
+
+    pub fn clear_vrf_pending(&mut self, mask: u8) {
+        self.vrf_pending &= !mask;
+    }
+}
+
+/// `Player` as stored by boards created before versioning.
+#[derive(AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct LegacyPlayerV0 {
+    pub player: Pubkey,
+    pub score: u64,
+    pub current_position: i16,
+    pub id: u8,
+    /// Push charges held; the original program had no other powerup.
+    pub powerup_score: u64,
+}
+
+/// `Board` as stored by boards created before versioning (read as version 0). Only
+/// `migrate_board` decodes it.
+#[derive(AnchorDeserialize, InitSpace)]
+pub struct LegacyBoardV0 {
+    pub game_id: u64,
+    #[max_len(6)]
+    pub players: Vec<LegacyPlayerV0>,
+    pub is_active: bool,
+    pub board: [u8; BOARD_SIZE],
+    pub board_side_len: u8,
+    pub max_players: u8,
+    pub registration_fee_lamports: u64,
+    pub lamports_per_score: u64,
+    pub players_count: u8,
+    pub king_current_position: u8,
+    pub last_move_timestamp: i64,
+    pub game_end_timestamp: i64,
+
+    pub powerup_current_position: u8,
+    pub bomb_current_position: u8,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct MapTemplate {
+    pub creator: Pubkey,
+    #[max_len(32)]
+    pub name: String,
+    pub board_side_len: u8,
+    pub king_start: u8,
+    #[max_len(6)]
+    pub spawn_points: Vec<u8>,
+    #[max_len(32)]
+    pub walls: Vec<u8>,
+}
+
+/// Staked operator allowed to crank `update_player_score`.
+#[account]
+#[derive(InitSpace)]
+pub struct Relayer {
+    pub authority: Pubkey,
+    pub stake_lamports: u64,
+    pub slashed_lamports: u64,
+    pub registered_at: i64,
+    /// Set by `unregister_relayer`; nonzero relayers can no longer crank.
+    pub unbonding_at: i64,
+}
+
+/// Authority `register_partner` allowed to create boards through `start_partner_game_session`.
+#[account]
+#[derive(InitSpace)]
+pub struct Partner {
+    pub authority: Pubkey,
+    pub games_created: u64,
+    pub registered_at: i64,
+}
+
+/// Lifetime stats for one wallet, accumulated by `update_player_profiles` after each game.
+#[account]
+#[derive(InitSpace)]
+pub struct PlayerProfile {
+    pub authority: Pubkey,
+    pub games_played: u32,
+    pub wins: u32,
+    pub total_score: u64,
+    pub best_score: u64,
+    pub created_at: i64,
+    pub rating: u32,
+    pub kings_captured: u32,
+    pub bombs_hit: u32,
+    /// Bit `i` is set once achievement `i` has been awarded.
+    pub achievements: u64,
+    /// Powerups left unused at game end, banked per `PowerupKind` for `redeem_powerup_credit`.
+    pub powerup_credits: [u8; POWERUP_KIND_COUNT],
+    /// Shown instead of the wallet in registration events; empty until set.
+    #[max_len(24)]
+    pub display_name: String,
+    /// Off-chain JSON with the player's avatar.
+    #[max_len(200)]
+    pub metadata_uri: String,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum AchievementKind {
+    KingCaptures,
+    Wins,
+    BombsSurvived,
+    GamesPlayed,
+    TotalScore,
+}
+
+/// An unlockable milestone: earned once the profile stat named by `kind` reaches `threshold`.
+#[account]
+#[derive(InitSpace)]
+pub struct Achievement {
+    pub id: u8,
+    pub kind: AchievementKind,
+    pub threshold: u64,
+    #[max_len(32)]
+    pub name: String,
+}
+
+impl Achievement {
+    pub fn is_earned(&self, profile: &PlayerProfile) -> bool {
+        let progress = match self.kind {
+            AchievementKind::KingCaptures => profile.kings_captured as u64,
+            AchievementKind::Wins => profile.wins as u64,
+            AchievementKind::BombsSurvived => profile.bombs_hit as u64,
+            AchievementKind::GamesPlayed => profile.games_played as u64,
+            AchievementKind::TotalScore => profile.total_score,
+        };
+        progress >= self.threshold
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum QuestKind {
+    KingCaptures,
+    GamesPlayed,
+    Wins,
+    Score,
+}
+
+/// Today's challenge in one quest slot; its lamport balance above rent funds the rewards.
+#[account]
+#[derive(InitSpace)]
+pub struct DailyQuest {
+    pub quest_id: u8,
+    /// Days since the Unix epoch this quest runs for; only games ending that day count.
+    pub day: i64,
+    pub kind: QuestKind,
+    pub target: u64,
+    pub reward_lamports: u64,
+}
+
+impl DailyQuest {
+    /// What one finished game contributes to this quest for the player at `player_index`.
+    pub fn progress_from(&self, board: &Board, player_index: usize) -> u64 {
+        let player = &board.players[player_index];
+        match self.kind {
+            QuestKind::KingCaptures => player.kings_captured as u64,
+            QuestKind::GamesPlayed => 1,
+            QuestKind::Wins => {
+                let top_score = board.players.iter().map(|p| p.score).max().unwrap_or(0);
+                (top_score > 0 && player.score == top_score) as u64
+            }
+            QuestKind::Score => player.score,
+        }
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct QuestProgress {
+    pub quest_id: u8,
+    pub player: Pubkey,
+    pub day: i64,
+    pub progress: u64,
+    pub claimed: bool,
+    /// Identifies the last round counted, so one game cannot be recorded twice.
+    pub last_game_id: u64,
+    pub last_game_end_timestamp: i64,
+}
+
+/// Program-wide admin settings, a singleton at `["config"]`.
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub admin: Pubkey,
+    /// Most lamports `withdraw_treasury` may move out of the treasury vault per Solana epoch.
+    pub withdraw_cap_per_epoch: u64,
+    pub withdraw_epoch: u64,
+    pub withdrawn_in_epoch: u64,
+    /// Minimum wait between `propose_param_change` and `execute_param_change`.
+    pub param_change_delay_seconds: i64,
+    /// ER validator `activate_and_delegate` delegates boards to; default lets any validator pick it up.
+    pub validator: Pubkey,
+    /// SPL mint `mint_xp` pays out of; its mint authority must be the `xp_authority` PDA.
+    pub xp_mint: Pubkey,
+    /// XP base units minted per point of final score; 0 turns XP off.
+    pub xp_per_score: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum GameParam {
+    RegistrationFeeLamports,
+    LamportsPerScore,
+    GameDurationSeconds,
+}
+
+/// A pending, timelocked change to one board parameter; closed when executed.
+#[account]
+#[derive(InitSpace)]
+pub struct ParamChange {
+    pub game_id: u64,
+    pub param: GameParam,
+    pub value: u64,
+    pub proposed_at: i64,
+    pub executable_at: i64,
+}
+
+/// Head-to-head stakes for one game, paid out to the winner by `distribute_rewards`.
+#[account]
+#[derive(InitSpace)]
+pub struct WagerEscrow {
+    pub game_id: u64,
+    /// Stake escrowed by each seat, indexed by player id - 1.
+    pub stakes: [u64; MAX_PLAYERS],
+    pub total_staked: u64,
+    pub settled: bool,
+}
+
+/// Marks a round refunded by `refund_stranded_board` while its board was stuck in the
+/// delegation program; `distribute_rewards` refuses to pay that round if the board comes back.
+#[account]
+#[derive(InitSpace)]
+pub struct StrandedRefund {
+    pub game_id: u64,
+    pub round: u32,
+    pub refunded_lamports: u64,
+    pub refunded_at: i64,
+}
+
+/// Spectator wagers on one game; its lamport balance above rent holds every stake.
+#[account]
+#[derive(InitSpace)]
+pub struct BettingPool {
+    pub game_id: u64,
+    pub round: u32,
+    pub total_staked: u64,
+    /// Stake on each player, indexed by player id - 1.
+    pub staked_on: [u64; MAX_PLAYERS],
+    pub settled: bool,
+    /// Bit `i` is set for every top scorer once settled.
+    pub winner_mask: u8,
+    pub winners_staked: u64,
+    /// Set when nobody backed a winner; every bet is then returned in full.
+    pub refund: bool,
+}
+
+/// A powerup credit already debited from its owner's profile and held for one round of one
+/// game. The profile stays on the base layer, so `reserve_powerup_credit` debits it there and
+/// `redeem_powerup_credit` on the ER only reads this account.
+#[account]
+#[derive(InitSpace)]
+pub struct CreditReservation {
+    pub player: Pubkey,
+    pub game_id: u64,
+    pub round: u32,
+    pub kind: PowerupKind,
+}
+
+/// One wallet watching one game; closed by `leave_spectator`.
+#[account]
+#[derive(InitSpace)]
+pub struct Spectator {
+    pub game_id: u64,
+    pub wallet: Pubkey,
+    pub joined_at: i64,
+    pub fee_lamports: u64,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct Bet {
+    pub game_id: u64,
+    pub round: u32,
+    pub bettor: Pubkey,
+    pub on_player: u8,
+    pub amount: u64,
+    pub claimed: bool,
+}
+
+/// Referral credits for one referrer; the unclaimed credits are held as this account's lamports.
+#[account]
+#[derive(InitSpace)]
+pub struct ReferralAccount {
+    pub referrer: Pubkey,
+    pub referrals: u32,
+    pub credits_lamports: u64,
+    pub claimed_lamports: u64,
+    pub created_at: i64,
+}
+
+/// Sponsor-funded reward pool for a free-play game; its lamport balance above rent pays rewards.
+#[account]
+#[derive(InitSpace)]
+pub struct SponsorVault {
+    pub game_id: u64,
+    pub sponsored_lamports: u64,
+    pub paid_lamports: u64,
+}
+
+/// Progressive pot shared by every jackpot-enabled game; its lamport balance above rent is the pot.
+#[account]
+#[derive(InitSpace)]
+pub struct JackpotVault {
+    pub total_contributed_lamports: u64,
+    pub total_paid_lamports: u64,
+    pub last_winner: Pubkey,
+    pub last_game_id: u64,
+}
+
+/// Program-owned store of treasury funds. Registration, rematch, and spectator fees, wager
+/// rake, and slashed relayer stake are paid into it, and rewards and wager refunds are paid
+/// out of it; anything else only leaves through the capped `withdraw_treasury`.
+#[account]
+#[derive(InitSpace)]
+pub struct TreasuryVault {
+    /// Program income plus `deposit_treasury` top-ups.
+    pub total_deposited_lamports: u64,
+    pub total_withdrawn_lamports: u64,
+    /// Rewards and refunds paid to players.
+    pub total_paid_lamports: u64,
+}
+
+/// Final result of one round, written by `record_game_summary` after the payout and never touched
+/// again, so it outlives `close_board`. Keyed by game id and end timestamp, one per rematch.
+#[account]
+#[derive(InitSpace)]
+pub struct GameSummary {
+    pub game_id: u64,
+    pub game_end_timestamp: i64,
+    /// Top scorer, the earliest to join on a tie.
+    pub winner: Pubkey,
+    pub players_count: u8,
+    pub players: [Pubkey; MAX_PLAYERS],
+    pub scores: [u64; MAX_PLAYERS],
+    /// Round clock from the end of warmup, including extensions.
+    pub duration_seconds: i64,
+    pub fees_collected_lamports: u64,
+    pub rewards_paid_lamports: u64,
+    pub recorded_at: i64,
+}
+
+/// A competitive window; its lamport balance above rent is the season prize pool.
+#[account]
+#[derive(InitSpace)]
+pub struct Season {
+    pub season_id: u32,
+    pub start_timestamp: i64,
+    pub end_timestamp: i64,
+    pub games_recorded: u32,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct SeasonEntry {
+    pub season_id: u32,
+    pub player: Pubkey,
+    pub points: u64,
+    pub games_played: u32,
+    pub prize_paid: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace, Debug)]
+pub enum ListingStatus {
+    Open,
+    Active,
+    Finished,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, InitSpace)]
+pub struct GameListing {
+    pub game_id: u64,
+    pub status: ListingStatus,
+    pub board_side_len: u8,
+    pub registration_fee_lamports: u64,
+    pub players_count: u8,
+    pub max_players: u8,
+    pub game_end_timestamp: i64,
+}
+
+impl GameListing {
+    pub fn from_board(board: &Board) -> Self {
+        let status = if board.is_active {
+            ListingStatus::Active
+        } else if board.game_end_timestamp > 0 {
+            ListingStatus::Finished
+        } else {
+            ListingStatus::Open
+        };
+        GameListing {
+            game_id: board.game_id,
+            status,
+            board_side_len: board.board_side_len,
+            registration_fee_lamports: board.registration_fee_lamports,
+            players_count: board.players_count,
+            max_players: board.max_players,
+            game_end_timestamp: board.game_end_timestamp,
+        }
+    }
+}
+
+/// Singleton index of live boards so clients can find joinable lobbies without scanning.
+#[account]
+#[derive(InitSpace)]
+pub struct GameRegistry {
+    #[max_len(32)]
+    pub listings: Vec<GameListing>,
+}
+
+impl GameRegistry {
+    /// Inserts or refreshes the board's listing; returns false if the registry is full of
+    /// unfinished games.
+    pub fn sync(&mut self, board: &Board) -> bool {
+        let listing = GameListing::from_board(board);
+        if let Some(existing) = self
+            .listings
+            .iter_mut()
+            .find(|l| l.game_id == listing.game_id)
+        {
+            *existing = listing;
+            return true;
+        }
+        if self.listings.len() >= MAX_LISTINGS {
+            match self
+                .listings
+                .iter()
+                .position(|l| l.status == ListingStatus::Finished)
+            {
+                Some(index) => {
+                    self.listings.remove(index);
+                }
+                None => return false,
+            }
+        }
+        self.listings.push(listing);
+        true
+    }
+
+    pub fn remove(&mut self, game_id: u64) {
+        self.listings.retain(|l| l.game_id != game_id);
+    }
 }
@@ -0,0 +1,179 @@
+use crate::constants::{
+    ASSOCIATED_TOKEN_PROGRAM_ID, SPL_MINT_LEN, SPL_TOKEN_PROGRAM_ID, TOKEN_METADATA_PROGRAM_ID,
+    TROPHY_SYMBOL,
+};
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+
+// Hand-encoded SPL Token, Associated Token and Metaplex Token Metadata instructions, so the
+// program does not need their crates just to mint one trophy per game and a little XP.
+const INITIALIZE_MINT2: u8 = 20;
+const MINT_TO: u8 = 7;
+const CREATE_ATA_IDEMPOTENT: u8 = 1;
+const CREATE_METADATA_ACCOUNT_V3: u8 = 33;
+const CREATE_MASTER_EDITION_V3: u8 = 17;
+
+pub fn metadata_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"metadata",
+            TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            mint.as_ref(),
+        ],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+pub fn master_edition_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"metadata",
+            TOKEN_METADATA_PROGRAM_ID.as_ref(),
+            mint.as_ref(),
+            b"edition",
+        ],
+        &TOKEN_METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+pub fn associated_token_address(wallet: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            wallet.as_ref(),
+            SPL_TOKEN_PROGRAM_ID.as_ref(),
+            mint.as_ref(),
+        ],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Zero-decimal mint whose mint and freeze authority is `authority`.
+pub fn initialize_mint_ix(mint: &Pubkey, authority: &Pubkey) -> Instruction {
+    let mut data = vec![INITIALIZE_MINT2, 0];
+    data.extend_from_slice(authority.as_ref());
+    data.push(1);
+    data.extend_from_slice(authority.as_ref());
+    Instruction {
+        program_id: SPL_TOKEN_PROGRAM_ID,
+        accounts: vec![AccountMeta::new(*mint, false)],
+        data,
+    }
+}
+
+pub fn create_associated_token_account_ix(
+    payer: &Pubkey,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: ASSOCIATED_TOKEN_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(associated_token_address(wallet, mint), false),
+            AccountMeta::new_readonly(*wallet, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(System::id(), false),
+            AccountMeta::new_readonly(SPL_TOKEN_PROGRAM_ID, false),
+        ],
+        data: vec![CREATE_ATA_IDEMPOTENT],
+    }
+}
+
+pub fn mint_one_ix(mint: &Pubkey, destination: &Pubkey, authority: &Pubkey) -> Instruction {
+    mint_to_ix(mint, destination, authority, 1)
+}
+
+pub fn mint_to_ix(
+    mint: &Pubkey,
+    destination: &Pubkey,
+    authority: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    let mut data = vec![MINT_TO];
+    data.extend_from_slice(&amount.to_le_bytes());
+    Instruction {
+        program_id: SPL_TOKEN_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*mint, false),
+            AccountMeta::new(*destination, false),
+            AccountMeta::new_readonly(*authority, true),
+        ],
+        data,
+    }
+}
+
+fn push_string(data: &mut Vec<u8>, value: &str) {
+    data.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    data.extend_from_slice(value.as_bytes());
+}
+
+/// Immutable metadata with `authority` as the sole, verified creator and no royalties.
+pub fn create_metadata_ix(
+    mint: &Pubkey,
+    authority: &Pubkey,
+    payer: &Pubkey,
+    name: &str,
+    uri: &str,
+) -> Instruction {
+    let mut data = vec![CREATE_METADATA_ACCOUNT_V3];
+    push_string(&mut data, name);
+    push_string(&mut data, TROPHY_SYMBOL);
+    push_string(&mut data, uri);
+    data.extend_from_slice(&0u16.to_le_bytes());
+    // creators: Some([{ authority, verified, share: 100 }])
+    data.push(1);
+    data.extend_from_slice(&1u32.to_le_bytes());
+    data.extend_from_slice(authority.as_ref());
+    data.push(1);
+    data.push(100);
+    // collection: None, uses: None, is_mutable: false, collection_details: None
+    data.extend_from_slice(&[0, 0, 0, 0]);
+    Instruction {
+        program_id: TOKEN_METADATA_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(metadata_address(mint), false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(System::id(), false),
+        ],
+        data,
+    }
+}
+
+/// Caps the supply at one so the trophy is a true 1/1.
+pub fn create_master_edition_ix(mint: &Pubkey, authority: &Pubkey, payer: &Pubkey) -> Instruction {
+    let mut data = vec![CREATE_MASTER_EDITION_V3, 1];
+    data.extend_from_slice(&0u64.to_le_bytes());
+    Instruction {
+        program_id: TOKEN_METADATA_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(master_edition_address(mint), false),
+            AccountMeta::new(*mint, false),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(metadata_address(mint), false),
+            AccountMeta::new_readonly(SPL_TOKEN_PROGRAM_ID, false),
+            AccountMeta::new_readonly(System::id(), false),
+        ],
+        data,
+    }
+}
+
+/// Mint authority of an initialised SPL mint, or `None` if it has none or is not a mint.
+pub fn mint_authority(mint: &AccountInfo) -> Option<Pubkey> {
+    if *mint.owner != SPL_TOKEN_PROGRAM_ID {
+        return None;
+    }
+    let data = mint.try_borrow_data().ok()?;
+    // COption<Pubkey> authority, supply, decimals, then `is_initialized`.
+    if data.len() != SPL_MINT_LEN || data[45] == 0 || data[0..4] != [1, 0, 0, 0] {
+        return None;
+    }
+    Some(Pubkey::new_from_array(data[4..36].try_into().unwrap()))
+}
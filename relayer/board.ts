@@ -37,22 +37,45 @@ export function toBoardStatusPayload(
     boardSideLen: Number(board.boardSideLen),
     maxPlayers: Number(board.maxPlayers),
     registrationFeeLamports: board.registrationFeeLamports.toString(),
+    // Mirrors `Board::registration_fee_for_seat` for the next open seat.
+    nextRegistrationFeeLamports: board.registrationFeeLamports
+      .muln(10_000 + Number(board.playersCount) * Number(board.feeEscalationBps ?? 0))
+      .divn(10_000)
+      .toString(),
     lamportsPerScore: board.lamportsPerScore.toString(),
     playersCount: Number(board.playersCount),
+    viewerCount: Number(board.viewerCount ?? 0),
     isActive: !!board.isActive,
     gameEndTimestamp,
+    delegationState: board.delegationState ? Object.keys(board.delegationState)[0] : "undelegated",
+    goldenKingUntil: Number(board.goldenKingUntil ?? 0),
+    doublePowerupsUntil: Number(board.doublePowerupsUntil ?? 0),
+    warmupEndsAt: Number(board.warmupEndsAt ?? 0),
+    halftimeAt: Number(board.halftimeAt ?? 0),
+    variant: board.variant ? Object.keys(board.variant)[0] : "classic",
+    kingPlayer: Number(board.kingPlayer ?? 0),
+    kingCarrier: Number(board.kingCarrier ?? 0),
     secondsRemaining: board.isActive ? Math.max(0, gameEndTimestamp - now) : 0,
     players: board.players.map((p: any) => ({
       id: Number(p.id),
       player: p.player.toBase58(),
       score: p.score.toString(),
       currentPosition: Number(p.currentPosition),
-      powerupScore: p.powerupScore.toString(),
+      powerup: p.powerup ? Object.keys(p.powerup)[0] : null,
+      movesMade: Number(p.movesMade),
+      kingsCaptured: Number(p.kingsCaptured),
+      bombsHit: Number(p.bombsHit),
+      powerupsUsed: Number(p.powerupsUsed),
     })),
     board: toBoardGrid(board.board, Number(board.boardSideLen)),
     boardLegend: {
       0: "empty",
       "1-max": "player id",
+      248: "poison",
+      249: "guard",
+      250: "jackpot",
+      251: "bomb kit",
+      252: "wall",
       253: "bomb",
       254: "powerup",
       255: "king",
@@ -1,6 +1,11 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program } from "@coral-xyz/anchor";
-import { sendAndConfirmTransaction, PublicKey, SystemProgram } from "@solana/web3.js";
+import {
+  sendAndConfirmTransaction,
+  ComputeBudgetProgram,
+  PublicKey,
+  SystemProgram,
+} from "@solana/web3.js";
 import express, { Request, Response } from "express";
 
 import { KingTiles } from "../target/types/king_tiles";
@@ -71,10 +76,32 @@ export async function bootstrapRelayer(): Promise<void> {
   const EPHEMERAL_ORACLE_QUEUE = new PublicKey(
     "5hBR571xnXppuCPveTrctfTU7tJLSN94nq7kv7FRK5Tc"
   );
+  const BASE_ORACLE_QUEUE = new PublicKey(
+    "Cuj97ggrhhidhbu39TijNVqE74xvKJ69gDervRUXAxGh"
+  );
+  const RELAYER_STAKE_LAMPORTS = Number(process.env.RELAYER_STAKE_LAMPORTS ?? 100_000_000);
+  const [relayerPDA] = PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode("relayer"), treasuryPubkey.toBuffer()],
+    program.programId
+  );
+  const [jackpotVaultPDA] = PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode("jackpot")],
+    program.programId
+  );
+  const TROPHY_METADATA_BASE_URI = process.env.TROPHY_METADATA_BASE_URI ?? "";
+  const TOKEN_PROGRAM_ID = new PublicKey("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
+  const ASSOCIATED_TOKEN_PROGRAM_ID = new PublicKey("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+  const TOKEN_METADATA_PROGRAM_ID = new PublicKey("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+  const SEASON_ID = process.env.SEASON_ID ? Number(process.env.SEASON_ID) : null;
+  // Players paid per distribute_rewards transaction; the program resumes from nextPayoutIndex.
+  const REWARD_PAGE_SIZE = Number(process.env.REWARD_PAGE_SIZE ?? 6);
   const KING_MOVE_INTERVAL_MS = 5_000;
   const POWERUP_SPAWN_INTERVAL_MS = 7_000;
   const BOMB_DROP_INTERVAL_MS = 10_000;
+  const GLOBAL_EVENT_INTERVAL_MS = 20_000;
+  const POISON_SPREAD_SECONDS = 5;
   const GAME_DURATION_MS = 60_000;
+  const CHECKPOINT_INTERVAL_MS = 15_000;
 
   type SessionState = {
     gameId: number;
@@ -88,6 +115,8 @@ export async function bootstrapRelayer(): Promise<void> {
     kingMoveInterval: NodeJS.Timeout | null;
     powerupSpawnInterval: NodeJS.Timeout | null;
     bombDropInterval: NodeJS.Timeout | null;
+    globalEventInterval: NodeJS.Timeout | null;
+    checkpointInterval: NodeJS.Timeout | null;
     scoreInterval: NodeJS.Timeout | null;
   };
   const sessions = new Map<number, SessionState>();
@@ -200,10 +229,33 @@ export async function bootstrapRelayer(): Promise<void> {
       await programER.methods
         .updatePlayerScore(new anchor.BN(gameId))
         .accountsPartial({
+          payer: treasuryPubkey,
+          relayer: relayerPDA,
           treasury: treasuryPubkey,
           boardAccount: boardPDA,
         })
         .rpc({ skipPreflight: true, commitment: "confirmed" });
+      const board = await programER.account.board.fetch(boardPDA);
+      if (board.kingNeedsRelocation) {
+        await requestKingMove(gameId, boardPDA);
+      }
+      const halftimeAt = Number(board.halftimeAt ?? 0);
+      if (halftimeAt > 0 && Date.now() / 1000 >= halftimeAt) {
+        await requestHalftime(gameId, boardPDA);
+      }
+      const poisoned = board.poison.words.some((word: any) => !word.isZero());
+      const nextSpread = Number(board.lastPoisonSpreadTimestamp) + POISON_SPREAD_SECONDS;
+      if (poisoned && Date.now() / 1000 >= nextSpread) {
+        await programER.methods
+          .spreadPoison(new anchor.BN(gameId))
+          .accountsPartial({
+            payer: treasuryPubkey,
+            relayer: relayerPDA,
+            treasury: treasuryPubkey,
+            boardAccount: boardPDA,
+          })
+          .rpc({ skipPreflight: true, commitment: "confirmed" });
+      }
     } catch (err: any) {
       console.error(
         `  [Score] updatePlayerScore failed for gameId ${gameId}:`,
@@ -221,7 +273,8 @@ export async function bootstrapRelayer(): Promise<void> {
   }
 
   function startScoreInterval(session: SessionState): void {
-    console.log(`  [Score] Starting score update interval every 1s...`);
+    // Scores settle on-chain on every board mutation; this only refreshes idle boards.
+    console.log(`  [Score] Starting score settle interval every 1s...`);
     updatePlayerScore(session.gameId, session.boardPDA);
     session.scoreInterval = setInterval(
       () => updatePlayerScore(session.gameId, session.boardPDA),
@@ -235,7 +288,8 @@ export async function bootstrapRelayer(): Promise<void> {
       const txHash = await programER.methods
         .requestRandomnessForKingMove(clientSeed, new anchor.BN(gameId))
         .accountsPartial({
-          treasurySigner: treasuryPubkey,
+          payer: treasuryPubkey,
+          treasury: treasuryPubkey,
           boardAccount: boardPDA,
           oracleQueue: EPHEMERAL_ORACLE_QUEUE,
         })
@@ -246,6 +300,58 @@ export async function bootstrapRelayer(): Promise<void> {
     }
   }
 
+  async function requestHalftime(gameId: number, boardPDA: PublicKey): Promise<void> {
+    try {
+      const clientSeed = Math.floor(Math.random() * 256);
+      const txHash = await programER.methods
+        .requestRandomnessForHalftime(clientSeed, new anchor.BN(gameId))
+        .accountsPartial({
+          payer: treasuryPubkey,
+          treasury: treasuryPubkey,
+          boardAccount: boardPDA,
+          oracleQueue: EPHEMERAL_ORACLE_QUEUE,
+        })
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      console.log(`  [Halftime] VRF request sent → seed=${clientSeed} txHash=${txHash}`);
+    } catch (err: any) {
+      console.error(`  [Halftime] VRF request failed for gameId ${gameId}:`, err.message ?? err);
+    }
+  }
+
+  async function requestKingStart(gameId: number, boardPDA: PublicKey): Promise<void> {
+    try {
+      const clientSeed = Math.floor(Math.random() * 256);
+      const txHash = await program.methods
+        .requestRandomnessForKingStart(clientSeed, new anchor.BN(gameId))
+        .accountsPartial({
+          treasurySigner: treasuryPubkey,
+          boardAccount: boardPDA,
+          oracleQueue: BASE_ORACLE_QUEUE,
+        })
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      console.log(`  [King] start VRF request sent → seed=${clientSeed} txHash=${txHash}`);
+    } catch (err: any) {
+      console.error(`  [King] start VRF request failed for gameId ${gameId}:`, err.message ?? err);
+    }
+  }
+
+  async function requestSpawnPlayers(gameId: number, boardPDA: PublicKey): Promise<void> {
+    try {
+      const clientSeed = Math.floor(Math.random() * 256);
+      const txHash = await programER.methods
+        .requestRandomnessForSpawnPlayers(clientSeed, new anchor.BN(gameId))
+        .accountsPartial({
+          treasurySigner: treasuryPubkey,
+          boardAccount: boardPDA,
+          oracleQueue: EPHEMERAL_ORACLE_QUEUE,
+        })
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      console.log(`  [Spawn] VRF request sent → seed=${clientSeed} txHash=${txHash}`);
+    } catch (err: any) {
+      console.error(`  [Spawn] VRF request failed for gameId ${gameId}:`, err.message ?? err);
+    }
+  }
+
   async function requestPowerupSpawn(gameId: number, boardPDA: PublicKey): Promise<void> {
     try {
       const clientSeed = Math.floor(Math.random() * 256);
@@ -263,6 +369,19 @@ export async function bootstrapRelayer(): Promise<void> {
     }
   }
 
+  // Commits the ER board to devnet without undelegating so a dead validator loses little.
+  async function checkpointBoard(gameId: number, boardPDA: PublicKey): Promise<void> {
+    try {
+      const txHash = await programER.methods
+        .checkpointBoard(new anchor.BN(gameId))
+        .accountsPartial({ treasury: treasuryPubkey, boardAccount: boardPDA })
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      console.log(`  [Checkpoint] Board committed for gameId=${gameId} txHash=${txHash}`);
+    } catch (err: any) {
+      console.error(`  [Checkpoint] Commit failed for gameId ${gameId}:`, err.message ?? err);
+    }
+  }
+
   async function requestBombDrop(gameId: number, boardPDA: PublicKey): Promise<void> {
     try {
       const clientSeed = Math.floor(Math.random() * 256);
@@ -280,6 +399,23 @@ export async function bootstrapRelayer(): Promise<void> {
     }
   }
 
+  async function requestGlobalEvent(gameId: number, boardPDA: PublicKey): Promise<void> {
+    try {
+      const clientSeed = Math.floor(Math.random() * 256);
+      const txHash = await programER.methods
+        .requestRandomnessForRandomEvent(clientSeed, new anchor.BN(gameId))
+        .accountsPartial({
+          treasurySigner: treasuryPubkey,
+          boardAccount: boardPDA,
+          oracleQueue: EPHEMERAL_ORACLE_QUEUE,
+        })
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      console.log(`  [Event] VRF request sent → seed=${clientSeed} txHash=${txHash}`);
+    } catch (err: any) {
+      console.error(`  [Event] VRF request failed for gameId ${gameId}:`, err.message ?? err);
+    }
+  }
+
   function stopAllIntervals(session: SessionState, clearGameTimer = false): void {
     if (session.kingMoveInterval) {
       clearInterval(session.kingMoveInterval);
@@ -296,6 +432,15 @@ export async function bootstrapRelayer(): Promise<void> {
       session.bombDropInterval = null;
       console.log(`  [Bomb] Interval stopped for gameId=${session.gameId}.`);
     }
+    if (session.globalEventInterval) {
+      clearInterval(session.globalEventInterval);
+      session.globalEventInterval = null;
+      console.log(`  [Event] Interval stopped for gameId=${session.gameId}.`);
+    }
+    if (session.checkpointInterval) {
+      clearInterval(session.checkpointInterval);
+      session.checkpointInterval = null;
+    }
     if (clearGameTimer && session.gameTimer) {
       clearTimeout(session.gameTimer);
       session.gameTimer = null;
@@ -314,6 +459,9 @@ export async function bootstrapRelayer(): Promise<void> {
     console.log(
       `  [Bomb]   Starting VRF interval every ${BOMB_DROP_INTERVAL_MS / 1000}s...`
     );
+    console.log(
+      `  [Event]  Starting VRF interval every ${GLOBAL_EVENT_INTERVAL_MS / 1000}s...`
+    );
 
     requestKingMove(session.gameId, session.boardPDA);
     session.kingMoveInterval = setInterval(
@@ -333,6 +481,17 @@ export async function bootstrapRelayer(): Promise<void> {
       BOMB_DROP_INTERVAL_MS
     );
 
+    // No immediate request: the first global event lands one interval into the round.
+    session.globalEventInterval = setInterval(
+      () => requestGlobalEvent(session.gameId, session.boardPDA),
+      GLOBAL_EVENT_INTERVAL_MS
+    );
+
+    session.checkpointInterval = setInterval(
+      () => checkpointBoard(session.gameId, session.boardPDA),
+      CHECKPOINT_INTERVAL_MS
+    );
+
     startScoreInterval(session);
     session.gameTimer = setTimeout(
       () => void endGameSession(session),
@@ -369,6 +528,8 @@ export async function bootstrapRelayer(): Promise<void> {
           kingMoveInterval: null,
           powerupSpawnInterval: null,
           bombDropInterval: null,
+          globalEventInterval: null,
+          checkpointInterval: null,
           scoreInterval: null,
         };
         sessions.set(gameId, session);
@@ -504,6 +665,312 @@ export async function bootstrapRelayer(): Promise<void> {
     }
   }
 
+  async function updatePlayerProfiles(
+    gameId: number,
+    boardPDA: PublicKey,
+    playerPubkeys: PublicKey[]
+  ): Promise<void> {
+    try {
+      const profilePDAs = playerPubkeys.map(
+        (pk) =>
+          PublicKey.findProgramAddressSync(
+            [anchor.utils.bytes.utf8.encode("profile"), pk.toBuffer()],
+            program.programId
+          )[0]
+      );
+      const infos = await solanaConnection.getMultipleAccountsInfo(profilePDAs, "confirmed");
+      const existing = profilePDAs.filter((_, i) => infos[i] !== null);
+      const txHash = await program.methods
+        .updatePlayerProfiles(new anchor.BN(gameId))
+        .accountsPartial({
+          treasury: treasuryPubkey,
+          boardAccount: boardPDA,
+        })
+        .remainingAccounts(
+          existing.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true }))
+        )
+        .rpc({ commitment: "confirmed" });
+      console.log(`  [Profiles] Updated ${existing.length} profiles â†’ txHash: ${txHash}`);
+      const ratingTxHash = await program.methods
+        .settleRatings(new anchor.BN(gameId))
+        .accountsPartial({
+          treasury: treasuryPubkey,
+          boardAccount: boardPDA,
+        })
+        .remainingAccounts(
+          existing.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true }))
+        )
+        .rpc({ commitment: "confirmed" });
+      console.log(`  [Profiles] Ratings settled â†’ txHash: ${ratingTxHash}`);
+    } catch (err: any) {
+      console.error(
+        `  [Profiles] profile/rating update failed for gameId ${gameId}:`,
+        err.message ?? err
+      );
+    }
+  }
+
+  // Awards every achievement whose condition a player's updated profile now meets, so
+  // clients can toast the resulting AchievementUnlockedEvent.
+  async function awardAchievements(playerPubkeys: PublicKey[]): Promise<void> {
+    try {
+      const achievements = await program.account.achievement.all();
+      if (achievements.length === 0) return;
+      for (const player of playerPubkeys) {
+        const [profilePDA] = PublicKey.findProgramAddressSync(
+          [anchor.utils.bytes.utf8.encode("profile"), player.toBuffer()],
+          program.programId
+        );
+        const profile = await program.account.playerProfile.fetchNullable(profilePDA);
+        if (!profile) continue;
+        const unlocked = BigInt(profile.achievements.toString());
+        for (const { account } of achievements) {
+          if ((unlocked >> BigInt(account.id)) & 1n) continue;
+          const kind = Object.keys(account.kind)[0];
+          const progress =
+            kind === "kingCaptures"
+              ? Number(profile.kingsCaptured)
+              : kind === "wins"
+                ? Number(profile.wins)
+                : kind === "bombsSurvived"
+                  ? Number(profile.bombsHit)
+                  : kind === "gamesPlayed"
+                    ? Number(profile.gamesPlayed)
+                    : Number(profile.totalScore);
+          if (progress < Number(account.threshold)) continue;
+          const txHash = await program.methods
+            .awardAchievement(account.id, player)
+            .accountsPartial({ profile: profilePDA })
+            .rpc({ commitment: "confirmed" });
+          console.log(
+            `  [Achievements] ${player.toBase58()} unlocked "${account.name}" â†’ txHash: ${txHash}`
+          );
+        }
+      }
+    } catch (err: any) {
+      console.error(`  [Achievements] award_achievement failed:`, err.message ?? err);
+    }
+  }
+
+  async function settleBets(gameId: number, boardPDA: PublicKey): Promise<void> {
+    const gameIdBuf = Buffer.alloc(8);
+    gameIdBuf.writeBigUInt64LE(BigInt(gameId));
+    try {
+      const { round } = await program.account.board.fetch(boardPDA);
+      const roundBuf = Buffer.alloc(4);
+      roundBuf.writeUInt32LE(round);
+      const [bettingPoolPDA] = PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode("betting_pool"), gameIdBuf, roundBuf],
+        program.programId
+      );
+      const pool = await program.account.bettingPool.fetchNullable(bettingPoolPDA);
+      if (!pool || pool.settled) return;
+      const txHash = await program.methods
+        .settleBets(new anchor.BN(gameId), round)
+        .accountsPartial({
+          treasury: treasuryPubkey,
+          boardAccount: boardPDA,
+          bettingPool: bettingPoolPDA,
+        })
+        .rpc({ commitment: "confirmed" });
+      console.log(`  [Bets] Settled betting pool for gameId ${gameId} â†’ txHash: ${txHash}`);
+    } catch (err: any) {
+      console.error(`  [Bets] settle_bets failed for gameId ${gameId}:`, err.message ?? err);
+    }
+  }
+
+  async function recordQuestProgress(
+    gameId: number,
+    boardPDA: PublicKey,
+    playerPubkeys: PublicKey[]
+  ): Promise<void> {
+    let quests: any[] = [];
+    try {
+      quests = await program.account.dailyQuest.all();
+    } catch (err: any) {
+      console.error(`  [Quests] Unable to load daily quests:`, err.message ?? err);
+      return;
+    }
+    for (const { account: quest } of quests) {
+      for (const player of playerPubkeys) {
+        try {
+          const txHash = await program.methods
+            .recordQuestProgress(quest.questId, new anchor.BN(gameId), player)
+            .accountsPartial({
+              treasury: treasuryPubkey,
+              boardAccount: boardPDA,
+              systemProgram: SystemProgram.programId,
+            })
+            .rpc({ commitment: "confirmed" });
+          console.log(
+            `  [Quests] Recorded quest ${quest.questId} progress for ${player.toBase58()} â†’ txHash: ${txHash}`
+          );
+        } catch (err: any) {
+          console.error(
+            `  [Quests] record_quest_progress failed for quest ${quest.questId} player ${player.toBase58()}:`,
+            err.message ?? err
+          );
+        }
+      }
+    }
+  }
+
+  async function recordSeasonResults(
+    gameId: number,
+    boardPDA: PublicKey,
+    playerPubkeys: PublicKey[]
+  ): Promise<void> {
+    if (SEASON_ID === null) return;
+    for (const player of playerPubkeys) {
+      try {
+        const txHash = await program.methods
+          .recordSeasonResult(SEASON_ID, new anchor.BN(gameId), player)
+          .accountsPartial({
+            treasury: treasuryPubkey,
+            boardAccount: boardPDA,
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc({ commitment: "confirmed" });
+        console.log(`  [Season] Recorded ${player.toBase58()} in season ${SEASON_ID} â†’ txHash: ${txHash}`);
+      } catch (err: any) {
+        console.error(
+          `  [Season] record_season_result failed for gameId ${gameId} player ${player.toBase58()}:`,
+          err.message ?? err
+        );
+      }
+    }
+  }
+
+  async function recordGameSummary(gameId: number, boardPDA: PublicKey): Promise<void> {
+    try {
+      const board = await program.account.board.fetch(boardPDA);
+      const gameIdBuf = Buffer.alloc(8);
+      gameIdBuf.writeBigUInt64LE(BigInt(gameId));
+      const gameEndBuf = Buffer.alloc(8);
+      gameEndBuf.writeBigInt64LE(BigInt(board.gameEndTimestamp.toString()));
+      const [gameSummary] = PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode("game_summary"), gameIdBuf, gameEndBuf],
+        program.programId
+      );
+      const txHash = await program.methods
+        .recordGameSummary(new anchor.BN(gameId))
+        .accountsPartial({
+          treasury: treasuryPubkey,
+          boardAccount: boardPDA,
+          gameSummary,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc({ commitment: "confirmed" });
+      console.log(`  [Summary] Recorded ${gameSummary.toBase58()} → txHash: ${txHash}`);
+    } catch (err: any) {
+      console.error(`  [Summary] record_game_summary failed for gameId ${gameId}:`, err.message ?? err);
+    }
+  }
+
+  async function mintTrophy(gameId: number, boardPDA: PublicKey): Promise<void> {
+    if (!TROPHY_METADATA_BASE_URI) return;
+    try {
+      const board = await program.account.board.fetch(boardPDA);
+      const players = board.players.slice(0, board.playersCount);
+      if (players.length === 0) return;
+      const winner = players.reduce((best: any, p: any) =>
+        Number(p.score) > Number(best.score) ? p : best
+      );
+      const winnerPubkey = new PublicKey(winner.player);
+      const gameIdBuf = Buffer.alloc(8);
+      gameIdBuf.writeBigUInt64LE(BigInt(gameId));
+      const gameEndBuf = Buffer.alloc(8);
+      gameEndBuf.writeBigInt64LE(BigInt(board.gameEndTimestamp.toString()));
+      const [trophyMint] = PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode("trophy"), gameIdBuf, gameEndBuf],
+        program.programId
+      );
+      const [winnerTokenAccount] = PublicKey.findProgramAddressSync(
+        [winnerPubkey.toBuffer(), TOKEN_PROGRAM_ID.toBuffer(), trophyMint.toBuffer()],
+        ASSOCIATED_TOKEN_PROGRAM_ID
+      );
+      const [metadata] = PublicKey.findProgramAddressSync(
+        [Buffer.from("metadata"), TOKEN_METADATA_PROGRAM_ID.toBuffer(), trophyMint.toBuffer()],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      const [masterEdition] = PublicKey.findProgramAddressSync(
+        [
+          Buffer.from("metadata"),
+          TOKEN_METADATA_PROGRAM_ID.toBuffer(),
+          trophyMint.toBuffer(),
+          Buffer.from("edition"),
+        ],
+        TOKEN_METADATA_PROGRAM_ID
+      );
+      const date = new Date(Number(board.gameEndTimestamp) * 1000).toISOString().slice(0, 10);
+      const uri = `${TROPHY_METADATA_BASE_URI}?game_id=${gameId}&score=${winner.score}&date=${date}`;
+      const txHash = await program.methods
+        .mintTrophy(new anchor.BN(gameId), uri)
+        .accountsPartial({
+          treasury: treasuryPubkey,
+          boardAccount: boardPDA,
+          winner: winnerPubkey,
+          trophyMint,
+          winnerTokenAccount,
+          metadata,
+          masterEdition,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+        .rpc({ commitment: "confirmed" });
+      console.log(`  [Trophy] Minted ${trophyMint.toBase58()} to ${winnerPubkey.toBase58()} â†’ txHash: ${txHash}`);
+    } catch (err: any) {
+      console.error(`  [Trophy] mint_trophy failed for gameId ${gameId}:`, err.message ?? err);
+    }
+  }
+
+  // Mints XP in proportion to final score once rewards are paid, if the config enables it.
+  async function mintXp(gameId: number, boardPDA: PublicKey): Promise<void> {
+    try {
+      const [configPDA] = PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode("config")],
+        program.programId
+      );
+      const config = await program.account.config.fetch(configPDA);
+      if (config.xpPerScore.isZero()) return;
+      const xpMint = new PublicKey(config.xpMint);
+      const board = await program.account.board.fetch(boardPDA);
+      const remainingAccounts = board.players
+        .slice(0, board.playersCount)
+        .flatMap((p: any) => {
+          const wallet = new PublicKey(p.player);
+          const [tokenAccount] = PublicKey.findProgramAddressSync(
+            [wallet.toBuffer(), TOKEN_PROGRAM_ID.toBuffer(), xpMint.toBuffer()],
+            ASSOCIATED_TOKEN_PROGRAM_ID
+          );
+          return [
+            { pubkey: wallet, isSigner: false, isWritable: false },
+            { pubkey: tokenAccount, isSigner: false, isWritable: true },
+          ];
+        });
+      const txHash = await program.methods
+        .mintXp(new anchor.BN(gameId))
+        .accountsPartial({
+          treasury: treasuryPubkey,
+          boardAccount: boardPDA,
+          config: configPDA,
+          xpMint,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(remainingAccounts)
+        .preInstructions([ComputeBudgetProgram.setComputeUnitLimit({ units: 400_000 })])
+        .rpc({ commitment: "confirmed" });
+      console.log(`  [XP] Minted XP for gameId=${gameId} → txHash: ${txHash}`);
+    } catch (err: any) {
+      console.error(`  [XP] mint_xp failed for gameId ${gameId}:`, err.message ?? err);
+    }
+  }
+
   async function distributeRewards(
     gameId: number,
     boardPDA: PublicKey,
@@ -618,31 +1085,72 @@ export async function bootstrapRelayer(): Promise<void> {
         console.log(`    player=${new PublicKey(p.player).toBase58()} score=${p.score}`);
       }
 
-      const rewardTx = await program.methods
-        .distributeRewards(new anchor.BN(gameId))
-        .accountsPartial({
-          treasury: treasuryPubkey,
-          boardAccount: boardPDA,
-          systemProgram: SystemProgram.programId,
-        })
-        .remainingAccounts(
-          playerPubkeys.map((pk) => ({
-            pubkey: pk,
-            isSigner: false,
-            isWritable: true,
-          }))
-        )
-        .transaction();
-
-      const rewardTxHash = await sendAndConfirmTransaction(
-        solanaConnection,
-        rewardTx,
-        [treasuryKeypair],
-        { skipPreflight: true, commitment: "confirmed" }
+      const gameIdBuf = Buffer.alloc(8);
+      gameIdBuf.writeBigUInt64LE(BigInt(gameId));
+      const [sponsorVaultPDA] = PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode("sponsor_vault"), gameIdBuf],
+        program.programId
+      );
+      const [wagerEscrowPDA] = PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode("wager"), gameIdBuf],
+        program.programId
       );
+      const roundBuf = Buffer.alloc(4);
+      roundBuf.writeUInt32LE(committedBoard.round);
+      const [strandedRefundPDA] = PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode("stranded_refund"), gameIdBuf, roundBuf],
+        program.programId
+      );
+      let rewardTxHash = "";
+      let pageStart = Number(committedBoard.nextPayoutIndex);
+      do {
+        // The program rejects a wallet listed twice in one page, so a wallet holding two
+        // seats is paid across separate pages.
+        const page: PublicKey[] = [];
+        while (page.length < REWARD_PAGE_SIZE && pageStart + page.length < playerPubkeys.length) {
+          const pubkey = playerPubkeys[pageStart + page.length];
+          if (page.some((seen) => seen.equals(pubkey))) break;
+          page.push(pubkey);
+        }
+        const rewardTx = await program.methods
+          .distributeRewards(new anchor.BN(gameId))
+          .accountsPartial({
+            treasury: treasuryPubkey,
+            boardAccount: boardPDA,
+            strandedRefund: strandedRefundPDA,
+            systemProgram: SystemProgram.programId,
+            sponsorVault: committedBoard.options.freePlay ? sponsorVaultPDA : null,
+            jackpotVault: committedBoard.jackpotWinner !== 0 ? jackpotVaultPDA : null,
+            wagerEscrow: Number(committedBoard.options.wagerLamports) > 0 ? wagerEscrowPDA : null,
+          })
+          .remainingAccounts(
+            page.map((pk) => ({
+              pubkey: pk,
+              isSigner: false,
+              isWritable: true,
+            }))
+          )
+          .transaction();
+
+        rewardTxHash = await sendAndConfirmTransaction(
+          solanaConnection,
+          rewardTx,
+          [treasuryKeypair],
+          { skipPreflight: true, commitment: "confirmed" }
+        );
+        pageStart += page.length;
+      } while (pageStart < playerPubkeys.length);
       const rewardTxSolscanUrl = `${SOLSCAN_DEVNET_TX_BASE}/${rewardTxHash}?cluster=devnet`;
       console.log(`  [Rewards] Devnet tx confirmed â†’ txHash: ${rewardTxHash}`);
       console.log(`  [Rewards] Solscan             â†’ ${rewardTxSolscanUrl}`);
+      await recordGameSummary(gameId, boardPDA);
+      await updatePlayerProfiles(gameId, boardPDA, playerPubkeys);
+      await awardAchievements(playerPubkeys);
+      await recordSeasonResults(gameId, boardPDA, playerPubkeys);
+      await recordQuestProgress(gameId, boardPDA, playerPubkeys);
+      await settleBets(gameId, boardPDA);
+      await mintTrophy(gameId, boardPDA);
+      await mintXp(gameId, boardPDA);
       const finalizedBoard = await program.account.board.fetch(boardPDA);
       const finalizedTxTrace: TxTrace = {
         ...txTrace,
@@ -752,6 +1260,9 @@ export async function bootstrapRelayer(): Promise<void> {
       let remainingMs = GAME_DURATION_MS;
       try {
         const committedBoard = (await program.account.board.fetch(boardPDA)) as any;
+        if (committedBoard?.spawnsPending) {
+          await requestSpawnPlayers(gameId, boardPDA);
+        }
         const endTs = Number(committedBoard?.gameEndTimestamp ?? 0);
         const nowSec = await getChainNowSec();
         remainingMs = Math.max(0, (endTs - nowSec) * 1000);
@@ -795,6 +1306,39 @@ export async function bootstrapRelayer(): Promise<void> {
     }
   }
 
+  // delegate_on_activation lobbies wait for us: start the round and delegate in one tx. The
+  // GameStartedEvent listener below then finds the board already delegated.
+  program.addEventListener("lobbyFilledEvent", async (event: any) => {
+    const gameId = Number(event.gameId);
+    console.log(`\n[Event] LobbyFilledEvent -> gameId: ${gameId}`);
+    const session = sessions.get(gameId);
+    if (!session) {
+      console.log(`  Skipping: gameId ${gameId} is not tracked by this relayer session map.`);
+      return;
+    }
+    try {
+      const [configPDA] = PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode("config")],
+        program.programId
+      );
+      const configInfo = await solanaConnection.getAccountInfo(configPDA, "confirmed");
+      const txHash = await program.methods
+        .activateAndDelegate(new anchor.BN(gameId))
+        .accountsPartial({
+          treasurySigner: treasuryPubkey,
+          boardAccount: session.boardPDA,
+          config: configInfo ? configPDA : null,
+          systemProgram: SystemProgram.programId,
+          pda: session.boardPDA,
+        })
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      session.txTrace = { ...session.txTrace, delegateBoardTxHash: txHash };
+      console.log(`  Board activated and delegated -> gameId=${gameId} txHash=${txHash}`);
+    } catch (err: any) {
+      console.error(`  [Activate] activate_and_delegate failed for gameId ${gameId}:`, err?.message ?? err);
+    }
+  });
+
   program.addEventListener("gameStartedEvent", async (event: any) => {
     const gameId = Number(event.gameId);
     console.log(`\n[Event] GameStartedEvent -> gameId: ${gameId}`);
@@ -827,6 +1371,10 @@ export async function bootstrapRelayer(): Promise<void> {
           "POST /start-session  body: { gameId, boardSideLen, maxPlayers, registrationFeeLamports, lamportsPerScore }",
         gameStatus: "GET /game-status?gameId=<number>",
         retryRewards: "POST /retry-rewards body: { gameId }",
+        rematch: "POST /rematch body: { gameId }",
+        resetBoard: "POST /reset-board body: { gameId, registrationFeeLamports, lamportsPerScore }",
+        refreshQuest:
+          "POST /refresh-quest body: { questId, kind, target, rewardLamports, budgetLamports }",
       },
     });
   });
@@ -861,11 +1409,26 @@ export async function bootstrapRelayer(): Promise<void> {
 
       activeGames.sort((a, b) => a.gameId - b.gameId);
       const lastCompletedByMode = buildLastCompletedByMode();
+      const [registryPDA] = PublicKey.findProgramAddressSync(
+        [anchor.utils.bytes.utf8.encode("registry")],
+        program.programId
+      );
+      const registry = await program.account.gameRegistry.fetchNullable(registryPDA);
+      const listings = (registry?.listings ?? []).map((l: any) => ({
+        gameId: Number(l.gameId),
+        status: Object.keys(l.status)[0],
+        boardSideLen: Number(l.boardSideLen),
+        registrationFeeLamports: l.registrationFeeLamports.toString(),
+        playersCount: Number(l.playersCount),
+        maxPlayers: Number(l.maxPlayers),
+        gameEndTimestamp: Number(l.gameEndTimestamp),
+      }));
 
       res.json({
         ok: true,
         activeGames,
         activeGameIds: activeGames.map((g) => g.gameId),
+        listings,
         lastCompletedGame,
         lastCompletedByMode,
       });
@@ -897,8 +1460,59 @@ export async function bootstrapRelayer(): Promise<void> {
       const gameId = Number(req.body?.gameId ?? 0);
       const boardSideLen = Number(req.body?.boardSideLen ?? 12);
       const maxPlayers = Number(req.body?.maxPlayers ?? 6);
-      const registrationFeeLamports = Number(req.body?.registrationFeeLamports ?? 1_000_000);
+      const freePlay = Boolean(req.body?.freePlay);
+      const registrationFeeLamports = Number(
+        req.body?.registrationFeeLamports ?? (freePlay ? 0 : 1_000_000)
+      );
       const lamportsPerScore = Number(req.body?.lamportsPerScore ?? 29_000);
+      const walls: number[] = Array.isArray(req.body?.walls) ? req.body.walls.map(Number) : [];
+      const mapTemplate: string | undefined = req.body?.mapTemplate;
+      const powerupTtlSeconds = Number(req.body?.powerupTtlSeconds ?? 0);
+      const bombPushStealPoints = Number(req.body?.bombPushStealPoints ?? 0);
+      const kingRelocation = req.body?.kingRelocation === "flee" ? { flee: {} } : { uniform: {} };
+      const randomKingStart = Boolean(req.body?.randomKingStart);
+      const spawnLayout =
+        req.body?.spawnLayout === "corners"
+          ? { corners: {} }
+          : req.body?.spawnLayout === "random"
+            ? { random: {} }
+            : { topRow: {} };
+      const relocateKingOnScore = Boolean(req.body?.relocateKingOnScore);
+      const kingMoveIntervalSeconds = Number(req.body?.kingMoveIntervalSeconds ?? 0);
+      const crankTipLamports = Number(req.body?.crankTipLamports ?? 0);
+      const crankTipBudgetLamports = Number(req.body?.crankTipBudgetLamports ?? 0);
+      const allowlistRoot: number[] = req.body?.allowlistRoot
+        ? Array.from(Buffer.from(String(req.body.allowlistRoot), "hex"))
+        : Array(32).fill(0);
+      const tokenGateMint = req.body?.tokenGateMint
+        ? new PublicKey(String(req.body.tokenGateMint))
+        : PublicKey.default;
+      const tokenGateMinAmount = Number(req.body?.tokenGateMinAmount ?? 0);
+      const referralFeeBps = Number(req.body?.referralFeeBps ?? 0);
+      const entryFeeUsdCents = Number(req.body?.entryFeeUsdCents ?? 0);
+      const jackpotFeeBps = Number(req.body?.jackpotFeeBps ?? 0);
+      const wagerLamports = Number(req.body?.wagerLamports ?? 0);
+      const wagerRakeBps = Number(req.body?.wagerRakeBps ?? 0);
+      const minPayoutScore = Number(req.body?.minPayoutScore ?? 0);
+      const participationRefundLamports = Number(req.body?.participationRefundLamports ?? 0);
+      const delegateOnActivation = req.body?.delegateOnActivation !== false;
+      const variants: Record<string, object> = {
+        kingPlayer: { kingPlayer: {} },
+        carryKing: { carryKing: {} },
+      };
+      const variant = variants[String(req.body?.variant)];
+      const campingDecayTicks = Number(req.body?.campingDecayTicks ?? 0);
+      const campingDecayPoints = Number(req.body?.campingDecayPoints ?? 1);
+      const bombPenaltyPoints = Number(req.body?.bombPenaltyPoints ?? 0);
+      const powerupPushDistance = Number(req.body?.powerupPushDistance ?? 0);
+      const pushCap = Number(req.body?.pushCap ?? 0);
+      const warmupSeconds = Number(req.body?.warmupSeconds ?? 0);
+      const halftimeFreezeSeconds = Number(req.body?.halftimeFreezeSeconds ?? 0);
+      const feeEscalationBps = Number(req.body?.feeEscalationBps ?? 0);
+      const spectatorFeeLamports = Number(req.body?.spectatorFeeLamports ?? 0);
+      const pythFeedId: number[] = req.body?.pythFeedId
+        ? Array.from(Buffer.from(String(req.body.pythFeedId).replace(/^0x/, ""), "hex"))
+        : Array(32).fill(0);
 
       const validMode =
         (boardSideLen === 8 && maxPlayers === 2) ||
@@ -916,18 +1530,98 @@ export async function bootstrapRelayer(): Promise<void> {
       if (
         !Number.isFinite(registrationFeeLamports) ||
         !Number.isFinite(lamportsPerScore) ||
-        registrationFeeLamports <= 0 ||
+        (freePlay ? registrationFeeLamports !== 0 : registrationFeeLamports <= 0) ||
         lamportsPerScore <= 0
       ) {
         res.status(400).json({
           ok: false,
-          error: "registrationFeeLamports and lamportsPerScore must be positive numbers.",
+          error: freePlay
+            ? "Free-play games take no registrationFeeLamports; lamportsPerScore must be positive."
+            : "registrationFeeLamports and lamportsPerScore must be positive numbers.",
         });
         return;
       }
 
       const [boardPDA] = getBoardPDA(treasuryPubkey, program.programId, gameId);
       console.log(`\n[/start-session] gameId=${gameId} boardPDA=${boardPDA.toBase58()}`);
+      // Set in the same transaction so nobody can register before the lobby is configured.
+      const setupIxs = [];
+      if (variant) {
+        setupIxs.push(
+          await program.methods
+            .setGameVariant(new anchor.BN(gameId), variant as any)
+            .accountsPartial({ treasury: treasuryPubkey, boardAccount: boardPDA })
+            .instruction()
+        );
+      }
+      if (campingDecayTicks > 0) {
+        setupIxs.push(
+          await program.methods
+            .setCampingDecay(
+              new anchor.BN(gameId),
+              campingDecayTicks,
+              new anchor.BN(campingDecayPoints)
+            )
+            .accountsPartial({ treasury: treasuryPubkey, boardAccount: boardPDA })
+            .instruction()
+        );
+      }
+      if (bombPenaltyPoints > 0) {
+        setupIxs.push(
+          await program.methods
+            .setBombPenalty(new anchor.BN(gameId), new anchor.BN(bombPenaltyPoints))
+            .accountsPartial({ treasury: treasuryPubkey, boardAccount: boardPDA })
+            .instruction()
+        );
+      }
+      if (powerupPushDistance > 0) {
+        setupIxs.push(
+          await program.methods
+            .setPowerupPushDistance(new anchor.BN(gameId), powerupPushDistance)
+            .accountsPartial({ treasury: treasuryPubkey, boardAccount: boardPDA })
+            .instruction()
+        );
+      }
+      if (pushCap > 0) {
+        setupIxs.push(
+          await program.methods
+            .setPushCap(new anchor.BN(gameId), pushCap)
+            .accountsPartial({ treasury: treasuryPubkey, boardAccount: boardPDA })
+            .instruction()
+        );
+      }
+      if (warmupSeconds > 0) {
+        setupIxs.push(
+          await program.methods
+            .setWarmup(new anchor.BN(gameId), warmupSeconds)
+            .accountsPartial({ treasury: treasuryPubkey, boardAccount: boardPDA })
+            .instruction()
+        );
+      }
+      if (halftimeFreezeSeconds > 0) {
+        setupIxs.push(
+          await program.methods
+            .setHalftime(new anchor.BN(gameId), halftimeFreezeSeconds)
+            .accountsPartial({ treasury: treasuryPubkey, boardAccount: boardPDA })
+            .instruction()
+        );
+      }
+      if (feeEscalationBps > 0) {
+        setupIxs.push(
+          await program.methods
+            .setFeeEscalation(new anchor.BN(gameId), feeEscalationBps)
+            .accountsPartial({ treasury: treasuryPubkey, boardAccount: boardPDA })
+            .instruction()
+        );
+      }
+      if (spectatorFeeLamports > 0) {
+        setupIxs.push(
+          await program.methods
+            .setSpectatorFee(new anchor.BN(gameId), new anchor.BN(spectatorFeeLamports))
+            .accountsPartial({ treasury: treasuryPubkey, boardAccount: boardPDA })
+            .instruction()
+        );
+      }
 
       if (treasuryPubkey.toBase58() !== PROGRAM_TREASURY_PUBKEY) {
         res.status(400).json({
@@ -948,18 +1642,44 @@ export async function bootstrapRelayer(): Promise<void> {
       }
 
       const tx = await (program.methods as any)
-        .startGameSession(
-          new anchor.BN(gameId),
+        .startGameSession(new anchor.BN(gameId), {
           boardSideLen,
           maxPlayers,
-          new anchor.BN(registrationFeeLamports),
-          new anchor.BN(lamportsPerScore)
-        )
+          registrationFeeLamports: new anchor.BN(registrationFeeLamports),
+          lamportsPerScore: new anchor.BN(lamportsPerScore),
+          walls: Buffer.from(walls),
+          options: {
+            powerupTtlSeconds: new anchor.BN(powerupTtlSeconds),
+            bombPushStealPoints: new anchor.BN(bombPushStealPoints),
+            kingRelocation,
+            randomKingStart,
+            spawnLayout,
+            relocateKingOnScore,
+            kingMoveIntervalSeconds: new anchor.BN(kingMoveIntervalSeconds),
+            crankTipLamports: new anchor.BN(crankTipLamports),
+            crankTipBudgetLamports: new anchor.BN(crankTipBudgetLamports),
+            allowlistRoot,
+            tokenGateMint,
+            tokenGateMinAmount: new anchor.BN(tokenGateMinAmount),
+            referralFeeBps,
+            freePlay,
+            entryFeeUsdCents: new anchor.BN(entryFeeUsdCents),
+            pythFeedId,
+            jackpotFeeBps,
+            wagerLamports: new anchor.BN(wagerLamports),
+            wagerRakeBps,
+            minPayoutScore: new anchor.BN(minPayoutScore),
+            participationRefundLamports: new anchor.BN(participationRefundLamports),
+            delegateOnActivation,
+          },
+        })
         .accountsPartial({
           treasurySigner: treasuryPubkey,
           boardAccount: boardPDA,
           systemProgram: SystemProgram.programId,
+          mapTemplate: mapTemplate ? new PublicKey(mapTemplate) : null,
         })
+        .postInstructions(setupIxs)
         .transaction();
 
       const txHash = await sendAndConfirmTransaction(solanaConnection, tx, [treasuryKeypair], {
@@ -982,9 +1702,14 @@ export async function bootstrapRelayer(): Promise<void> {
         kingMoveInterval: null,
         powerupSpawnInterval: null,
         bombDropInterval: null,
+        globalEventInterval: null,
+        checkpointInterval: null,
         scoreInterval: null,
       });
       clearGameStatusCache(gameId);
+      if (randomKingStart) {
+        await requestKingStart(gameId, boardPDA);
+      }
 
       res.json({
         ok: true,
@@ -1238,6 +1963,7 @@ export async function bootstrapRelayer(): Promise<void> {
         .usePower(new anchor.BN(gameId), playerId, direction)
         .accountsPartial({
           treasury: treasuryPubkey,
+          authority: treasuryPubkey,
           boardAccount: boardPDA,
         })
         .rpc({ skipPreflight: true, commitment: "confirmed" });
@@ -1286,6 +2012,309 @@ export async function bootstrapRelayer(): Promise<void> {
       res.status(500).json({ ok: false, error: detail });
     }
   });
+  app.post("/rematch", async (req: Request, res: Response) => {
+    try {
+      const gameId = Number(req.body?.gameId);
+      if (!Number.isFinite(gameId)) {
+        res.status(400).json({ ok: false, error: "Invalid body. Expected gameId: number." });
+        return;
+      }
+
+      const [boardPDA] = getBoardPDA(treasuryPubkey, program.programId, gameId);
+      const txHash = await program.methods
+        .rematch(new anchor.BN(gameId), false)
+        .accountsPartial({
+          treasury: treasuryPubkey,
+          boardAccount: boardPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc({ commitment: "confirmed" });
+      clearGameStatusCache(gameId);
+      res.json({ ok: true, gameId, boardPDA: boardPDA.toBase58(), txHash });
+    } catch (error: any) {
+      const detail = error?.message ?? "Unknown error";
+      res.status(500).json({ ok: false, error: detail });
+    }
+  });
+  // Pausing freezes the on-chain round and the relayer cranks; resuming re-arms them
+  // for whatever time the board still has left.
+  app.post("/pause-game", async (req: Request, res: Response) => {
+    try {
+      const gameId = Number(req.body?.gameId);
+      const session = sessions.get(gameId);
+      if (!session) {
+        res.status(404).json({ ok: false, error: `Game ${gameId} is not tracked by this relayer.` });
+        return;
+      }
+      const txHash = await programER.methods
+        .pauseGame(new anchor.BN(gameId))
+        .accountsPartial({ treasury: treasuryPubkey, boardAccount: session.boardPDA })
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      stopAllIntervals(session, true);
+      clearGameStatusCache(gameId);
+      res.json({ ok: true, gameId, txHash });
+    } catch (error: any) {
+      res.status(500).json({ ok: false, error: error?.message ?? "Unknown error" });
+    }
+  });
+
+  app.post("/seed-poison", async (req: Request, res: Response) => {
+    try {
+      const gameId = Number(req.body?.gameId);
+      const cell = Number(req.body?.cell);
+      const session = sessions.get(gameId);
+      if (!session || !Number.isInteger(cell) || cell < 0 || cell >= session.boardSideLen ** 2) {
+        res.status(400).json({ ok: false, error: "Expected a tracked gameId and a cell on its board." });
+        return;
+      }
+      const txHash = await programER.methods
+        .seedPoison(new anchor.BN(gameId), cell)
+        .accountsPartial({ treasury: treasuryPubkey, boardAccount: session.boardPDA })
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      clearGameStatusCache(gameId);
+      res.json({ ok: true, gameId, cell, txHash });
+    } catch (error: any) {
+      res.status(500).json({ ok: false, error: error?.message ?? "Unknown error" });
+    }
+  });
+
+  app.post("/resume-game", async (req: Request, res: Response) => {
+    try {
+      const gameId = Number(req.body?.gameId);
+      const session = sessions.get(gameId);
+      if (!session) {
+        res.status(404).json({ ok: false, error: `Game ${gameId} is not tracked by this relayer.` });
+        return;
+      }
+      const txHash = await programER.methods
+        .resumeGame(new anchor.BN(gameId))
+        .accountsPartial({ treasury: treasuryPubkey, boardAccount: session.boardPDA })
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      const board = (await programER.account.board.fetch(session.boardPDA)) as any;
+      const remainingMs = Math.max(
+        0,
+        (Number(board.gameEndTimestamp) - (await getChainNowSec())) * 1000
+      );
+      startKingMoveInterval(session, remainingMs);
+      clearGameStatusCache(gameId);
+      res.json({ ok: true, gameId, txHash, gameEndTimestamp: Number(board.gameEndTimestamp) });
+    } catch (error: any) {
+      res.status(500).json({ ok: false, error: error?.message ?? "Unknown error" });
+    }
+  });
+  // Validator failover: undelegate from the current ER, wait for the board to land on devnet,
+  // delegate it to the backup, then resume the round there.
+  app.post("/redelegate", async (req: Request, res: Response) => {
+    try {
+      const gameId = Number(req.body?.gameId);
+      const session = sessions.get(gameId);
+      if (!session || !req.body?.backupValidator) {
+        res.status(400).json({
+          ok: false,
+          error: "Expected a tracked gameId and backupValidator: base58 pubkey.",
+        });
+        return;
+      }
+      const backupValidator = new PublicKey(String(req.body.backupValidator));
+      stopAllIntervals(session, true);
+      const undelegateTxHash = await programER.methods
+        .redelegateBoard(new anchor.BN(gameId), backupValidator)
+        .accountsPartial({ treasury: treasuryPubkey, boardAccount: session.boardPDA })
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      for (let i = 0; i < 30; i++) {
+        const info = await solanaConnection.getAccountInfo(session.boardPDA, "confirmed");
+        if (info?.owner.equals(program.programId)) break;
+        await sleep(1_000);
+      }
+      const delegateTxHash = await program.methods
+        .completeRedelegation(new anchor.BN(gameId))
+        .accountsPartial({
+          treasurySigner: treasuryPubkey,
+          boardAccount: session.boardPDA,
+          systemProgram: SystemProgram.programId,
+          pda: session.boardPDA,
+        })
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      const resumeTxHash = await programER.methods
+        .resumeGame(new anchor.BN(gameId))
+        .accountsPartial({ treasury: treasuryPubkey, boardAccount: session.boardPDA })
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      const board = (await programER.account.board.fetch(session.boardPDA)) as any;
+      const remainingMs = Math.max(
+        0,
+        (Number(board.gameEndTimestamp) - (await getChainNowSec())) * 1000
+      );
+      startKingMoveInterval(session, remainingMs);
+      clearGameStatusCache(gameId);
+      res.json({ ok: true, gameId, undelegateTxHash, delegateTxHash, resumeTxHash });
+    } catch (error: any) {
+      res.status(500).json({ ok: false, error: error?.message ?? "Unknown error" });
+    }
+  });
+
+  app.post("/extend-game", async (req: Request, res: Response) => {
+    try {
+      const gameId = Number(req.body?.gameId);
+      const extraSeconds = Number(req.body?.extraSeconds);
+      const session = sessions.get(gameId);
+      if (!session || !Number.isInteger(extraSeconds) || extraSeconds <= 0) {
+        res.status(400).json({
+          ok: false,
+          error: "Expected a tracked gameId and extraSeconds > 0.",
+        });
+        return;
+      }
+      const txHash = await programER.methods
+        .extendGame(new anchor.BN(gameId), new anchor.BN(extraSeconds))
+        .accountsPartial({ treasury: treasuryPubkey, boardAccount: session.boardPDA })
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      const board = (await programER.account.board.fetch(session.boardPDA)) as any;
+      const remainingMs = Math.max(
+        0,
+        (Number(board.gameEndTimestamp) - (await getChainNowSec())) * 1000
+      );
+      startKingMoveInterval(session, remainingMs);
+      clearGameStatusCache(gameId);
+      res.json({ ok: true, gameId, txHash, gameEndTimestamp: Number(board.gameEndTimestamp) });
+    } catch (error: any) {
+      res.status(500).json({ ok: false, error: error?.message ?? "Unknown error" });
+    }
+  });
+
+  app.post("/expand-lobby", async (req: Request, res: Response) => {
+    try {
+      const gameId = Number(req.body?.gameId);
+      const boardSideLen = Number(req.body?.boardSideLen);
+      const maxPlayers = Number(req.body?.maxPlayers);
+      const session = sessions.get(gameId);
+      const validMode =
+        (boardSideLen === 10 && maxPlayers === 4) || (boardSideLen === 12 && maxPlayers === 6);
+      if (!session || !validMode || maxPlayers <= session.maxPlayers) {
+        res.status(400).json({
+          ok: false,
+          error: "Expected a tracked gameId and a larger mode (10x10 with 4 or 12x12 with 6 players).",
+        });
+        return;
+      }
+      const txHash = await program.methods
+        .expandLobby(new anchor.BN(gameId), boardSideLen, maxPlayers)
+        .accountsPartial({ treasury: treasuryPubkey, boardAccount: session.boardPDA })
+        .rpc({ skipPreflight: true, commitment: "confirmed" });
+      session.boardSideLen = boardSideLen;
+      session.maxPlayers = maxPlayers;
+      clearGameStatusCache(gameId);
+      res.json({ ok: true, gameId, txHash, boardSideLen, maxPlayers });
+    } catch (error: any) {
+      res.status(500).json({ ok: false, error: error?.message ?? "Unknown error" });
+    }
+  });
+
+  // Daily crank target: call once per UTC day for each quest slot.
+  app.post("/refresh-quest", async (req: Request, res: Response) => {
+    try {
+      const questId = Number(req.body?.questId);
+      const kinds: Record<string, object> = {
+        kingCaptures: { kingCaptures: {} },
+        gamesPlayed: { gamesPlayed: {} },
+        wins: { wins: {} },
+        score: { score: {} },
+      };
+      const kind = kinds[String(req.body?.kind)];
+      const target = Number(req.body?.target);
+      const rewardLamports = Number(req.body?.rewardLamports ?? 0);
+      const budgetLamports = Number(req.body?.budgetLamports ?? 0);
+      if (!Number.isInteger(questId) || questId < 0 || questId > 255 || !kind || !(target > 0)) {
+        res.status(400).json({
+          ok: false,
+          error:
+            "Expected questId: 0-255, kind: kingCaptures | gamesPlayed | wins | score, target > 0.",
+        });
+        return;
+      }
+      const txHash = await program.methods
+        .refreshDailyQuest(
+          questId,
+          kind as any,
+          new anchor.BN(target),
+          new anchor.BN(rewardLamports),
+          new anchor.BN(budgetLamports)
+        )
+        .accountsPartial({
+          treasury: treasuryPubkey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc({ commitment: "confirmed" });
+      res.json({ ok: true, questId, txHash });
+    } catch (error: any) {
+      res.status(500).json({ ok: false, error: error?.message ?? "Unknown error" });
+    }
+  });
+
+  app.post("/reset-board", async (req: Request, res: Response) => {
+    try {
+      const gameId = Number(req.body?.gameId);
+      const registrationFeeLamports = Number(req.body?.registrationFeeLamports ?? 1_000_000);
+      const lamportsPerScore = Number(req.body?.lamportsPerScore ?? 29_000);
+      if (!Number.isFinite(gameId)) {
+        res.status(400).json({ ok: false, error: "Invalid body. Expected gameId: number." });
+        return;
+      }
+
+      const [boardPDA] = getBoardPDA(treasuryPubkey, program.programId, gameId);
+      const txHash = await program.methods
+        .resetBoard(
+          new anchor.BN(gameId),
+          new anchor.BN(registrationFeeLamports),
+          new anchor.BN(lamportsPerScore)
+        )
+        .accountsPartial({
+          treasury: treasuryPubkey,
+          boardAccount: boardPDA,
+        })
+        .rpc({ commitment: "confirmed" });
+      clearGameStatusCache(gameId);
+      res.json({ ok: true, gameId, boardPDA: boardPDA.toBase58(), txHash });
+    } catch (error: any) {
+      const detail = error?.message ?? "Unknown error";
+      res.status(500).json({ ok: false, error: detail });
+    }
+  });
+  async function ensureRelayerRegistered(): Promise<void> {
+    if (await solanaConnection.getAccountInfo(relayerPDA)) return;
+    try {
+      const txHash = await program.methods
+        .registerRelayer(new anchor.BN(RELAYER_STAKE_LAMPORTS))
+        .accountsPartial({
+          authority: treasuryPubkey,
+          relayer: relayerPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc({ commitment: "confirmed" });
+      console.log(`  [Relayer] Registered with stake=${RELAYER_STAKE_LAMPORTS} txHash=${txHash}`);
+    } catch (err: any) {
+      console.error(`  [Relayer] register_relayer failed:`, err.message ?? err);
+    }
+  }
+
+  async function ensureJackpotVault(): Promise<void> {
+    if (await solanaConnection.getAccountInfo(jackpotVaultPDA)) return;
+    try {
+      const txHash = await program.methods
+        .createJackpotVault()
+        .accountsPartial({
+          treasury: treasuryPubkey,
+          jackpotVault: jackpotVaultPDA,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc({ commitment: "confirmed" });
+      console.log(`  [Jackpot] Created jackpot vault txHash=${txHash}`);
+    } catch (err: any) {
+      console.error(`  [Jackpot] create_jackpot_vault failed:`, err.message ?? err);
+    }
+  }
+
+  await ensureRelayerRegistered();
+  await ensureJackpotVault();
   await recoverSessionsFromChain();
   startSessionWatchdog();
 
@@ -16,20 +16,34 @@ export type BoardStatusPayload = {
   boardSideLen: number;
   maxPlayers: number;
   registrationFeeLamports: string;
+  nextRegistrationFeeLamports: string;
   lamportsPerScore: string;
   playersCount: number;
+  viewerCount: number;
   isActive: boolean;
   gameEndTimestamp: number;
+  delegationState: string;
+  goldenKingUntil: number;
+  doublePowerupsUntil: number;
+  warmupEndsAt: number;
+  halftimeAt: number;
+  variant: string;
+  kingPlayer: number;
+  kingCarrier: number;
   secondsRemaining: number;
   players: Array<{
     id: number;
     player: string;
     score: string;
     currentPosition: number;
-    powerupScore: string;
+    powerup: string | null;
+    movesMade: number;
+    kingsCaptured: number;
+    bombsHit: number;
+    powerupsUsed: number;
   }>;
   board: number[][];
-  boardLegend: { 0: string; "1-max": string; 253: string; 254: string; 255: string };
+  boardLegend: { 0: string; "1-max": string; 248: string; 249: string; 250: string; 251: string; 252: string; 253: string; 254: string; 255: string };
 };
 
 export type CompletedGameSnapshot = BoardStatusPayload & {
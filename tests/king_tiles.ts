@@ -146,7 +146,7 @@ testSuite("King Tiles - Magic Block (Ephemeral Rollups)", () => {
         const playerKeypair = playerKeypairs[i];
         try {
           const tx = await program.methods
-            .registerPlayer(new anchor.BN(GAME_ID))
+            .registerPlayer(new anchor.BN(GAME_ID), Array(32).fill(0), [], null)
             .accountsPartial({
               payer: playerKeypair.publicKey,
               boardAccount: boardPDA,
@@ -178,6 +178,46 @@ testSuite("King Tiles - Magic Block (Ephemeral Rollups)", () => {
     });
   });
 
+  // ── 1b. use_power signer checks (base layer, lobby still open) ────────────
+  describe("1b. use_power signers", () => {
+    it("rejects a wallet that neither owns the seat nor is the treasury", async function () {
+      this.timeout(30_000);
+      const stranger = Keypair.generate();
+      try {
+        await program.methods
+          .usePower(new anchor.BN(GAME_ID), 1, { up: {} })
+          .accountsPartial({
+            treasury: treasuryPubkey,
+            authority: stranger.publicKey,
+            boardAccount: boardPDA,
+          })
+          .signers([stranger])
+          .rpc({ commitment: "confirmed" });
+        assert.fail("use_power signed by a stranger should fail");
+      } catch (err: unknown) {
+        assert.include(String(err), "NotPlayer");
+      }
+    });
+
+    it("accepts the seat owner's signature and only stops at the undelegated board", async function () {
+      this.timeout(30_000);
+      try {
+        await program.methods
+          .usePower(new anchor.BN(GAME_ID), 1, { up: {} })
+          .accountsPartial({
+            treasury: treasuryPubkey,
+            authority: playerKeypairs[0].publicKey,
+            boardAccount: boardPDA,
+          })
+          .signers([playerKeypairs[0]])
+          .rpc({ commitment: "confirmed" });
+        assert.fail("use_power on a lobby board should fail");
+      } catch (err: unknown) {
+        assert.include(String(err), "BoardNotDelegated");
+      }
+    });
+  });
+
   // // ── 2. Wait for relayer delegation, then do deterministic game test ───────
   // describe("2. Deterministic game test on ER", () => {
   //   it("waits for relayer to delegate board and activate game on ER", async function () {
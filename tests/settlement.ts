@@ -0,0 +1,651 @@
+import dotenv from "dotenv";
+import { resolve } from "path";
+
+dotenv.config({ path: resolve(process.cwd(), ".env") });
+
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { KingTiles } from "../target/types/king_tiles";
+import {
+  LAMPORTS_PER_SOL,
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+  sendAndConfirmTransaction,
+} from "@solana/web3.js";
+import bs58 from "bs58";
+import { assert } from "chai";
+
+// ── Constants mirrored from the program ──────────────────────────────────────
+const MIN_RELAYER_STAKE_LAMPORTS = 100_000_000;
+const REGISTRATION_FEE_LAMPORTS = 1_000_000;
+const WAGER_LAMPORTS = 1_000_000;
+const LAMPORTS_PER_SCORE = 29_000;
+const BET_LAMPORTS = 1_000_000;
+
+const sleep = (ms: number) => new Promise((res) => setTimeout(res, ms));
+
+// ─────────────────────────────────────────────────────────────────────────────
+
+// Every flow below runs on its own fresh board under a game id the relayer does not track,
+// so these tests only need the treasury and two funded player keys, not a running relayer.
+const anchorProvider = anchor.AnchorProvider.env();
+const isLocalnet =
+  anchorProvider.connection.rpcEndpoint.includes("localhost") ||
+  anchorProvider.connection.rpcEndpoint.includes("127.0.0.1");
+
+const testSuite =
+  isLocalnet || !process.env.TREASURY_SECRET_BASE58 ? describe.skip : describe;
+
+function loadKeypair(privateKeyBase58?: string): Keypair {
+  if (!privateKeyBase58) {
+    throw new Error(
+      "Missing private key in env. Set TREASURY_SECRET_BASE58 and PLAYER_*_PRIVATE_KEY for tests."
+    );
+  }
+  const privateKeyBytes = bs58.decode(privateKeyBase58);
+  return privateKeyBytes.length === 32
+    ? Keypair.fromSeed(privateKeyBytes)
+    : Keypair.fromSecretKey(privateKeyBytes);
+}
+
+function u64Bytes(value: number): Buffer {
+  const buf = Buffer.alloc(8);
+  buf.writeBigUInt64LE(BigInt(value));
+  return buf;
+}
+
+function i64Bytes(value: number): Buffer {
+  const buf = Buffer.alloc(8);
+  buf.writeBigInt64LE(BigInt(value));
+  return buf;
+}
+
+function u32Bytes(value: number): Buffer {
+  const buf = Buffer.alloc(4);
+  buf.writeUInt32LE(value);
+  return buf;
+}
+
+async function expectError(call: Promise<unknown>, code: string): Promise<void> {
+  try {
+    await call;
+  } catch (err: unknown) {
+    assert.include(String(err), code);
+    return;
+  }
+  assert.fail(`Expected the transaction to fail with ${code}`);
+}
+
+function gameOptions(overrides: Record<string, unknown> = {}) {
+  return {
+    powerupTtlSeconds: new anchor.BN(0),
+    bombPushStealPoints: new anchor.BN(0),
+    kingRelocation: { uniform: {} },
+    randomKingStart: false,
+    spawnLayout: { topRow: {} },
+    relocateKingOnScore: false,
+    kingMoveIntervalSeconds: new anchor.BN(0),
+    crankTipLamports: new anchor.BN(0),
+    crankTipBudgetLamports: new anchor.BN(0),
+    allowlistRoot: Array(32).fill(0),
+    tokenGateMint: PublicKey.default,
+    tokenGateMinAmount: new anchor.BN(0),
+    referralFeeBps: 0,
+    freePlay: false,
+    entryFeeUsdCents: new anchor.BN(0),
+    pythFeedId: Array(32).fill(0),
+    jackpotFeeBps: 0,
+    wagerLamports: new anchor.BN(0),
+    wagerRakeBps: 0,
+    minPayoutScore: new anchor.BN(0),
+    participationRefundLamports: new anchor.BN(0),
+    delegateOnActivation: false,
+    ...overrides,
+  };
+}
+
+testSuite("King Tiles - settlement and admin flows (base layer)", () => {
+  const provider = anchor.AnchorProvider.env();
+  const connection = provider.connection;
+  anchor.setProvider(provider);
+  const program = anchor.workspace.kingTiles as Program<KingTiles>;
+
+  const treasuryKeypair = loadKeypair(process.env.TREASURY_SECRET_BASE58);
+  const treasuryPubkey = treasuryKeypair.publicKey;
+  let players: Keypair[] = [];
+
+  // Fresh ids per run so reruns never collide with boards left behind by a failed run.
+  const baseGameId = 900_000_000 + (Math.floor(Date.now() / 1000) % 1_000_000) * 10;
+
+  const pda = (...seeds: (Buffer | Uint8Array)[]) =>
+    PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+  const boardPda = (gameId: number) =>
+    pda(Buffer.from("board"), treasuryPubkey.toBuffer(), u64Bytes(gameId));
+  const configPda = pda(Buffer.from("config"));
+
+  async function startSession(
+    gameId: number,
+    options: Record<string, unknown> = {}
+  ): Promise<PublicKey> {
+    const boardAccount = boardPda(gameId);
+    await program.methods
+      .startGameSession(new anchor.BN(gameId), {
+        boardSideLen: 8,
+        maxPlayers: 2,
+        registrationFeeLamports: new anchor.BN(REGISTRATION_FEE_LAMPORTS),
+        lamportsPerScore: new anchor.BN(LAMPORTS_PER_SCORE),
+        walls: Buffer.from([]),
+        options: gameOptions(options),
+      })
+      .accountsPartial({
+        treasurySigner: treasuryPubkey,
+        boardAccount,
+        systemProgram: SystemProgram.programId,
+        mapTemplate: null,
+      })
+      .signers([treasuryKeypair])
+      .rpc({ commitment: "confirmed" });
+    return boardAccount;
+  }
+
+  async function register(gameId: number, player: Keypair, wager = false): Promise<void> {
+    await program.methods
+      .registerPlayer(new anchor.BN(gameId), Array(32).fill(0), [], null)
+      .accountsPartial({
+        payer: player.publicKey,
+        boardAccount: boardPda(gameId),
+        treasury: treasuryPubkey,
+        systemProgram: SystemProgram.programId,
+        wagerEscrow: wager ? pda(Buffer.from("wager"), u64Bytes(gameId)) : null,
+      })
+      .signers([player])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function closeBoard(gameId: number): Promise<void> {
+    await program.methods
+      .closeBoard(new anchor.BN(gameId))
+      .accountsPartial({ boardAccount: boardPda(gameId), treasury: treasuryPubkey })
+      .signers([treasuryKeypair])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  before(async function () {
+    players = [process.env.PLAYER_ONE_PRIVATE_KEY, process.env.PLAYER_TWO_PRIVATE_KEY]
+      .filter(Boolean)
+      .map((k) => loadKeypair(k));
+    assert.lengthOf(players, 2, "Need PLAYER_ONE_PRIVATE_KEY and PLAYER_TWO_PRIVATE_KEY in .env");
+  });
+
+  // ── Timelocked parameter changes ──────────────────────────────────────────
+  describe("propose_param_change / execute_param_change", () => {
+    const gameId = baseGameId + 1;
+    let delaySeconds = 0;
+
+    before(async function () {
+      this.timeout(60_000);
+      const existing = await program.account.config.fetchNullable(configPda);
+      if (!existing) {
+        await program.methods
+          .initializeConfig(treasuryPubkey, new anchor.BN(0), new anchor.BN(5), PublicKey.default)
+          .accountsPartial({ treasury: treasuryPubkey, config: configPda })
+          .signers([treasuryKeypair])
+          .rpc({ commitment: "confirmed" });
+      }
+      const config = await program.account.config.fetch(configPda);
+      delaySeconds = Number(config.paramChangeDelaySeconds);
+      await startSession(gameId);
+    });
+
+    after(async function () {
+      this.timeout(30_000);
+      await closeBoard(gameId);
+    });
+
+    it("refuses to apply a proposal before the delay has passed", async function () {
+      this.timeout(30_000);
+      if (delaySeconds === 0) this.skip();
+      await program.methods
+        .proposeParamChange(
+          new anchor.BN(gameId),
+          { registrationFeeLamports: {} },
+          new anchor.BN(2 * REGISTRATION_FEE_LAMPORTS)
+        )
+        .accountsPartial({ treasury: treasuryPubkey, config: configPda })
+        .signers([treasuryKeypair])
+        .rpc({ commitment: "confirmed" });
+
+      await expectError(
+        program.methods
+          .executeParamChange(new anchor.BN(gameId))
+          .accountsPartial({ treasury: treasuryPubkey, boardAccount: boardPda(gameId) })
+          .rpc({ commitment: "confirmed" }),
+        "ParamChangeLocked"
+      );
+    });
+
+    it("applies the proposal once the delay has passed", async function () {
+      if (delaySeconds > 60) this.skip();
+      this.timeout((delaySeconds + 30) * 1000);
+      const paramChange = pda(Buffer.from("param_change"), u64Bytes(gameId));
+      if (!(await program.account.paramChange.fetchNullable(paramChange))) {
+        await program.methods
+          .proposeParamChange(
+            new anchor.BN(gameId),
+            { registrationFeeLamports: {} },
+            new anchor.BN(2 * REGISTRATION_FEE_LAMPORTS)
+          )
+          .accountsPartial({ treasury: treasuryPubkey, config: configPda })
+          .signers([treasuryKeypair])
+          .rpc({ commitment: "confirmed" });
+      }
+      await sleep((delaySeconds + 2) * 1000);
+
+      await program.methods
+        .executeParamChange(new anchor.BN(gameId))
+        .accountsPartial({ treasury: treasuryPubkey, boardAccount: boardPda(gameId) })
+        .rpc({ commitment: "confirmed" });
+
+      const board = await program.account.board.fetch(boardPda(gameId));
+      assert.equal(Number(board.registrationFeeLamports), 2 * REGISTRATION_FEE_LAMPORTS);
+      assert.isNull(await program.account.paramChange.fetchNullable(paramChange));
+    });
+  });
+
+  // ── Head-to-head wager cancellation ───────────────────────────────────────
+  describe("cancel_wager", () => {
+    const gameId = baseGameId + 2;
+
+    before(async function () {
+      this.timeout(60_000);
+      // With delegate_on_activation the full lobby waits for the relayer, which skips this id.
+      await startSession(gameId, {
+        wagerLamports: new anchor.BN(WAGER_LAMPORTS),
+        delegateOnActivation: true,
+      });
+      for (const player of players) await register(gameId, player, true);
+    });
+
+    const cancel = (accounts: PublicKey[]) =>
+      program.methods
+        .cancelWager(new anchor.BN(gameId))
+        .accountsPartial({
+          treasury: treasuryPubkey,
+          boardAccount: boardPda(gameId),
+          wagerEscrow: pda(Buffer.from("wager"), u64Bytes(gameId)),
+        })
+        .remainingAccounts(
+          accounts.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true }))
+        )
+        .signers([treasuryKeypair])
+        .rpc({ commitment: "confirmed" });
+
+    it("rejects a refund list missing a player", async function () {
+      this.timeout(30_000);
+      await expectError(cancel([players[0].publicKey]), "InvalidPayoutPage");
+    });
+
+    it("rejects a refund list out of join order", async function () {
+      this.timeout(30_000);
+      await expectError(
+        cancel([players[1].publicKey, players[0].publicKey]),
+        "InvalidPayoutAccount"
+      );
+    });
+
+    it("refunds every stake and fee and closes the board", async function () {
+      this.timeout(30_000);
+      const before = await Promise.all(players.map((p) => connection.getBalance(p.publicKey)));
+
+      await cancel(players.map((p) => p.publicKey));
+
+      for (let i = 0; i < players.length; i++) {
+        const after = await connection.getBalance(players[i].publicKey);
+        assert.equal(after - before[i], REGISTRATION_FEE_LAMPORTS + WAGER_LAMPORTS);
+      }
+      assert.isNull(await program.account.board.fetchNullable(boardPda(gameId)));
+      assert.isNull(
+        await program.account.wagerEscrow.fetchNullable(pda(Buffer.from("wager"), u64Bytes(gameId)))
+      );
+    });
+  });
+
+  // ── Relayer staking ───────────────────────────────────────────────────────
+  describe("register_relayer / slash_relayer / unregister_relayer", () => {
+    const relayerKeypair = Keypair.generate();
+    const relayerPda = () => pda(Buffer.from("relayer"), relayerKeypair.publicKey.toBuffer());
+
+    before(async function () {
+      this.timeout(30_000);
+      const fundTx = new Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: treasuryPubkey,
+          toPubkey: relayerKeypair.publicKey,
+          lamports: MIN_RELAYER_STAKE_LAMPORTS + 0.01 * LAMPORTS_PER_SOL,
+        })
+      );
+      await sendAndConfirmTransaction(connection, fundTx, [treasuryKeypair], {
+        commitment: "confirmed",
+      });
+    });
+
+    const registerRelayer = (stake: number) =>
+      program.methods
+        .registerRelayer(new anchor.BN(stake))
+        .accountsPartial({ authority: relayerKeypair.publicKey })
+        .signers([relayerKeypair])
+        .rpc({ commitment: "confirmed" });
+
+    const slash = (amount: number) =>
+      program.methods
+        .slashRelayer(new anchor.BN(amount))
+        .accountsPartial({ treasury: treasuryPubkey, relayer: relayerPda() })
+        .signers([treasuryKeypair])
+        .rpc({ commitment: "confirmed" });
+
+    it("rejects a stake below the minimum", async function () {
+      this.timeout(30_000);
+      await expectError(
+        registerRelayer(MIN_RELAYER_STAKE_LAMPORTS / 2),
+        "InsufficientRelayerStake"
+      );
+    });
+
+    it("registers a relayer with the minimum stake", async function () {
+      this.timeout(30_000);
+      await registerRelayer(MIN_RELAYER_STAKE_LAMPORTS);
+      const relayer = await program.account.relayer.fetch(relayerPda());
+      assert.equal(Number(relayer.stakeLamports), MIN_RELAYER_STAKE_LAMPORTS);
+      assert.equal(Number(relayer.unbondingAt), 0);
+    });
+
+    it("rejects a slash larger than the stake", async function () {
+      this.timeout(30_000);
+      await expectError(slash(MIN_RELAYER_STAKE_LAMPORTS + 1), "InvalidSlashAmount");
+    });
+
+    it("deducts a slash from the stake", async function () {
+      this.timeout(30_000);
+      await slash(1_000);
+      const relayer = await program.account.relayer.fetch(relayerPda());
+      assert.equal(Number(relayer.stakeLamports), MIN_RELAYER_STAKE_LAMPORTS - 1_000);
+      assert.equal(Number(relayer.slashedLamports), 1_000);
+    });
+
+    it("keeps the stake locked while unbonding", async function () {
+      this.timeout(30_000);
+      await program.methods
+        .unregisterRelayer()
+        .accountsPartial({ authority: relayerKeypair.publicKey })
+        .signers([relayerKeypair])
+        .rpc({ commitment: "confirmed" });
+      const relayer = await program.account.relayer.fetch(relayerPda());
+      assert.isAbove(Number(relayer.unbondingAt), 0);
+
+      await expectError(
+        program.methods
+          .withdrawRelayerStake()
+          .accountsPartial({ authority: relayerKeypair.publicKey })
+          .signers([relayerKeypair])
+          .rpc({ commitment: "confirmed" }),
+        "RelayerStakeLocked"
+      );
+    });
+  });
+
+  // ── Paged payout ──────────────────────────────────────────────────────────
+  describe("distribute_rewards pagination", () => {
+    const gameId = baseGameId + 3;
+    let boardAccount: PublicKey;
+
+    const distribute = (page: PublicKey[]) =>
+      program.methods
+        .distributeRewards(new anchor.BN(gameId))
+        .accountsPartial({
+          treasury: treasuryPubkey,
+          boardAccount,
+          strandedRefund: pda(Buffer.from("stranded_refund"), u64Bytes(gameId), u32Bytes(0)),
+          systemProgram: SystemProgram.programId,
+          sponsorVault: null,
+          jackpotVault: null,
+          wagerEscrow: null,
+        })
+        .remainingAccounts(
+          page.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true }))
+        )
+        .signers([treasuryKeypair])
+        .rpc({ commitment: "confirmed" });
+
+    before(async function () {
+      this.timeout(30_000);
+      boardAccount = await startSession(gameId);
+    });
+
+    after(async function () {
+      this.timeout(30_000);
+      await closeBoard(gameId);
+    });
+
+    it("refuses to pay out a lobby whose round never started", async function () {
+      this.timeout(30_000);
+      await expectError(distribute([]), "GameNotStarted");
+    });
+
+    it("starts the round on the base layer once the lobby fills", async function () {
+      this.timeout(60_000);
+      for (const player of players) await register(gameId, player);
+      const board = await program.account.board.fetch(boardAccount);
+      assert.isTrue(board.isActive);
+      assert.isAbove(Number(board.gameEndTimestamp), 0);
+    });
+
+    it("rejects a page that lists a wallet twice or runs past the last player", async function () {
+      const board = await program.account.board.fetch(boardAccount);
+      const waitMs = (Number(board.gameEndTimestamp) + 5) * 1000 - Date.now();
+      this.timeout(Math.max(waitMs, 0) + 60_000);
+      await sleep(Math.max(waitMs, 0));
+
+      await expectError(
+        distribute([players[0].publicKey, players[0].publicKey]),
+        "DuplicatePayoutAccount"
+      );
+      await expectError(
+        distribute([...players.map((p) => p.publicKey), Keypair.generate().publicKey]),
+        "InvalidPayoutPage"
+      );
+    });
+
+    it("pays one player per page and finishes after the last page", async function () {
+      const board = await program.account.board.fetch(boardAccount);
+      const waitMs = (Number(board.gameEndTimestamp) + 5) * 1000 - Date.now();
+      this.timeout(Math.max(waitMs, 0) + 60_000);
+      await sleep(Math.max(waitMs, 0));
+
+      await distribute([players[0].publicKey]);
+      let after = await program.account.board.fetch(boardAccount);
+      assert.equal(after.nextPayoutIndex, 1);
+      assert.isFalse(after.rewardsDistributed);
+
+      await distribute([players[1].publicKey]);
+      after = await program.account.board.fetch(boardAccount);
+      assert.equal(after.nextPayoutIndex, 2);
+      assert.isTrue(after.rewardsDistributed);
+    });
+  });
+
+  // ── Spectator betting across rounds of one game id ────────────────────────
+  describe("place_bet / settle_bets / claim_bet", () => {
+    const gameId = baseGameId + 5;
+    let boardAccount: PublicKey;
+
+    const poolPda = (round: number) =>
+      pda(Buffer.from("betting_pool"), u64Bytes(gameId), u32Bytes(round));
+    const betPda = (round: number, bettor: PublicKey) =>
+      pda(Buffer.from("bet"), u64Bytes(gameId), u32Bytes(round), bettor.toBuffer());
+
+    const placeBet = (bettor: Keypair, onPlayer: number) =>
+      program.methods
+        .placeBet(new anchor.BN(gameId), onPlayer, new anchor.BN(BET_LAMPORTS))
+        .accountsPartial({ bettor: bettor.publicKey, treasury: treasuryPubkey, boardAccount })
+        .signers([bettor])
+        .rpc({ commitment: "confirmed" });
+    const settle = (round: number) =>
+      program.methods
+        .settleBets(new anchor.BN(gameId), round)
+        .accountsPartial({ treasury: treasuryPubkey, boardAccount, bettingPool: poolPda(round) })
+        .rpc({ commitment: "confirmed" });
+    const claim = (bettor: Keypair, round: number) =>
+      program.methods
+        .claimBet(new anchor.BN(gameId), round)
+        .accountsPartial({
+          bettor: bettor.publicKey,
+          bettingPool: poolPda(round),
+          bet: betPda(round, bettor.publicKey),
+        })
+        .signers([bettor])
+        .rpc({ commitment: "confirmed" });
+    const resetBoard = () =>
+      program.methods
+        .resetBoard(
+          new anchor.BN(gameId),
+          new anchor.BN(REGISTRATION_FEE_LAMPORTS),
+          new anchor.BN(LAMPORTS_PER_SCORE)
+        )
+        .accountsPartial({ treasury: treasuryPubkey, boardAccount })
+        .signers([treasuryKeypair])
+        .rpc({ commitment: "confirmed" });
+
+    // Registers the first player, bets on them, then fills the lobby and waits out the round.
+    async function playRound(bettor: Keypair): Promise<void> {
+      await register(gameId, players[0]);
+      await placeBet(bettor, 1);
+      await register(gameId, players[1]);
+      const board = await program.account.board.fetch(boardAccount);
+      await sleep(Math.max((Number(board.gameEndTimestamp) + 3) * 1000 - Date.now(), 0));
+    }
+
+    before(async function () {
+      this.timeout(30_000);
+      boardAccount = await startSession(gameId);
+    });
+
+    after(async function () {
+      this.timeout(30_000);
+      await closeBoard(gameId);
+    });
+
+    it("settles and pays a round's pool from its final scores", async function () {
+      this.timeout(180_000);
+      await playRound(players[1]);
+
+      await settle(0);
+      const pool = await program.account.bettingPool.fetch(poolPda(0));
+      assert.isTrue(pool.settled);
+      assert.equal(pool.round, 0);
+      // Nobody scored, so both seats tie as top scorers and the lone bet takes the pool.
+      assert.equal(pool.winnerMask, 0b11);
+      assert.isFalse(pool.refund);
+
+      const before = await connection.getBalance(players[1].publicKey);
+      await claim(players[1], 0);
+      const after = await connection.getBalance(players[1].publicKey);
+      assert.isAbove(after - before, BET_LAMPORTS - 10_000);
+      await expectError(claim(players[1], 0), "BetAlreadyClaimed");
+    });
+
+    it("opens a fresh pool after a reset and refunds one the reset left unsettled", async function () {
+      this.timeout(180_000);
+      await resetBoard();
+      let board = await program.account.board.fetch(boardAccount);
+      assert.equal(board.round, 1);
+
+      await playRound(players[0]);
+      const pool = await program.account.bettingPool.fetch(poolPda(1));
+      assert.equal(pool.round, 1);
+      assert.isFalse(pool.settled);
+      assert.equal(Number(pool.totalStaked), BET_LAMPORTS);
+
+      await resetBoard();
+      board = await program.account.board.fetch(boardAccount);
+      assert.equal(board.round, 2);
+
+      await settle(1);
+      const stale = await program.account.bettingPool.fetch(poolPda(1));
+      assert.isTrue(stale.refund);
+      const before = await connection.getBalance(players[0].publicKey);
+      await claim(players[0], 1);
+      const after = await connection.getBalance(players[0].publicKey);
+      assert.isAbove(after - before, BET_LAMPORTS - 10_000);
+    });
+  });
+
+  // ── Game summary written after the payout ─────────────────────────────────
+  describe("record_game_summary", () => {
+    const gameId = baseGameId + 6;
+    let boardAccount: PublicKey;
+
+    const distribute = (page: PublicKey[]) =>
+      program.methods
+        .distributeRewards(new anchor.BN(gameId))
+        .accountsPartial({
+          treasury: treasuryPubkey,
+          boardAccount,
+          strandedRefund: pda(Buffer.from("stranded_refund"), u64Bytes(gameId), u32Bytes(0)),
+          systemProgram: SystemProgram.programId,
+          sponsorVault: null,
+          jackpotVault: null,
+          wagerEscrow: null,
+        })
+        .remainingAccounts(
+          page.map((pubkey) => ({ pubkey, isSigner: false, isWritable: true }))
+        )
+        .signers([treasuryKeypair])
+        .rpc({ commitment: "confirmed" });
+
+    const gameSummaryPda = (gameEndTimestamp: number) =>
+      pda(Buffer.from("game_summary"), u64Bytes(gameId), i64Bytes(gameEndTimestamp));
+
+    const recordSummary = (gameEndTimestamp: number) =>
+      program.methods
+        .recordGameSummary(new anchor.BN(gameId))
+        .accountsPartial({
+          treasury: treasuryPubkey,
+          boardAccount,
+          gameSummary: gameSummaryPda(gameEndTimestamp),
+        })
+        .signers([treasuryKeypair])
+        .rpc({ commitment: "confirmed" });
+
+    before(async function () {
+      this.timeout(60_000);
+      boardAccount = await startSession(gameId);
+      for (const player of players) await register(gameId, player);
+    });
+
+    after(async function () {
+      this.timeout(30_000);
+      await closeBoard(gameId);
+    });
+
+    it("only records the summary after the last payout page", async function () {
+      const board = await program.account.board.fetch(boardAccount);
+      const gameEndTimestamp = Number(board.gameEndTimestamp);
+      const waitMs = (gameEndTimestamp + 5) * 1000 - Date.now();
+      this.timeout(Math.max(waitMs, 0) + 60_000);
+      await sleep(Math.max(waitMs, 0));
+
+      await distribute([players[0].publicKey]);
+      await expectError(recordSummary(gameEndTimestamp), "RewardsPending");
+
+      await distribute([players[1].publicKey]);
+      await recordSummary(gameEndTimestamp);
+      const summary = await program.account.gameSummary.fetch(gameSummaryPda(gameEndTimestamp));
+      assert.equal(summary.playersCount, 2);
+      assert.isTrue(summary.players[0].equals(players[0].publicKey));
+      assert.isTrue(summary.players[1].equals(players[1].publicKey));
+      assert.equal(Number(summary.gameEndTimestamp), gameEndTimestamp);
+    });
+  });
+});